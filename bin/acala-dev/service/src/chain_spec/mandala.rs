@@ -343,6 +343,7 @@ fn testnet_genesis(
 		module_evm: Some(EVMConfig {
 			accounts: evm_genesis_accounts,
 			network_contract_index,
+			open_deployment: true,
 		}),
 		module_staking_pool: Some(StakingPoolConfig {
 			staking_pool_params: module_staking_pool::Params {
@@ -528,6 +529,7 @@ fn mandala_genesis(
 		module_evm: Some(EVMConfig {
 			accounts: evm_genesis_accounts,
 			network_contract_index,
+			open_deployment: true,
 		}),
 		module_staking_pool: Some(StakingPoolConfig {
 			staking_pool_params: module_staking_pool::Params {
@@ -201,8 +201,14 @@ where
 	let babe_config = babe_link.config().clone();
 	let shared_epoch_changes = babe_link.epoch_changes().clone();
 
+	let log_index = acala_rpc::LogIndex::new(client.clone());
+	task_manager
+		.spawn_handle()
+		.spawn("evm-log-index", log_index.clone().run());
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
+		let backend = backend.clone();
 		let keystore = keystore_container.sync_keystore();
 		let transaction_pool = transaction_pool.clone();
 		let select_chain = select_chain.clone();
@@ -210,6 +216,7 @@ where
 		move |deny_unsafe, subscription_executor| -> acala_rpc::RpcExtension {
 			let deps = acala_rpc::FullDeps {
 				client: client.clone(),
+				backend: backend.clone(),
 				pool: transaction_pool.clone(),
 				select_chain: select_chain.clone(),
 				deny_unsafe,
@@ -225,6 +232,7 @@ where
 					subscription_executor,
 					finality_provider: finality_proof_provider.clone(),
 				},
+				log_index: log_index.clone(),
 			};
 
 			acala_rpc::create_full(deps)
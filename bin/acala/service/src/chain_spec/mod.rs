@@ -1,6 +1,8 @@
 //! Acala chain configurations.
 
-use acala_primitives::{AccountId, AccountPublic, Balance, Nonce, PREDEPLOY_ADDRESS_START};
+use acala_primitives::{
+	erc20_mirror_address, AccountId, AccountPublic, Balance, Nonce, TokenSymbol, PREDEPLOY_ADDRESS_START,
+};
 use module_evm::GenesisAccount;
 use sc_chain_spec::ChainSpecExtension;
 use serde::{Deserialize, Serialize};
@@ -8,7 +10,7 @@ use sp_consensus_babe::AuthorityId as BabeId;
 use sp_core::{sr25519, Bytes, Pair, Public, H160};
 use sp_finality_grandpa::AuthorityId as GrandpaId;
 use sp_runtime::traits::IdentifyAccount;
-use sp_std::{collections::btree_map::BTreeMap, str::FromStr};
+use sp_std::{collections::btree_map::BTreeMap, convert::TryFrom, str::FromStr};
 
 #[cfg(feature = "with-acala-runtime")]
 pub mod acala;
@@ -75,5 +77,22 @@ pub fn evm_genesis() -> (BTreeMap<H160, GenesisAccount<Balance, Nonce>>, u64) {
 		accounts.insert(addr, account);
 		network_contract_index += 1;
 	}
+	// the ERC20 mirrors of the native tokens are precompiles, make sure they have code
+	// so that contracts can call them. They are at `ERC20_MIRROR_ADDRESS_START` plus the
+	// token index rather than in the pre-deployed range from `PREDEPLOY_ADDRESS_START`,
+	// which is taken by the contracts above. `InstallErc20MirrorCode` installs the same
+	// code on the running chains.
+	for symbol in (0..=u8::MAX).filter_map(|index| TokenSymbol::try_from(index).ok()) {
+		let addr = erc20_mirror_address(symbol);
+		accounts.insert(
+			addr,
+			GenesisAccount {
+				nonce: 0u32,
+				balance: 0u128,
+				storage: BTreeMap::new(),
+				code: runtime_common::ERC20_MIRROR_CODE.to_vec(),
+			},
+		);
+	}
 	(accounts, network_contract_index)
 }
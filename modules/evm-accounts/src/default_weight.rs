@@ -1,4 +1,10 @@
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 2.0.0
+//! Weights for evm_accounts
+//!
+//! `claim_account` is generated by the Substrate benchmark CLI 2.0.0. The
+//! others aren't benchmarked yet and are estimated by the storage accesses,
+//! rounded up. `rebind_account` recovers a signature like `claim_account`
+//! and is estimated as it plus `unbind_account`. They are to be replaced by
+//! running the benchmarks in `runtime/mandala/src/benchmarking/evm_accounts.rs`.
 
 #![allow(unused_parens)]
 #![allow(unused_imports)]
@@ -12,4 +18,19 @@ impl crate::WeightInfo for () {
 			.saturating_add(DbWeight::get().reads(3 as Weight))
 			.saturating_add(DbWeight::get().writes(4 as Weight))
 	}
+	fn claim_default_account() -> Weight {
+		(120_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(4 as Weight))
+	}
+	fn unbind_account() -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn rebind_account() -> Weight {
+		(1_450_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(6 as Weight))
+			.saturating_add(DbWeight::get().writes(6 as Weight))
+	}
 }
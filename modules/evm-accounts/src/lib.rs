@@ -9,17 +9,21 @@
 
 use codec::Encode;
 use frame_support::{
-	decl_error, decl_event, decl_module, decl_storage, ensure,
-	traits::{Currency, Happened, OnKilledAccount, ReservableCurrency, StoredMap},
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::{DispatchError, DispatchResult},
+	ensure,
+	traits::{Currency, Get, Happened, OnKilledAccount, ReservableCurrency, StoredMap},
 	transactional,
 	weights::Weight,
 	StorageMap,
 };
 use frame_system::ensure_signed;
+use module_support::Erc20BalanceChecker;
 use orml_traits::account::MergeAccount;
 use primitives::evm::AddressMapping;
-use sp_core::{crypto::AccountId32, ecdsa, H160};
+use sp_core::{crypto::AccountId32, ecdsa, H160, U256};
 use sp_io::{crypto::secp256k1_ecdsa_recover, hashing::keccak_256};
+use sp_runtime::traits::{Saturating, Zero};
 use sp_std::marker::PhantomData;
 use sp_std::vec::Vec;
 
@@ -29,6 +33,9 @@ mod tests;
 
 pub trait WeightInfo {
 	fn claim_account() -> Weight;
+	fn claim_default_account() -> Weight;
+	fn unbind_account() -> Weight;
+	fn rebind_account() -> Weight;
 }
 
 pub type EcdsaSignature = ecdsa::Signature;
@@ -47,9 +54,19 @@ pub trait Config: frame_system::Config {
 	/// Merge free balance from source to dest.
 	type MergeAccount: MergeAccount<Self::AccountId>;
 
+	/// Check the ERC20 balances of the default EVM addresses, which have no
+	/// keys and can't be unbound while holding ERC20 tokens.
+	type Erc20BalanceChecker: Erc20BalanceChecker;
+
 	/// Handler to kill account in system.
 	type KillAccount: Happened<Self::AccountId>;
 
+	/// Chain ID of EVM, part of the EIP-712 domain of the claim signature.
+	type ChainId: Get<u64>;
+
+	/// The blocks before the mapping of an account can be unbound or rebound.
+	type MappingCooldown: Get<Self::BlockNumber>;
+
 	/// Weight information for the extrinsics in this module.
 	type WeightInfo: WeightInfo;
 }
@@ -62,6 +79,11 @@ decl_event!(
 		/// Mapping between Substrate accounts and EVM accounts
 		/// claim account. \[account_id, evm_address\]
 		ClaimAccount(AccountId, EvmAddress),
+		/// Unbind the EVM address of the account. \[account_id, evm_address\]
+		UnbindAccount(AccountId, EvmAddress),
+		/// Replace the EVM address of the account. \[account_id,
+		/// old_evm_address, new_evm_address\]
+		RebindAccount(AccountId, EvmAddress, EvmAddress),
 	}
 );
 
@@ -80,6 +102,12 @@ decl_error! {
 		NonZeroRefCount,
 		/// Account still has active reserved
 		StillHasActiveReserved,
+		/// AccountId has not mapped
+		AccountIdNotMapped,
+		/// The mapping is in cooldown
+		MappingInCooldown,
+		/// The default EVM address still holds ERC20 tokens
+		StillHasErc20Balance,
 	}
 }
 
@@ -87,6 +115,8 @@ decl_storage! {
 	trait Store for Module<T: Config> as EvmAccounts {
 		pub Accounts get(fn accounts): map hasher(twox_64_concat) EvmAddress => Option<T::AccountId>;
 		pub EvmAddresses get(fn evm_addresses): map hasher(twox_64_concat) T::AccountId => Option<EvmAddress>;
+		/// The block of the latest mapping of the account.
+		pub BoundAt get(fn bound_at): map hasher(twox_64_concat) T::AccountId => Option<T::BlockNumber>;
 	}
 }
 
@@ -97,6 +127,9 @@ decl_module! {
 
 		/// Claim account mapping between Substrate accounts and EVM accounts.
 		/// Ensure eth_address has not been mapped.
+		///
+		/// The signature is either the EIP-712 typed data signature of the
+		/// account, or the `personal_sign` signature of the hex encoded account.
 		#[weight = T::WeightInfo::claim_account()]
 		#[transactional]
 		pub fn claim_account(origin, eth_address: EvmAddress, eth_signature: EcdsaSignature) {
@@ -106,42 +139,175 @@ decl_module! {
 			ensure!(!EvmAddresses::<T>::contains_key(&who), Error::<T>::AccountIdHasMapped);
 			ensure!(!Accounts::<T>::contains_key(eth_address), Error::<T>::EthAddressHasMapped);
 
-			// recover evm address from signature
-			let address = Self::eth_recover(&eth_signature, &who.using_encoded(to_ascii_hex), &[][..]).ok_or(Error::<T>::BadSignature)?;
-			ensure!(eth_address == address, Error::<T>::InvalidSignature);
+			Self::verify_signature(&who, eth_address, &eth_signature)?;
+			Self::do_bind(&who, eth_address)?;
 
-			// check if the evm padded address already exists
-			let account_id = T::AddressMapping::to_account(&eth_address);
-			let mut nonce = <T as frame_system::Config>::Index::default();
-			if frame_system::Module::<T>::is_explicit(&account_id) {
-				// merge balance from `evm padded address` to `origin`
-				T::MergeAccount::merge_account(&account_id, &who)?;
+			Self::deposit_event(RawEvent::ClaimAccount(who, eth_address));
+		}
 
-				nonce = frame_system::Module::<T>::account_nonce(&account_id);
-				// finally kill the account
-				T::KillAccount::happened(&account_id);
-			}
-			//	make the origin nonce the max between origin amd evm padded address
-			let origin_nonce = frame_system::Module::<T>::account_nonce(&who);
-			if origin_nonce < nonce {
-				frame_system::Account::<T>::mutate(&who, |v| {
-					v.nonce = nonce;
-				});
-			}
+		/// Claim the default EVM address of the origin, which is derived from
+		/// the account id, for the accounts without an Ethereum key. Ensure
+		/// the origin has no EVM address.
+		///
+		/// The mapping can be replaced by `rebind_account` after the cooldown.
+		#[weight = T::WeightInfo::claim_default_account()]
+		#[transactional]
+		pub fn claim_default_account(origin) {
+			let who = ensure_signed(origin)?;
 
-			// update accounts
-			if let Some(evm_addr) = EvmAddresses::<T>::get(&who) {
-				Accounts::<T>::remove(&evm_addr);
-			}
-			Accounts::<T>::insert(eth_address, &who);
-			EvmAddresses::<T>::insert(&who, eth_address);
+			// ensure account_id has no evm address, including the padded `evm:` accounts
+			ensure!(
+				T::AddressMapping::to_evm_address(&who).is_none(),
+				Error::<T>::AccountIdHasMapped
+			);
+			let eth_address = Self::default_evm_address(&who);
+			ensure!(!Accounts::<T>::contains_key(eth_address), Error::<T>::EthAddressHasMapped);
+
+			Self::do_bind(&who, eth_address)?;
 
 			Self::deposit_event(RawEvent::ClaimAccount(who, eth_address));
 		}
+
+		/// Remove the mapping of the EVM address of the origin, the EVM address
+		/// is mapped to its padded `evm:` account again, which takes over the
+		/// nonce of the origin.
+		///
+		/// Ensure the cooldown of the mapping has passed, the origin has no
+		/// reserved balance, and the default EVM address of the origin holds no
+		/// ERC20 tokens.
+		///
+		/// The contracts maintained by the EVM address aren't migrated, they
+		/// can't be managed until the address is bound to an account again.
+		/// Transfer their maintainer with `module_evm` before unbinding.
+		#[weight = T::WeightInfo::unbind_account().saturating_add(T::Erc20BalanceChecker::has_erc20_balance_weight())]
+		#[transactional]
+		pub fn unbind_account(origin) {
+			let who = ensure_signed(origin)?;
+			let eth_address = Self::do_unbind(&who)?;
+
+			Self::deposit_event(RawEvent::UnbindAccount(who, eth_address));
+		}
+
+		/// Replace the EVM address of the origin with `eth_address`, proved by
+		/// the signature of the new key the same as `claim_account`. The
+		/// balance and the nonce of the padded `evm:` account of the new
+		/// address are merged to the origin.
+		///
+		/// Ensure the cooldown of the mapping has passed, the origin has no
+		/// reserved balance, and the default EVM address of the origin holds no
+		/// ERC20 tokens.
+		///
+		/// The contracts maintained by the old EVM address aren't migrated, the
+		/// same as `unbind_account`.
+		#[weight = T::WeightInfo::rebind_account().saturating_add(T::Erc20BalanceChecker::has_erc20_balance_weight())]
+		#[transactional]
+		pub fn rebind_account(origin, eth_address: EvmAddress, eth_signature: EcdsaSignature) {
+			let who = ensure_signed(origin)?;
+			ensure!(!Accounts::<T>::contains_key(eth_address), Error::<T>::EthAddressHasMapped);
+			Self::verify_signature(&who, eth_address, &eth_signature)?;
+
+			let old_address = Self::do_unbind(&who)?;
+			Self::do_bind(&who, eth_address)?;
+
+			Self::deposit_event(RawEvent::RebindAccount(who, old_address, eth_address));
+		}
 	}
 }
 
 impl<T: Config> Module<T> {
+	/// Ensure `eth_signature` is the signature of `who` signed by the key of
+	/// `eth_address`, either EIP-712 or `personal_sign`.
+	fn verify_signature(who: &T::AccountId, eth_address: EvmAddress, eth_signature: &EcdsaSignature) -> DispatchResult {
+		// recover evm address from the EIP-712 signature, or the `personal_sign` signature
+		let address = Self::eth_recover_eip712(eth_signature, who)
+			.filter(|address| *address == eth_address)
+			.or_else(|| Self::eth_recover(eth_signature, &who.using_encoded(to_ascii_hex), &[][..]))
+			.ok_or(Error::<T>::BadSignature)?;
+		ensure!(eth_address == address, Error::<T>::InvalidSignature);
+		Ok(())
+	}
+
+	/// Map `who` and `eth_address`, the padded `evm:` account of
+	/// `eth_address` is merged to `who`.
+	fn do_bind(who: &T::AccountId, eth_address: EvmAddress) -> DispatchResult {
+		// check if the evm padded address already exists
+		let account_id = T::AddressMapping::to_account(&eth_address);
+		let mut nonce = <T as frame_system::Config>::Index::default();
+		if frame_system::Module::<T>::is_explicit(&account_id) {
+			// merge balance from `evm padded address` to `origin`
+			T::MergeAccount::merge_account(&account_id, who)?;
+
+			nonce = frame_system::Module::<T>::account_nonce(&account_id);
+			// finally kill the account
+			T::KillAccount::happened(&account_id);
+		}
+		//	make the origin nonce the max between origin amd evm padded address
+		let origin_nonce = frame_system::Module::<T>::account_nonce(who);
+		if origin_nonce < nonce {
+			frame_system::Account::<T>::mutate(who, |v| {
+				v.nonce = nonce;
+			});
+		}
+
+		// update accounts
+		if let Some(evm_addr) = EvmAddresses::<T>::get(who) {
+			Accounts::<T>::remove(&evm_addr);
+		}
+		Accounts::<T>::insert(eth_address, who);
+		EvmAddresses::<T>::insert(who, eth_address);
+		BoundAt::<T>::insert(who, frame_system::Module::<T>::block_number());
+		Ok(())
+	}
+
+	/// Map `who` to its default EVM address without merging the padded
+	/// `evm:` account, used by the modules holding ERC20 tokens.
+	fn bind_default_address(who: &T::AccountId) -> EvmAddress {
+		let eth_address = Self::default_evm_address(who);
+		Accounts::<T>::insert(eth_address, who);
+		EvmAddresses::<T>::insert(who, eth_address);
+		BoundAt::<T>::insert(who, frame_system::Module::<T>::block_number());
+		Self::deposit_event(RawEvent::ClaimAccount(who.clone(), eth_address));
+		eth_address
+	}
+
+	/// Remove the mapping of `who`, returns the unmapped EVM address.
+	fn do_unbind(who: &T::AccountId) -> Result<EvmAddress, DispatchError> {
+		let eth_address = Self::evm_addresses(who).ok_or(Error::<T>::AccountIdNotMapped)?;
+		let bound_at = Self::bound_at(who).unwrap_or_else(Zero::zero);
+		ensure!(
+			frame_system::Module::<T>::block_number() >= bound_at.saturating_add(T::MappingCooldown::get()),
+			Error::<T>::MappingInCooldown
+		);
+		// the reserved balance is owned by the EVM address, e.g. the storage
+		// deposits of contracts, it can't be unreserved after unbinding
+		ensure!(
+			T::Currency::reserved_balance(who).is_zero(),
+			Error::<T>::StillHasActiveReserved
+		);
+		// nobody has the key of the default address, its ERC20 tokens would be
+		// lost after unbinding
+		ensure!(
+			eth_address != Self::default_evm_address(who) || !T::Erc20BalanceChecker::has_erc20_balance(eth_address),
+			Error::<T>::StillHasErc20Balance
+		);
+
+		Accounts::<T>::remove(eth_address);
+		EvmAddresses::<T>::remove(who);
+		BoundAt::<T>::remove(who);
+
+		// the EVM address is mapped to its padded `evm:` account again, make its
+		// nonce the max between it and `who`, so the transactions signed by the
+		// key of the EVM address can't be replayed
+		let account_id = T::AddressMapping::to_account(&eth_address);
+		let nonce = frame_system::Module::<T>::account_nonce(who);
+		if frame_system::Module::<T>::account_nonce(&account_id) < nonce {
+			frame_system::Account::<T>::mutate(&account_id, |v| {
+				v.nonce = nonce;
+			});
+		}
+		Ok(eth_address)
+	}
+
 	// Constructs the message that Ethereum RPC's `personal_sign` and `eth_sign`
 	// would sign.
 	pub fn ethereum_signable_message(what: &[u8], extra: &[u8]) -> Vec<u8> {
@@ -164,10 +330,47 @@ impl<T: Config> Module<T> {
 	// using the Ethereum RPC's `personal_sign` and `eth_sign`.
 	pub fn eth_recover(s: &EcdsaSignature, what: &[u8], extra: &[u8]) -> Option<EvmAddress> {
 		let msg = keccak_256(&Self::ethereum_signable_message(what, extra));
-		let mut res = EvmAddress::default();
-		res.0
-			.copy_from_slice(&keccak_256(&secp256k1_ecdsa_recover(&s.0, &msg).ok()?[..])[12..]);
-		Some(res)
+		recover(s, &msg)
+	}
+
+	// The EIP-712 domain separator, the chain id and the genesis hash stop the
+	// signatures from being replayed on other chains.
+	fn eip712_domain_separator() -> [u8; 32] {
+		let mut chain_id = [0u8; 32];
+		U256::from(T::ChainId::get()).to_big_endian(&mut chain_id);
+
+		let mut domain = keccak_256(b"EIP712Domain(string name,string version,uint256 chainId,bytes32 salt)").to_vec();
+		domain.extend_from_slice(&keccak_256(b"Acala EVM claim"));
+		domain.extend_from_slice(&keccak_256(b"1"));
+		domain.extend_from_slice(&chain_id);
+		domain.extend_from_slice(frame_system::Module::<T>::block_hash(T::BlockNumber::zero()).as_ref());
+		keccak_256(&domain)
+	}
+
+	// Constructs the EIP-712 typed data hash that Ethereum RPC's
+	// `eth_signTypedData` would sign, the message is
+	// `Transaction(bytes substrateAddress)`.
+	pub fn eip712_signable_message(who: &T::AccountId) -> [u8; 32] {
+		let mut message = keccak_256(b"Transaction(bytes substrateAddress)").to_vec();
+		message.extend_from_slice(&keccak_256(&who.encode()));
+
+		let mut v = b"\x19\x01".to_vec();
+		v.extend_from_slice(&Self::eip712_domain_separator());
+		v.extend_from_slice(&keccak_256(&message));
+		keccak_256(&v)
+	}
+
+	// Attempts to recover the Ethereum address from the EIP-712 typed data
+	// signature of the account.
+	pub fn eth_recover_eip712(s: &EcdsaSignature, who: &T::AccountId) -> Option<EvmAddress> {
+		recover(s, &Self::eip712_signable_message(who))
+	}
+
+	/// The default EVM address of `who`, the first 20 bytes of the hash of
+	/// the prefixed account id.
+	pub fn default_evm_address(who: &T::AccountId) -> EvmAddress {
+		let payload = (b"evm:default", who);
+		EvmAddress::from_slice(&payload.using_encoded(keccak_256)[0..20])
 	}
 
 	pub fn eth_public(secret: &secp256k1::SecretKey) -> secp256k1::PublicKey {
@@ -178,11 +381,10 @@ impl<T: Config> Module<T> {
 	}
 	pub fn eth_sign(secret: &secp256k1::SecretKey, what: &[u8], extra: &[u8]) -> EcdsaSignature {
 		let msg = keccak_256(&Self::ethereum_signable_message(&to_ascii_hex(what)[..], extra));
-		let (sig, recovery_id) = secp256k1::sign(&secp256k1::Message::parse(&msg), secret);
-		let mut r = [0u8; 65];
-		r[0..64].copy_from_slice(&sig.serialize()[..]);
-		r[64] = recovery_id.serialize();
-		EcdsaSignature::from_slice(&r)
+		sign(secret, &msg)
+	}
+	pub fn eth_sign_eip712(secret: &secp256k1::SecretKey, who: &T::AccountId) -> EcdsaSignature {
+		sign(secret, &Self::eip712_signable_message(who))
 	}
 
 	fn on_killed_account(who: &T::AccountId) {
@@ -190,6 +392,7 @@ impl<T: Config> Module<T> {
 		if let Some(evm_addr) = Self::evm_addresses(who) {
 			Accounts::<T>::remove(evm_addr);
 			EvmAddresses::<T>::remove(who);
+			BoundAt::<T>::remove(who);
 		}
 	}
 }
@@ -220,6 +423,10 @@ where
 			}
 		})
 	}
+
+	fn get_or_create_evm_address(account_id: &T::AccountId) -> H160 {
+		Self::to_evm_address(account_id).unwrap_or_else(|| Module::<T>::bind_default_address(account_id))
+	}
 }
 
 pub struct CallKillAccount<T>(PhantomData<T>);
@@ -229,6 +436,21 @@ impl<T: Config> OnKilledAccount<T::AccountId> for CallKillAccount<T> {
 	}
 }
 
+fn recover(s: &EcdsaSignature, msg: &[u8; 32]) -> Option<EvmAddress> {
+	let mut res = EvmAddress::default();
+	res.0
+		.copy_from_slice(&keccak_256(&secp256k1_ecdsa_recover(&s.0, msg).ok()?[..])[12..]);
+	Some(res)
+}
+
+fn sign(secret: &secp256k1::SecretKey, msg: &[u8; 32]) -> EcdsaSignature {
+	let (sig, recovery_id) = secp256k1::sign(&secp256k1::Message::parse(msg), secret);
+	let mut r = [0u8; 65];
+	r[0..64].copy_from_slice(&sig.serialize()[..]);
+	r[64] = recovery_id.serialize();
+	EcdsaSignature::from_slice(&r)
+}
+
 /// Converts the given binary data into ASCII-encoded hex. It will be twice the
 /// length.
 pub fn to_ascii_hex(data: &[u8]) -> Vec<u8> {
@@ -9,6 +9,7 @@ use primitives::{Amount, Balance, CurrencyId, TokenSymbol};
 use sp_core::H256;
 use sp_io::hashing::keccak_256;
 use sp_runtime::{testing::Header, traits::IdentityLookup, Perbill};
+use sp_std::cell::RefCell;
 
 pub type AccountId = AccountId32;
 pub type BlockNumber = u64;
@@ -77,6 +78,8 @@ pub type System = frame_system::Module<Runtime>;
 
 parameter_types! {
 	pub const ExistentialDeposit: u64 = 1;
+	pub const ChainId: u64 = 595;
+	pub const MappingCooldown: u64 = 10;
 }
 impl pallet_balances::Config for Runtime {
 	type Balance = Balance;
@@ -120,12 +123,30 @@ impl orml_currencies::Config for Runtime {
 pub type Currencies = orml_currencies::Module<Runtime>;
 pub type AdaptedBasicCurrency = orml_currencies::BasicCurrencyAdapter<Runtime, Balances, Amount, BlockNumber>;
 
+thread_local! {
+	pub static ERC20_HOLDERS: RefCell<Vec<EvmAddress>> = RefCell::new(vec![]);
+}
+
+pub struct MockErc20BalanceChecker;
+impl Erc20BalanceChecker for MockErc20BalanceChecker {
+	fn has_erc20_balance(address: H160) -> bool {
+		ERC20_HOLDERS.with(|v| v.borrow().contains(&address))
+	}
+
+	fn has_erc20_balance_weight() -> Weight {
+		0
+	}
+}
+
 impl Config for Runtime {
 	type Event = TestEvent;
 	type Currency = Balances;
 	type AddressMapping = EvmAddressMapping<Runtime>;
 	type MergeAccount = Currencies;
+	type Erc20BalanceChecker = MockErc20BalanceChecker;
 	type KillAccount = ();
+	type ChainId = ChainId;
+	type MappingCooldown = MappingCooldown;
 	type WeightInfo = ();
 }
 pub type EvmAccountsModule = Module<Runtime>;
@@ -4,7 +4,11 @@
 
 use super::*;
 use frame_support::{assert_noop, assert_ok};
-use mock::{alice, bob, EvmAccountsModule, ExtBuilder, Origin, Runtime, System, TestEvent, ALICE, BOB};
+use mock::{
+	alice, bob, bob_account_id, Balances, EvmAccountsModule, ExtBuilder, Origin, Runtime, System, TestEvent, ALICE,
+	BOB, ERC20_HOLDERS,
+};
+use sp_core::H256;
 
 #[test]
 fn claim_account_work() {
@@ -76,3 +80,245 @@ fn claim_account_should_not_work() {
 		);
 	});
 }
+
+#[test]
+fn claim_account_with_eip712_signature_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(EvmAccountsModule::claim_account(
+			Origin::signed(ALICE::get()),
+			EvmAccountsModule::eth_address(&alice()),
+			EvmAccountsModule::eth_sign_eip712(&alice(), &ALICE::get())
+		));
+		assert_eq!(
+			EvmAccountsModule::accounts(EvmAccountsModule::eth_address(&alice())),
+			Some(ALICE::get())
+		);
+
+		// signed for another account
+		assert_noop!(
+			EvmAccountsModule::claim_account(
+				Origin::signed(BOB::get()),
+				EvmAccountsModule::eth_address(&bob()),
+				EvmAccountsModule::eth_sign_eip712(&bob(), &ALICE::get())
+			),
+			Error::<Runtime>::InvalidSignature
+		);
+	});
+}
+
+#[test]
+fn eip712_signature_should_not_be_replayed_on_other_chains() {
+	ExtBuilder::default().build().execute_with(|| {
+		let signature = EvmAccountsModule::eth_sign_eip712(&alice(), &ALICE::get());
+
+		// the chain with another genesis hash
+		frame_system::BlockHash::<Runtime>::insert(0, H256::repeat_byte(1));
+		assert_noop!(
+			EvmAccountsModule::claim_account(
+				Origin::signed(ALICE::get()),
+				EvmAccountsModule::eth_address(&alice()),
+				signature
+			),
+			Error::<Runtime>::InvalidSignature
+		);
+	});
+}
+
+#[test]
+fn claim_default_account_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let default_address = EvmAccountsModule::default_evm_address(&ALICE::get());
+		assert_ne!(default_address, EvmAccountsModule::default_evm_address(&BOB::get()));
+
+		assert_ok!(EvmAccountsModule::claim_default_account(Origin::signed(ALICE::get())));
+		let event = TestEvent::evm_accounts(RawEvent::ClaimAccount(ALICE::get(), default_address));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert_eq!(EvmAccountsModule::accounts(default_address), Some(ALICE::get()));
+		assert_eq!(
+			EvmAddressMapping::<Runtime>::to_evm_address(&ALICE::get()),
+			Some(default_address)
+		);
+		assert_eq!(EvmAddressMapping::<Runtime>::to_account(&default_address), ALICE::get());
+
+		assert_noop!(
+			EvmAccountsModule::claim_default_account(Origin::signed(ALICE::get())),
+			Error::<Runtime>::AccountIdHasMapped
+		);
+		assert_noop!(
+			EvmAccountsModule::claim_account(
+				Origin::signed(ALICE::get()),
+				EvmAccountsModule::eth_address(&alice()),
+				EvmAccountsModule::eth_sign(&alice(), &ALICE::get().encode(), &[][..])
+			),
+			Error::<Runtime>::AccountIdHasMapped
+		);
+		// the padded `evm:` accounts have an evm address already
+		assert_noop!(
+			EvmAccountsModule::claim_default_account(Origin::signed(bob_account_id())),
+			Error::<Runtime>::AccountIdHasMapped
+		);
+	});
+}
+
+#[test]
+fn get_or_create_evm_address_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let default_address = EvmAccountsModule::default_evm_address(&ALICE::get());
+		assert_eq!(EvmAddressMapping::<Runtime>::to_evm_address(&ALICE::get()), None);
+		assert_eq!(
+			EvmAddressMapping::<Runtime>::get_or_create_evm_address(&ALICE::get()),
+			default_address
+		);
+		assert_eq!(EvmAccountsModule::accounts(default_address), Some(ALICE::get()));
+		assert_eq!(EvmAccountsModule::evm_addresses(ALICE::get()), Some(default_address));
+
+		// the padded `evm:` accounts keep their address
+		assert_eq!(
+			EvmAddressMapping::<Runtime>::get_or_create_evm_address(&bob_account_id()),
+			EvmAccountsModule::eth_address(&bob())
+		);
+		assert_eq!(EvmAccountsModule::evm_addresses(bob_account_id()), None);
+	});
+}
+
+#[test]
+fn unbind_account_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let eth_address = EvmAccountsModule::eth_address(&alice());
+		assert_noop!(
+			EvmAccountsModule::unbind_account(Origin::signed(ALICE::get())),
+			Error::<Runtime>::AccountIdNotMapped
+		);
+
+		assert_ok!(EvmAccountsModule::claim_account(
+			Origin::signed(ALICE::get()),
+			eth_address,
+			EvmAccountsModule::eth_sign(&alice(), &ALICE::get().encode(), &[][..])
+		));
+		assert_noop!(
+			EvmAccountsModule::unbind_account(Origin::signed(ALICE::get())),
+			Error::<Runtime>::MappingInCooldown
+		);
+
+		System::set_block_number(11);
+		let _ = Balances::deposit_creating(&ALICE::get(), 100);
+		assert_ok!(Balances::reserve(&ALICE::get(), 10));
+		assert_noop!(
+			EvmAccountsModule::unbind_account(Origin::signed(ALICE::get())),
+			Error::<Runtime>::StillHasActiveReserved
+		);
+
+		Balances::unreserve(&ALICE::get(), 10);
+		assert_ok!(EvmAccountsModule::unbind_account(Origin::signed(ALICE::get())));
+		let event = TestEvent::evm_accounts(RawEvent::UnbindAccount(ALICE::get(), eth_address));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert_eq!(EvmAccountsModule::accounts(eth_address), None);
+		assert_eq!(EvmAccountsModule::evm_addresses(ALICE::get()), None);
+		assert_ne!(EvmAddressMapping::<Runtime>::to_account(&eth_address), ALICE::get());
+		assert_eq!(Balances::free_balance(ALICE::get()), 100);
+	});
+}
+
+#[test]
+fn unbind_account_should_migrate_nonce() {
+	ExtBuilder::default().build().execute_with(|| {
+		let eth_address = EvmAccountsModule::eth_address(&alice());
+		assert_ok!(EvmAccountsModule::claim_account(
+			Origin::signed(ALICE::get()),
+			eth_address,
+			EvmAccountsModule::eth_sign(&alice(), &ALICE::get().encode(), &[][..])
+		));
+		System::inc_account_nonce(&ALICE::get());
+		System::inc_account_nonce(&ALICE::get());
+
+		System::set_block_number(11);
+		assert_ok!(EvmAccountsModule::unbind_account(Origin::signed(ALICE::get())));
+		let account_id = EvmAddressMapping::<Runtime>::to_account(&eth_address);
+		assert_eq!(System::account_nonce(&account_id), 2);
+
+		// the nonce is migrated back when the address is claimed again
+		assert_ok!(EvmAccountsModule::claim_account(
+			Origin::signed(BOB::get()),
+			eth_address,
+			EvmAccountsModule::eth_sign(&alice(), &BOB::get().encode(), &[][..])
+		));
+		assert_eq!(System::account_nonce(&BOB::get()), 2);
+	});
+}
+
+#[test]
+fn unbind_default_account_with_erc20_balance_should_not_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let default_address = EvmAccountsModule::default_evm_address(&ALICE::get());
+		assert_ok!(EvmAccountsModule::claim_default_account(Origin::signed(ALICE::get())));
+		System::set_block_number(11);
+
+		ERC20_HOLDERS.with(|v| v.borrow_mut().push(default_address));
+		assert_noop!(
+			EvmAccountsModule::unbind_account(Origin::signed(ALICE::get())),
+			Error::<Runtime>::StillHasErc20Balance
+		);
+		assert_noop!(
+			EvmAccountsModule::rebind_account(
+				Origin::signed(ALICE::get()),
+				EvmAccountsModule::eth_address(&alice()),
+				EvmAccountsModule::eth_sign_eip712(&alice(), &ALICE::get())
+			),
+			Error::<Runtime>::StillHasErc20Balance
+		);
+
+		ERC20_HOLDERS.with(|v| v.borrow_mut().clear());
+		assert_ok!(EvmAccountsModule::unbind_account(Origin::signed(ALICE::get())));
+	});
+}
+
+#[test]
+fn rebind_account_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let alice_address = EvmAccountsModule::eth_address(&alice());
+		let bob_address = EvmAccountsModule::eth_address(&bob());
+		assert_ok!(EvmAccountsModule::claim_account(
+			Origin::signed(ALICE::get()),
+			alice_address,
+			EvmAccountsModule::eth_sign(&alice(), &ALICE::get().encode(), &[][..])
+		));
+		assert_noop!(
+			EvmAccountsModule::rebind_account(
+				Origin::signed(ALICE::get()),
+				bob_address,
+				EvmAccountsModule::eth_sign(&bob(), &ALICE::get().encode(), &[][..])
+			),
+			Error::<Runtime>::MappingInCooldown
+		);
+
+		System::set_block_number(11);
+		assert_noop!(
+			EvmAccountsModule::rebind_account(
+				Origin::signed(ALICE::get()),
+				bob_address,
+				EvmAccountsModule::eth_sign(&alice(), &ALICE::get().encode(), &[][..])
+			),
+			Error::<Runtime>::InvalidSignature
+		);
+		assert_ok!(EvmAccountsModule::rebind_account(
+			Origin::signed(ALICE::get()),
+			bob_address,
+			EvmAccountsModule::eth_sign_eip712(&bob(), &ALICE::get())
+		));
+		let event = TestEvent::evm_accounts(RawEvent::RebindAccount(ALICE::get(), alice_address, bob_address));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert_eq!(EvmAccountsModule::accounts(alice_address), None);
+		assert_eq!(EvmAccountsModule::accounts(bob_address), Some(ALICE::get()));
+		assert_eq!(EvmAccountsModule::evm_addresses(ALICE::get()), Some(bob_address));
+
+		// the balance of the padded account of the new address is merged
+		assert_eq!(Balances::free_balance(bob_account_id()), 0);
+		assert_eq!(Balances::free_balance(ALICE::get()), 100000);
+
+		// the cooldown starts again
+		assert_noop!(
+			EvmAccountsModule::unbind_account(Origin::signed(ALICE::get())),
+			Error::<Runtime>::MappingInCooldown
+		);
+	});
+}
@@ -3,19 +3,40 @@
 use codec::{Decode, Encode};
 use ethereum_types::BigEndianHash;
 use frame_support::{
-	decl_error, decl_module,
+	debug, decl_error, decl_event, decl_module, decl_storage,
 	dispatch::{DispatchError, DispatchResult},
+	ensure,
+	traits::{
+		BalanceStatus, Currency, EnsureOrigin, ExistenceRequirement, Get, Imbalance, LockIdentifier, OnUnbalanced,
+		WithdrawReasons,
+	},
+	weights::{DispatchClass, Weight},
+	IterableStorageMap,
 };
 use hex_literal::hex;
 use module_evm::ExitReason;
+use orml_traits::{
+	account::MergeAccount, MultiCurrency, MultiCurrencyExtended, MultiLockableCurrency, MultiReservableCurrency,
+};
 use primitive_types::H256;
+use primitives::{evm::AddressMapping, CurrencyId};
 use sp_core::{H160, U256};
-use sp_runtime::{RuntimeDebug, SaturatedConversion};
-use support::{EVMBridge as EVMBridgeTrait, EVM};
+use sp_runtime::{
+	traits::{CheckedSub, Convert, UniqueSaturatedInto, Zero},
+	RuntimeDebug, SaturatedConversion, TransactionOutcome,
+};
+use sp_std::{marker::PhantomData, vec::Vec};
+use support::{EVMBridge as EVMBridgeTrait, Erc20Allowances, Erc20BalanceChecker, EVM};
 
+mod default_weight;
 mod mock;
 mod tests;
 
+pub trait WeightInfo {
+	fn register_erc20() -> Weight;
+	fn deregister_erc20() -> Weight;
+}
+
 #[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug)]
 pub struct InvokeContext {
 	pub contract: H160,
@@ -23,44 +44,184 @@ pub struct InvokeContext {
 }
 
 pub type BalanceOf<T> = <<T as Config>::EVM as EVM>::Balance;
+pub type NegativeImbalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
 
 /// EvmBridge module trait
 pub trait Config: frame_system::Config {
+	type Event: From<Event> + Into<<Self as frame_system::Config>::Event>;
+
 	type EVM: EVM;
+
+	/// Mapping from address to account id.
+	type AddressMapping: AddressMapping<Self::AccountId>;
+
+	/// The gas limit of the ERC20 queries.
+	type QueryGasLimit: Get<u32>;
+
+	/// The gas limit of the ERC20 transfers and approvals.
+	type TransactGasLimit: Get<u32>;
+
+	/// Convert the used gas of the ERC20 calls to weight.
+	type GasToWeight: Convert<u32, Weight>;
+
+	/// The currency paying the gas of the ERC20 transfers and approvals.
+	type Currency: Currency<Self::AccountId, Balance = BalanceOf<Self>>;
+
+	/// Handler for the gas fee of the ERC20 transfers and approvals.
+	type OnTransactionPayment: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// The origin which may register ERC20 contracts as currencies.
+	type RegisterOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The maximum number of the registered ERC20 contracts, which bounds
+	/// the scan of `has_erc20_balance`.
+	type MaxErc20Contracts: Get<u32>;
+
+	/// Weight information for the extrinsics in this module.
+	type WeightInfo: WeightInfo;
 }
 
+decl_event!(
+	pub enum Event {
+		/// The ERC20 contract has been registered as a currency. \[contract\]
+		Erc20Registered(H160),
+		/// The ERC20 contract has been deregistered. \[contract\]
+		Erc20Deregistered(H160),
+		/// The call of the ERC20 contract reverted with the reason.
+		/// \[contract, reason\]
+		ExecutionReverted(H160, Vec<u8>),
+	}
+);
+
 decl_error! {
 	pub enum Error for Module<T: Config> {
-		Revert,
-		Fatal,
-		Error
+		/// Execution reverted
+		ExecutionRevert,
+		/// Execution fatal
+		ExecutionFatal,
+		/// Execution error
+		ExecutionError,
+		/// The return value of the contract is invalid
+		InvalidReturnValue,
+		/// The account has no EVM address
+		AddressNotMapped,
+		/// The balance is too low
+		BalanceTooLow,
+		/// The allowance is too low
+		AllowanceTooLow,
+		/// Transfer or approve with the zero address
+		ZeroAddress,
+		/// The operation is not supported by ERC20 tokens
+		NotSupported,
+		/// The ERC20 contract isn't registered as a currency
+		Erc20NotRegistered,
+		/// The gas limit of the ERC20 call exceeds the weight left in the block
+		ExceedBlockWeight,
+		/// The number of the registered ERC20 contracts reaches the maximum
+		TooManyErc20Contracts,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as EvmBridge {
+		/// The allowances of the ERC20 mirror contracts of the native tokens.
+		///
+		/// Allowances: map CurrencyId, (owner, spender) => Balance
+		pub Allowances get(fn allowances): double_map hasher(twox_64_concat) CurrencyId, hasher(twox_64_concat) (H160, H160) => BalanceOf<T>;
+
+		/// The ERC20 contracts registered as `CurrencyId::ERC20` currencies.
+		///
+		/// Erc20Contracts: map H160 => bool
+		pub Erc20Contracts get(fn erc20_contracts): map hasher(twox_64_concat) H160 => bool;
+
+		/// The number of the registered ERC20 contracts.
+		///
+		/// Erc20ContractCount: u32
+		pub Erc20ContractCount get(fn erc20_contract_count): u32;
 	}
 }
 
 decl_module! {
 	pub struct Module<T: Config> for enum Call where origin: T::Origin {
 		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// The gas limit of the ERC20 queries.
+		const QueryGasLimit: u32 = T::QueryGasLimit::get();
+
+		/// The gas limit of the ERC20 transfers and approvals.
+		const TransactGasLimit: u32 = T::TransactGasLimit::get();
+
+		/// The maximum number of the registered ERC20 contracts.
+		const MaxErc20Contracts: u32 = T::MaxErc20Contracts::get();
+
+		/// Register the ERC20 contract as the currency `CurrencyId::ERC20`.
+		///
+		/// The dispatch origin of this call must be `RegisterOrigin`.
+		#[weight = T::WeightInfo::register_erc20()]
+		pub fn register_erc20(origin, contract: H160) {
+			T::RegisterOrigin::ensure_origin(origin)?;
+			if !Self::erc20_contracts(contract) {
+				let count = Self::erc20_contract_count();
+				ensure!(count < T::MaxErc20Contracts::get(), Error::<T>::TooManyErc20Contracts);
+				Erc20ContractCount::put(count + 1);
+				Erc20Contracts::insert(contract, true);
+			}
+			Self::deposit_event(Event::Erc20Registered(contract));
+		}
+
+		/// Deregister the ERC20 contract, the currency can't be transferred
+		/// by `MultiCurrencyAdapter` anymore.
+		///
+		/// The dispatch origin of this call must be `RegisterOrigin`.
+		#[weight = T::WeightInfo::deregister_erc20()]
+		pub fn deregister_erc20(origin, contract: H160) {
+			T::RegisterOrigin::ensure_origin(origin)?;
+			if Erc20Contracts::take(contract) {
+				Erc20ContractCount::mutate(|count| *count = count.saturating_sub(1));
+			}
+			Self::deposit_event(Event::Erc20Deregistered(contract));
+		}
 	}
 }
 
 impl<T: Config> EVMBridgeTrait<InvokeContext, BalanceOf<T>> for Module<T> {
+	fn name(context: InvokeContext) -> Result<Vec<u8>, DispatchError> {
+		// ERC20.name method hash
+		let input = hex!("06fdde03").to_vec();
+
+		let output = Self::query(context, input)?;
+		Self::decode_string(output)
+	}
+
+	fn symbol(context: InvokeContext) -> Result<Vec<u8>, DispatchError> {
+		// ERC20.symbol method hash
+		let input = hex!("95d89b41").to_vec();
+
+		let output = Self::query(context, input)?;
+		Self::decode_string(output)
+	}
+
+	fn decimals(context: InvokeContext) -> Result<u8, DispatchError> {
+		// ERC20.decimals method hash
+		let input = hex!("313ce567").to_vec();
+
+		let output = Self::query(context, input)?;
+		let value = Self::decode_uint(&output)?;
+		ensure!(value <= U256::from(u8::max_value()), Error::<T>::InvalidReturnValue);
+		Ok(value.low_u32() as u8)
+	}
+
 	fn total_supply(context: InvokeContext) -> Result<BalanceOf<T>, DispatchError> {
 		// ERC20.totalSupply method hash
 		let input = hex!("18160ddd").to_vec();
 
-		let info = T::EVM::execute(
-			H160::default(),
-			context.contract,
-			input,
-			Default::default(),
-			2_100_000,
-			None,
-		)?;
-
-		Self::handle_exit_reason(info.exit_reason)?;
-
-		let value = U256::from(info.output.as_slice()).saturated_into::<u128>();
-		Ok(value.saturated_into::<BalanceOf<T>>())
+		let output = Self::query(context, input)?;
+		Ok(Self::decode_uint(&output)?
+			.saturated_into::<u128>()
+			.saturated_into::<BalanceOf<T>>())
 	}
 
 	fn balance_of(context: InvokeContext, address: H160) -> Result<BalanceOf<T>, DispatchError> {
@@ -69,18 +230,22 @@ impl<T: Config> EVMBridgeTrait<InvokeContext, BalanceOf<T>> for Module<T> {
 		// append address
 		input.extend_from_slice(H256::from(address).as_bytes());
 
-		let info = T::EVM::execute(
-			H160::default(),
-			context.contract,
-			input,
-			Default::default(),
-			2_100_000,
-			None,
-		)?;
+		let output = Self::query(context, input)?;
+		Ok(Self::decode_uint(&output)?
+			.saturated_into::<u128>()
+			.saturated_into::<BalanceOf<T>>())
+	}
 
-		Self::handle_exit_reason(info.exit_reason)?;
+	fn allowance(context: InvokeContext, owner: H160, spender: H160) -> Result<BalanceOf<T>, DispatchError> {
+		// ERC20.allowance method hash
+		let mut input = hex!("dd62ed3e").to_vec();
+		// append owner address
+		input.extend_from_slice(H256::from(owner).as_bytes());
+		// append spender address
+		input.extend_from_slice(H256::from(spender).as_bytes());
 
-		Ok(U256::from(info.output.as_slice())
+		let output = Self::query(context, input)?;
+		Ok(Self::decode_uint(&output)?
 			.saturated_into::<u128>()
 			.saturated_into::<BalanceOf<T>>())
 	}
@@ -93,26 +258,502 @@ impl<T: Config> EVMBridgeTrait<InvokeContext, BalanceOf<T>> for Module<T> {
 		// append amount to be transferred
 		input.extend_from_slice(H256::from_uint(&U256::from(value.saturated_into::<u128>())).as_bytes());
 
-		let info = T::EVM::execute(
+		Self::transact(context, input)
+	}
+
+	fn approve(context: InvokeContext, spender: H160, value: BalanceOf<T>) -> DispatchResult {
+		// ERC20.approve method hash
+		let mut input = hex!("095ea7b3").to_vec();
+		// append spender address
+		input.extend_from_slice(H256::from(spender).as_bytes());
+		// append amount to be approved
+		input.extend_from_slice(H256::from_uint(&U256::from(value.saturated_into::<u128>())).as_bytes());
+
+		Self::transact(context, input)
+	}
+
+	fn transfer_from(context: InvokeContext, from: H160, to: H160, value: BalanceOf<T>) -> DispatchResult {
+		// ERC20.transferFrom method hash
+		let mut input = hex!("23b872dd").to_vec();
+		// append sender address
+		input.extend_from_slice(H256::from(from).as_bytes());
+		// append receiver address
+		input.extend_from_slice(H256::from(to).as_bytes());
+		// append amount to be transferred
+		input.extend_from_slice(H256::from_uint(&U256::from(value.saturated_into::<u128>())).as_bytes());
+
+		Self::transact(context, input)
+	}
+}
+
+impl<T: Config> Erc20Allowances<CurrencyId, BalanceOf<T>> for Module<T> {
+	fn allowance(currency_id: CurrencyId, owner: H160, spender: H160) -> BalanceOf<T> {
+		Self::allowances(currency_id, (owner, spender))
+	}
+
+	fn set_allowance(currency_id: CurrencyId, owner: H160, spender: H160, amount: BalanceOf<T>) {
+		if amount.is_zero() {
+			Allowances::<T>::remove(currency_id, (owner, spender));
+		} else {
+			Allowances::<T>::insert(currency_id, (owner, spender), amount);
+		}
+	}
+}
+
+impl<T: Config> Erc20BalanceChecker for Module<T> {
+	fn has_erc20_balance(address: H160) -> bool {
+		Erc20Contracts::iter().any(|(contract, registered)| {
+			registered
+				&& Self::balance_of(
+					InvokeContext {
+						contract,
+						source: Default::default(),
+					},
+					address,
+				)
+				.map_or(false, |balance| !balance.is_zero())
+		})
+	}
+
+	fn has_erc20_balance_weight() -> Weight {
+		T::GasToWeight::convert(T::QueryGasLimit::get())
+			.saturating_add(<T as frame_system::Config>::DbWeight::get().reads(1))
+			.saturating_mul(T::MaxErc20Contracts::get().into())
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// Execute a view method of the contract, returns the output. The changes
+	/// of the execution are discarded.
+	fn query(context: InvokeContext, input: Vec<u8>) -> Result<Vec<u8>, DispatchError> {
+		Self::ensure_block_weight(T::QueryGasLimit::get())?;
+		let info = frame_support::storage::with_transaction(|| {
+			TransactionOutcome::Rollback(T::EVM::execute(
+				context.source,
+				context.contract,
+				input,
+				Default::default(),
+				T::QueryGasLimit::get(),
+				None,
+			))
+		})?;
+		Self::charge_gas(info.used_gas);
+
+		Self::handle_exit_reason(context.contract, info.exit_reason, &info.output)?;
+		Ok(info.output)
+	}
+
+	/// Execute a method of the contract which returns `bool`, the output is
+	/// allowed to be empty for the tokens returning nothing.
+	///
+	/// The account of the source pays the fee of the gas limit up front, the
+	/// fee of unused gas is refunded.
+	fn transact(context: InvokeContext, input: Vec<u8>) -> DispatchResult {
+		let gas_limit = T::TransactGasLimit::get();
+		Self::ensure_block_weight(gas_limit)?;
+
+		let payer = T::AddressMapping::to_account(&context.source);
+		let gas_price = T::EVM::gas_price();
+		let max_fee = gas_price.saturating_mul(gas_limit.into());
+		let fee = T::Currency::withdraw(
+			&payer,
+			max_fee,
+			WithdrawReasons::TRANSACTION_PAYMENT,
+			ExistenceRequirement::AllowDeath,
+		)?;
+
+		let info = match T::EVM::execute(
 			context.source,
 			context.contract,
 			input,
 			Default::default(),
-			2_100_000,
+			gas_limit,
 			None,
-		)?;
+		) {
+			Ok(info) => info,
+			Err(e) => {
+				T::Currency::resolve_creating(&payer, fee);
+				return Err(e);
+			}
+		};
+		Self::charge_gas(info.used_gas);
 
-		Self::handle_exit_reason(info.exit_reason)
+		// refund the fee of unused gas
+		let used_gas: u32 = info.used_gas.unique_saturated_into();
+		let refund = T::Currency::deposit_creating(
+			&payer,
+			max_fee.saturating_sub(gas_price.saturating_mul(used_gas.into())),
+		);
+		if let Ok(fee) = fee.offset(refund) {
+			T::OnTransactionPayment::on_unbalanced(fee);
+		}
+
+		Self::handle_exit_reason(context.contract, info.exit_reason, &info.output)?;
+		ensure!(
+			info.output.is_empty() || !Self::decode_uint(&info.output)?.is_zero(),
+			Error::<T>::ExecutionRevert
+		);
+		Ok(())
 	}
-}
 
-impl<T: Config> Module<T> {
-	fn handle_exit_reason(exit_reason: ExitReason) -> Result<(), DispatchError> {
+	/// Ensure the block has weight left for `gas_limit`, the ERC20 calls run
+	/// inside the extrinsics of fixed weights.
+	fn ensure_block_weight(gas_limit: u32) -> DispatchResult {
+		let max_weight = <T as frame_system::Config>::AvailableBlockRatio::get()
+			* <T as frame_system::Config>::MaximumBlockWeight::get();
+		let weight = frame_system::Module::<T>::block_weight()
+			.get(DispatchClass::Normal)
+			.saturating_add(T::GasToWeight::convert(gas_limit));
+		ensure!(weight <= max_weight, Error::<T>::ExceedBlockWeight);
+		Ok(())
+	}
+
+	/// The ERC20 calls run inside the extrinsics of fixed weights, the used
+	/// gas is added to the weight of the block.
+	fn charge_gas(used_gas: U256) {
+		let used_gas: u32 = used_gas.unique_saturated_into();
+		frame_system::Module::<T>::register_extra_weight_unchecked(
+			T::GasToWeight::convert(used_gas),
+			DispatchClass::Normal,
+		);
+	}
+
+	fn handle_exit_reason(contract: H160, exit_reason: ExitReason, output: &[u8]) -> Result<(), DispatchError> {
 		match exit_reason {
 			ExitReason::Succeed(_) => Ok(()),
-			ExitReason::Revert(_) => Err(Error::<T>::Revert.into()),
-			ExitReason::Fatal(_) => Err(Error::<T>::Fatal.into()),
-			ExitReason::Error(_) => Err(Error::<T>::Error.into()),
+			ExitReason::Revert(_) => Err(Self::revert_error(contract, output).into()),
+			ExitReason::Fatal(_) => Err(Error::<T>::ExecutionFatal.into()),
+			ExitReason::Error(_) => Err(Error::<T>::ExecutionError.into()),
+		}
+	}
+
+	/// Map the revert reason in `output` to the error, the reasons of the
+	/// OpenZeppelin ERC20 contracts are recognized. The reason is deposited
+	/// in `ExecutionReverted`, the event is discarded if the caller rolls
+	/// back the failed extrinsic.
+	fn revert_error(contract: H160, output: &[u8]) -> Error<T> {
+		let reason = match Self::decode_revert_reason(output) {
+			Some(reason) => reason,
+			None => return Error::<T>::ExecutionRevert,
+		};
+		Self::deposit_event(Event::ExecutionReverted(contract, reason.clone()));
+
+		let contains = |pattern: &[u8]| reason.windows(pattern.len()).any(|window| window == pattern);
+		if contains(b"exceeds balance") {
+			Error::<T>::BalanceTooLow
+		} else if contains(b"exceeds allowance") || contains(b"allowance below zero") {
+			Error::<T>::AllowanceTooLow
+		} else if contains(b"zero address") {
+			Error::<T>::ZeroAddress
+		} else {
+			Error::<T>::ExecutionRevert
+		}
+	}
+
+	/// Decode the reason of `revert(string)`, which is ABI encoded as
+	/// `Error(string)`.
+	fn decode_revert_reason(output: &[u8]) -> Option<Vec<u8>> {
+		// Error(string) method hash
+		if output.len() < 4 || output[0..4] != hex!("08c379a0") {
+			return None;
+		}
+		Self::decode_string(output[4..].to_vec()).ok()
+	}
+
+	/// Decode the ABI encoded `uint256`.
+	fn decode_uint(output: &[u8]) -> Result<U256, DispatchError> {
+		ensure!(output.len() == 32, Error::<T>::InvalidReturnValue);
+		Ok(U256::from_big_endian(output))
+	}
+
+	/// Decode the ABI encoded `string`.
+	fn decode_string(output: Vec<u8>) -> Result<Vec<u8>, DispatchError> {
+		// the output is 32-byte aligned and consists of 3 parts:
+		// - the offset of the string data
+		// - the length of the string, at the offset
+		// - the string data, right padded
+		ensure!(
+			output.len() >= 64 && output.len() % 32 == 0,
+			Error::<T>::InvalidReturnValue
+		);
+
+		let offset = Self::decode_uint(&output[0..32])?;
+		ensure!(offset <= U256::from(output.len() - 32), Error::<T>::InvalidReturnValue);
+		let offset = offset.as_usize();
+
+		let length = Self::decode_uint(&output[offset..offset + 32])?;
+		ensure!(
+			length <= U256::from(output.len() - offset - 32),
+			Error::<T>::InvalidReturnValue
+		);
+		let length = length.as_usize();
+
+		Ok(output[offset + 32..offset + 32 + length].to_vec())
+	}
+}
+
+/// Routes `CurrencyId::ERC20` currencies to the ERC20 contracts via the
+/// EVMBridge, and the other currencies to `Currency`.
+///
+/// The balances of an account are the ERC20 balances of its EVM address, the
+/// ERC20 tokens can't be minted, burned, locked or reserved. Only the ERC20
+/// contracts registered by `register_erc20` are currencies, the others have
+/// no balances and can't be transferred. The sender of an ERC20 transfer pays
+/// its gas at the EVM gas price.
+///
+/// `set_lock` and `extend_lock` can't return an error, they log the ERC20
+/// locks and leave the tokens unlocked. The callers locking an arbitrary
+/// currency must reject `CurrencyId::ERC20` themselves.
+pub struct MultiCurrencyAdapter<T, Currency>(PhantomData<(T, Currency)>);
+
+impl<T, Currency> MultiCurrencyAdapter<T, Currency>
+where
+	T: Config,
+	Currency: MultiCurrency<T::AccountId, CurrencyId = CurrencyId, Balance = BalanceOf<T>>,
+{
+	fn reject_erc20_lock(lock_id: LockIdentifier, currency_id: CurrencyId, who: &T::AccountId) {
+		debug::warn!(
+			"ERC20 tokens can't be locked, lock_id: {:?}, currency_id: {:?}, who: {:?}",
+			lock_id,
+			currency_id,
+			who
+		);
+	}
+
+	fn erc20_balance(contract: H160, who: &T::AccountId) -> BalanceOf<T> {
+		if !Module::<T>::erc20_contracts(contract) {
+			return Zero::zero();
 		}
+		T::AddressMapping::to_evm_address(who)
+			.and_then(|address| {
+				let context = InvokeContext {
+					contract,
+					source: Default::default(),
+				};
+				Module::<T>::balance_of(context, address).ok()
+			})
+			.unwrap_or_default()
+	}
+}
+
+impl<T, Currency> MultiCurrency<T::AccountId> for MultiCurrencyAdapter<T, Currency>
+where
+	T: Config,
+	Currency: MultiCurrency<T::AccountId, CurrencyId = CurrencyId, Balance = BalanceOf<T>>,
+{
+	type CurrencyId = CurrencyId;
+	type Balance = Currency::Balance;
+
+	fn minimum_balance(currency_id: Self::CurrencyId) -> Self::Balance {
+		match currency_id {
+			CurrencyId::ERC20(_) => Zero::zero(),
+			_ => Currency::minimum_balance(currency_id),
+		}
+	}
+
+	fn total_issuance(currency_id: Self::CurrencyId) -> Self::Balance {
+		match currency_id {
+			CurrencyId::ERC20(contract) if Module::<T>::erc20_contracts(contract) => {
+				Module::<T>::total_supply(InvokeContext {
+					contract,
+					source: Default::default(),
+				})
+				.unwrap_or_default()
+			}
+			CurrencyId::ERC20(_) => Zero::zero(),
+			_ => Currency::total_issuance(currency_id),
+		}
+	}
+
+	fn total_balance(currency_id: Self::CurrencyId, who: &T::AccountId) -> Self::Balance {
+		match currency_id {
+			CurrencyId::ERC20(contract) => Self::erc20_balance(contract, who),
+			_ => Currency::total_balance(currency_id, who),
+		}
+	}
+
+	fn free_balance(currency_id: Self::CurrencyId, who: &T::AccountId) -> Self::Balance {
+		match currency_id {
+			CurrencyId::ERC20(contract) => Self::erc20_balance(contract, who),
+			_ => Currency::free_balance(currency_id, who),
+		}
+	}
+
+	fn ensure_can_withdraw(currency_id: Self::CurrencyId, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		match currency_id {
+			CurrencyId::ERC20(contract) => {
+				Self::erc20_balance(contract, who)
+					.checked_sub(&amount)
+					.ok_or(Error::<T>::BalanceTooLow)?;
+				Ok(())
+			}
+			_ => Currency::ensure_can_withdraw(currency_id, who, amount),
+		}
+	}
+
+	fn transfer(
+		currency_id: Self::CurrencyId,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult {
+		match currency_id {
+			CurrencyId::ERC20(contract) => {
+				ensure!(Module::<T>::erc20_contracts(contract), Error::<T>::Erc20NotRegistered);
+				if amount.is_zero() || from == to {
+					return Ok(());
+				}
+				let source = T::AddressMapping::to_evm_address(from).ok_or(Error::<T>::AddressNotMapped)?;
+				let to = T::AddressMapping::get_or_create_evm_address(to);
+				Module::<T>::transfer(InvokeContext { contract, source }, to, amount)
+			}
+			_ => Currency::transfer(currency_id, from, to, amount),
+		}
+	}
+
+	fn deposit(currency_id: Self::CurrencyId, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		match currency_id {
+			CurrencyId::ERC20(_) => Err(Error::<T>::NotSupported.into()),
+			_ => Currency::deposit(currency_id, who, amount),
+		}
+	}
+
+	fn withdraw(currency_id: Self::CurrencyId, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		match currency_id {
+			CurrencyId::ERC20(_) => Err(Error::<T>::NotSupported.into()),
+			_ => Currency::withdraw(currency_id, who, amount),
+		}
+	}
+
+	fn can_slash(currency_id: Self::CurrencyId, who: &T::AccountId, value: Self::Balance) -> bool {
+		match currency_id {
+			CurrencyId::ERC20(_) => value.is_zero(),
+			_ => Currency::can_slash(currency_id, who, value),
+		}
+	}
+
+	fn slash(currency_id: Self::CurrencyId, who: &T::AccountId, amount: Self::Balance) -> Self::Balance {
+		match currency_id {
+			CurrencyId::ERC20(_) => amount,
+			_ => Currency::slash(currency_id, who, amount),
+		}
+	}
+}
+
+impl<T, Currency> MultiCurrencyExtended<T::AccountId> for MultiCurrencyAdapter<T, Currency>
+where
+	T: Config,
+	Currency: MultiCurrencyExtended<T::AccountId, CurrencyId = CurrencyId, Balance = BalanceOf<T>>,
+{
+	type Amount = Currency::Amount;
+
+	fn update_balance(currency_id: Self::CurrencyId, who: &T::AccountId, by_amount: Self::Amount) -> DispatchResult {
+		match currency_id {
+			CurrencyId::ERC20(_) => {
+				ensure!(by_amount.is_zero(), Error::<T>::NotSupported);
+				Ok(())
+			}
+			_ => Currency::update_balance(currency_id, who, by_amount),
+		}
+	}
+}
+
+impl<T, Currency> MultiLockableCurrency<T::AccountId> for MultiCurrencyAdapter<T, Currency>
+where
+	T: Config,
+	Currency: MultiLockableCurrency<T::AccountId, CurrencyId = CurrencyId, Balance = BalanceOf<T>>,
+{
+	type Moment = Currency::Moment;
+
+	fn set_lock(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &T::AccountId, amount: Self::Balance) {
+		if currency_id.is_erc20_currency_id() {
+			Self::reject_erc20_lock(lock_id, currency_id, who);
+		} else {
+			Currency::set_lock(lock_id, currency_id, who, amount)
+		}
+	}
+
+	fn extend_lock(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &T::AccountId, amount: Self::Balance) {
+		if currency_id.is_erc20_currency_id() {
+			Self::reject_erc20_lock(lock_id, currency_id, who);
+		} else {
+			Currency::extend_lock(lock_id, currency_id, who, amount)
+		}
+	}
+
+	fn remove_lock(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &T::AccountId) {
+		if !currency_id.is_erc20_currency_id() {
+			Currency::remove_lock(lock_id, currency_id, who)
+		}
+	}
+}
+
+impl<T, Currency> MultiReservableCurrency<T::AccountId> for MultiCurrencyAdapter<T, Currency>
+where
+	T: Config,
+	Currency: MultiReservableCurrency<T::AccountId, CurrencyId = CurrencyId, Balance = BalanceOf<T>>,
+{
+	fn can_reserve(currency_id: Self::CurrencyId, who: &T::AccountId, value: Self::Balance) -> bool {
+		match currency_id {
+			CurrencyId::ERC20(_) => value.is_zero(),
+			_ => Currency::can_reserve(currency_id, who, value),
+		}
+	}
+
+	fn slash_reserved(currency_id: Self::CurrencyId, who: &T::AccountId, value: Self::Balance) -> Self::Balance {
+		match currency_id {
+			CurrencyId::ERC20(_) => value,
+			_ => Currency::slash_reserved(currency_id, who, value),
+		}
+	}
+
+	fn reserved_balance(currency_id: Self::CurrencyId, who: &T::AccountId) -> Self::Balance {
+		match currency_id {
+			CurrencyId::ERC20(_) => Zero::zero(),
+			_ => Currency::reserved_balance(currency_id, who),
+		}
+	}
+
+	fn reserve(currency_id: Self::CurrencyId, who: &T::AccountId, value: Self::Balance) -> DispatchResult {
+		match currency_id {
+			CurrencyId::ERC20(_) => {
+				ensure!(value.is_zero(), Error::<T>::NotSupported);
+				Ok(())
+			}
+			_ => Currency::reserve(currency_id, who, value),
+		}
+	}
+
+	fn unreserve(currency_id: Self::CurrencyId, who: &T::AccountId, value: Self::Balance) -> Self::Balance {
+		match currency_id {
+			CurrencyId::ERC20(_) => value,
+			_ => Currency::unreserve(currency_id, who, value),
+		}
+	}
+
+	fn repatriate_reserved(
+		currency_id: Self::CurrencyId,
+		slashed: &T::AccountId,
+		beneficiary: &T::AccountId,
+		value: Self::Balance,
+		status: BalanceStatus,
+	) -> sp_std::result::Result<Self::Balance, DispatchError> {
+		match currency_id {
+			CurrencyId::ERC20(_) => Ok(value),
+			_ => Currency::repatriate_reserved(currency_id, slashed, beneficiary, value, status),
+		}
+	}
+}
+
+impl<T, Currency> MergeAccount<T::AccountId> for MultiCurrencyAdapter<T, Currency>
+where
+	T: Config,
+	Currency: MergeAccount<T::AccountId>,
+{
+	/// The ERC20 tokens aren't merged, they're owned by the EVM address and
+	/// follow its mapping, e.g. the tokens of the padded `evm:` account are
+	/// owned by the account which binds its EVM address.
+	fn merge_account(source: &T::AccountId, dest: &T::AccountId) -> DispatchResult {
+		Currency::merge_account(source, dest)
 	}
 }
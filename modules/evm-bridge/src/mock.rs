@@ -3,10 +3,11 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{impl_outer_origin, ord_parameter_types, parameter_types};
+use frame_support::{impl_outer_event, impl_outer_origin, ord_parameter_types, parameter_types};
 use frame_system::EnsureSignedBy;
 use module_evm::GenesisAccount;
-use primitives::evm::AddressMapping;
+use orml_traits::parameter_type_with_key;
+use primitives::{evm::AddressMapping, Amount};
 use sp_core::{bytes::from_hex, crypto::AccountId32, H256};
 use sp_runtime::{testing::Header, traits::IdentityLookup, Perbill};
 use sp_std::{collections::btree_map::BTreeMap, str::FromStr};
@@ -18,13 +19,27 @@ pub type Balance = u128;
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Runtime;
 
+mod evm_bridge {
+	pub use super::super::*;
+}
+
 impl_outer_origin! {
 	pub enum Origin for Runtime {}
 }
 
+impl_outer_event! {
+	pub enum TestEvent for Runtime {
+		frame_system<T>,
+		pallet_balances<T>,
+		orml_tokens<T>,
+		module_evm<T>,
+		evm_bridge,
+	}
+}
+
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
-	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockWeight: u32 = 10_000_000;
 	pub const MaximumBlockLength: u32 = 2 * 1024;
 	pub const AvailableBlockRatio: Perbill = Perbill::one();
 }
@@ -40,7 +55,7 @@ impl frame_system::Config for Runtime {
 	type AccountId = AccountId;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
-	type Event = ();
+	type Event = TestEvent;
 	type BlockHashCount = BlockHashCount;
 	type MaximumBlockWeight = MaximumBlockWeight;
 	type DbWeight = ();
@@ -65,7 +80,7 @@ parameter_types! {
 impl pallet_balances::Config for Runtime {
 	type Balance = Balance;
 	type DustRemoval = ();
-	type Event = ();
+	type Event = TestEvent;
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
 	type WeightInfo = ();
@@ -74,6 +89,23 @@ impl pallet_balances::Config for Runtime {
 
 pub type Balances = pallet_balances::Module<Runtime>;
 
+parameter_type_with_key! {
+	pub ExistentialDeposits: |currency_id: CurrencyId| -> Balance {
+		Default::default()
+	};
+}
+
+impl orml_tokens::Config for Runtime {
+	type Event = TestEvent;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type WeightInfo = ();
+	type ExistentialDeposits = ExistentialDeposits;
+	type OnDust = ();
+}
+pub type Tokens = orml_tokens::Module<Runtime>;
+
 pub struct EvmAddressMapping;
 impl AddressMapping<AccountId> for EvmAddressMapping {
 	fn to_account(address: &H160) -> AccountId {
@@ -83,8 +115,17 @@ impl AddressMapping<AccountId> for EvmAddressMapping {
 		AccountId32::from(data).into()
 	}
 
-	fn to_evm_address(_account_id: &AccountId) -> Option<H160> {
-		None
+	fn to_evm_address(account_id: &AccountId) -> Option<H160> {
+		let data: [u8; 32] = account_id.clone().into();
+		if data.starts_with(b"evm:") {
+			Some(H160::from_slice(&data[4..24]))
+		} else {
+			None
+		}
+	}
+
+	fn get_or_create_evm_address(account_id: &AccountId) -> H160 {
+		Self::to_evm_address(account_id).unwrap_or_default()
 	}
 }
 
@@ -118,23 +159,63 @@ impl module_evm::Config for Runtime {
 	type TransferMaintainerDeposit = TransferMaintainerDeposit;
 	type StorageDepositPerByte = StorageDepositPerByte;
 	type StorageDefaultQuota = StorageDefaultQuota;
+	type RentPerBytePerBlock = ();
+	type RentGracePeriod = ();
+	type RentCollectionPeriod = ();
+	type MaxRentCollections = ();
+	type OnRentPayment = ();
+	type CodeUpgradeDelay = ();
 
-	type Event = ();
+	type Event = TestEvent;
 	type Precompiles = ();
 	type ChainId = ();
 	type Runner = module_evm::runner::native::Runner<Self>;
 	type GasToWeight = ();
+	type StorageMetering = ();
 	type NetworkContractOrigin = EnsureSignedBy<NetworkContractAccount, AccountId32>;
 	type NetworkContractSource = NetworkContractSource;
+	type OnTransactionPayment = ();
+	type MinGasPrice = MinGasPrice;
+	type GasPriceMultiplier = ();
+	type DeploymentOrigin = EnsureSignedBy<NetworkContractAccount, AccountId32>;
+	type DeveloperDeposit = ();
 	type WeightInfo = ();
 }
 
 pub type EVM = module_evm::Module<Runtime>;
 
+parameter_types! {
+	pub const MinGasPrice: Balance = 1;
+}
+
+parameter_types! {
+	pub const QueryGasLimit: u32 = 1_000_000;
+	pub const TransactGasLimit: u32 = 2_100_000;
+	pub const MaxErc20Contracts: u32 = 2;
+}
+
+pub struct GasToWeight;
+impl Convert<u32, u64> for GasToWeight {
+	fn convert(a: u32) -> u64 {
+		a as u64
+	}
+}
+
 impl Config for Runtime {
+	type Event = TestEvent;
 	type EVM = EVM;
+	type AddressMapping = EvmAddressMapping;
+	type QueryGasLimit = QueryGasLimit;
+	type TransactGasLimit = TransactGasLimit;
+	type GasToWeight = GasToWeight;
+	type Currency = Balances;
+	type OnTransactionPayment = ();
+	type RegisterOrigin = EnsureSignedBy<NetworkContractAccount, AccountId32>;
+	type MaxErc20Contracts = MaxErc20Contracts;
+	type WeightInfo = ();
 }
 pub type EvmBridgeModule = Module<Runtime>;
+pub type Currencies = MultiCurrencyAdapter<Runtime, Tokens>;
 
 pub struct ExtBuilder();
 
@@ -144,6 +225,8 @@ impl Default for ExtBuilder {
 	}
 }
 
+pub const INITIAL_BALANCE: Balance = 1_000_000_000;
+
 pub fn erc20_address() -> H160 {
 	H160::from_str("2000000000000000000000000000000000000001").unwrap()
 }
@@ -162,6 +245,15 @@ impl ExtBuilder {
 			.build_storage::<Runtime>()
 			.unwrap();
 
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![
+				(EvmAddressMapping::to_account(&alice()), INITIAL_BALANCE),
+				(EvmAddressMapping::to_account(&bob()), INITIAL_BALANCE),
+			],
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
 		let mut accounts = BTreeMap::new();
 		let mut storage = BTreeMap::new();
 		storage.insert(
@@ -184,12 +276,17 @@ impl ExtBuilder {
 		module_evm::GenesisConfig::<Runtime> {
 			accounts,
 			network_contract_index: 2048,
+			open_deployment: true,
 		}
 		.assimilate_storage(&mut t)
 		.unwrap();
 
 		let mut ext = sp_io::TestExternalities::new(t);
-		ext.execute_with(|| System::set_block_number(1));
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			Erc20Contracts::insert(erc20_address(), true);
+			Erc20ContractCount::put(1);
+		});
 		ext
 	}
 }
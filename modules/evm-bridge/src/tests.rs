@@ -3,8 +3,14 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_err, assert_ok};
-use mock::{alice, bob, erc20_address, EvmBridgeModule, ExtBuilder, Runtime};
+use frame_support::{assert_err, assert_noop, assert_ok};
+use mock::{
+	alice, bob, erc20_address, AccountId, Balances, Currencies, EvmAddressMapping, EvmBridgeModule, ExtBuilder,
+	MaxErc20Contracts, MaximumBlockWeight, NetworkContractAccount, Origin, QueryGasLimit, Runtime, System, TestEvent,
+	Tokens, INITIAL_BALANCE,
+};
+use primitives::TokenSymbol;
+use sp_runtime::traits::BadOrigin;
 
 #[test]
 fn should_read_total_supply() {
@@ -14,7 +20,7 @@ fn should_read_total_supply() {
 				contract: H160::default(),
 				source: Default::default()
 			}),
-			Ok(0)
+			Err(Error::<Runtime>::InvalidReturnValue.into())
 		);
 
 		assert_eq!(
@@ -55,8 +61,14 @@ fn should_transfer() {
 				alice(),
 				10
 			),
-			Error::<Runtime>::Revert
+			Error::<Runtime>::BalanceTooLow
 		);
+		// the revert reason is deposited
+		assert!(System::events().iter().any(|record| match &record.event {
+			TestEvent::evm_bridge(Event::ExecutionReverted(contract, reason)) =>
+				*contract == erc20_address() && reason.ends_with(b"exceeds balance"),
+			_ => false,
+		}));
 
 		assert_ok!(EvmBridgeModule::transfer(
 			InvokeContext {
@@ -106,7 +118,266 @@ fn should_transfer() {
 				alice(),
 				100
 			),
-			Error::<Runtime>::Revert
+			Error::<Runtime>::BalanceTooLow
 		);
 	});
 }
+
+#[test]
+fn should_read_metadata() {
+	ExtBuilder::default().build().execute_with(|| {
+		let context = InvokeContext {
+			contract: erc20_address(),
+			source: Default::default(),
+		};
+
+		// the demo contract has no metadata
+		assert_err!(EvmBridgeModule::name(context), Error::<Runtime>::ExecutionRevert);
+		assert_err!(EvmBridgeModule::symbol(context), Error::<Runtime>::ExecutionRevert);
+		assert_err!(EvmBridgeModule::decimals(context), Error::<Runtime>::ExecutionRevert);
+	});
+}
+
+#[test]
+fn should_decode_string() {
+	ExtBuilder::default().build().execute_with(|| {
+		let mut output = H256::from_low_u64_be(32).as_bytes().to_vec();
+		output.extend_from_slice(H256::from_low_u64_be(5).as_bytes());
+		let mut data = [0u8; 32];
+		data[0..5].copy_from_slice(b"Token");
+		output.extend_from_slice(&data);
+		assert_eq!(EvmBridgeModule::decode_string(output.clone()), Ok(b"Token".to_vec()));
+
+		// the length overflows the output
+		output[63] = 33;
+		assert_err!(
+			EvmBridgeModule::decode_string(output.clone()),
+			Error::<Runtime>::InvalidReturnValue
+		);
+
+		// the offset overflows the output
+		output[31] = 96;
+		assert_err!(
+			EvmBridgeModule::decode_string(output),
+			Error::<Runtime>::InvalidReturnValue
+		);
+	});
+}
+
+#[test]
+fn should_approve_and_transfer_from() {
+	ExtBuilder::default().build().execute_with(|| {
+		let context = InvokeContext {
+			contract: erc20_address(),
+			source: alice(),
+		};
+		assert_eq!(EvmBridgeModule::allowance(context, alice(), bob()), Ok(0));
+		assert_ok!(EvmBridgeModule::approve(context, bob(), 100));
+		assert_eq!(EvmBridgeModule::allowance(context, alice(), bob()), Ok(100));
+		assert_err!(
+			EvmBridgeModule::approve(context, H160::default(), 100),
+			Error::<Runtime>::ZeroAddress
+		);
+
+		let context = InvokeContext {
+			contract: erc20_address(),
+			source: bob(),
+		};
+		assert_ok!(EvmBridgeModule::transfer_from(context, alice(), bob(), 60));
+		assert_eq!(EvmBridgeModule::balance_of(context, bob()), Ok(60));
+		assert_eq!(EvmBridgeModule::allowance(context, alice(), bob()), Ok(40));
+		assert_err!(
+			EvmBridgeModule::transfer_from(context, alice(), bob(), 50),
+			Error::<Runtime>::AllowanceTooLow
+		);
+		assert_err!(
+			EvmBridgeModule::transfer_from(context, bob(), alice(), 10),
+			Error::<Runtime>::AllowanceTooLow
+		);
+	});
+}
+
+#[test]
+fn multi_currency_adapter_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let erc20 = CurrencyId::ERC20(erc20_address());
+		let alice_account = EvmAddressMapping::to_account(&alice());
+		let bob_account = EvmAddressMapping::to_account(&bob());
+
+		assert_eq!(Currencies::total_issuance(erc20), u128::max_value());
+		assert_eq!(Currencies::free_balance(erc20, &alice_account), u128::max_value());
+		assert_eq!(Currencies::free_balance(erc20, &bob_account), 0);
+
+		assert_ok!(Currencies::transfer(erc20, &alice_account, &bob_account, 100));
+		assert_eq!(Currencies::free_balance(erc20, &bob_account), 100);
+		assert_ok!(Currencies::ensure_can_withdraw(erc20, &bob_account, 100));
+		assert_err!(
+			Currencies::ensure_can_withdraw(erc20, &bob_account, 101),
+			Error::<Runtime>::BalanceTooLow
+		);
+		assert_err!(
+			Currencies::transfer(erc20, &bob_account, &alice_account, 101),
+			Error::<Runtime>::BalanceTooLow
+		);
+		assert_err!(
+			Currencies::transfer(erc20, &AccountId::new([1u8; 32]), &bob_account, 1),
+			Error::<Runtime>::AddressNotMapped
+		);
+		assert_err!(
+			Currencies::deposit(erc20, &bob_account, 1),
+			Error::<Runtime>::NotSupported
+		);
+		assert_err!(
+			Currencies::reserve(erc20, &bob_account, 1),
+			Error::<Runtime>::NotSupported
+		);
+
+		// the other currencies are routed to the tokens
+		let ausd = CurrencyId::Token(TokenSymbol::AUSD);
+		assert_ok!(Currencies::deposit(ausd, &bob_account, 10));
+		assert_eq!(Tokens::free_balance(ausd, &bob_account), 10);
+		assert_eq!(Currencies::free_balance(ausd, &bob_account), 10);
+	});
+}
+
+#[test]
+fn register_erc20_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let erc20 = CurrencyId::ERC20(erc20_address());
+		let alice_account = EvmAddressMapping::to_account(&alice());
+		let bob_account = EvmAddressMapping::to_account(&bob());
+		let governance = Origin::signed(NetworkContractAccount::get());
+
+		assert_noop!(
+			EvmBridgeModule::deregister_erc20(Origin::signed(alice_account.clone()), erc20_address()),
+			BadOrigin
+		);
+		assert_ok!(EvmBridgeModule::deregister_erc20(governance.clone(), erc20_address()));
+		assert!(!EvmBridgeModule::erc20_contracts(erc20_address()));
+
+		// the unregistered contract isn't a currency
+		assert_eq!(Currencies::total_issuance(erc20), 0);
+		assert_eq!(Currencies::free_balance(erc20, &alice_account), 0);
+		assert_noop!(
+			Currencies::transfer(erc20, &alice_account, &bob_account, 100),
+			Error::<Runtime>::Erc20NotRegistered
+		);
+
+		assert_ok!(EvmBridgeModule::register_erc20(governance, erc20_address()));
+		assert_ok!(Currencies::transfer(erc20, &alice_account, &bob_account, 100));
+		assert_eq!(Currencies::free_balance(erc20, &bob_account), 100);
+	});
+}
+
+#[test]
+fn register_erc20_should_be_bounded() {
+	ExtBuilder::default().build().execute_with(|| {
+		let governance = Origin::signed(NetworkContractAccount::get());
+		assert_eq!(EvmBridgeModule::erc20_contract_count(), 1);
+
+		// registering again doesn't count
+		assert_ok!(EvmBridgeModule::register_erc20(governance.clone(), erc20_address()));
+		assert_eq!(EvmBridgeModule::erc20_contract_count(), 1);
+
+		assert_ok!(EvmBridgeModule::register_erc20(governance.clone(), alice()));
+		assert_eq!(EvmBridgeModule::erc20_contract_count(), MaxErc20Contracts::get());
+		assert_noop!(
+			EvmBridgeModule::register_erc20(governance.clone(), bob()),
+			Error::<Runtime>::TooManyErc20Contracts
+		);
+
+		assert_ok!(EvmBridgeModule::deregister_erc20(governance.clone(), alice()));
+		assert_ok!(EvmBridgeModule::deregister_erc20(governance.clone(), alice()));
+		assert_eq!(EvmBridgeModule::erc20_contract_count(), 1);
+		assert_ok!(EvmBridgeModule::register_erc20(governance, bob()));
+		assert_eq!(EvmBridgeModule::erc20_contract_count(), 2);
+
+		assert_eq!(
+			EvmBridgeModule::has_erc20_balance_weight(),
+			u64::from(QueryGasLimit::get()) * u64::from(MaxErc20Contracts::get())
+		);
+	});
+}
+
+#[test]
+fn queries_should_be_read_only_and_charge_weight() {
+	ExtBuilder::default().build().execute_with(|| {
+		let context = InvokeContext {
+			contract: erc20_address(),
+			source: Default::default(),
+		};
+		let weight = System::block_weight().total();
+
+		assert_eq!(EvmBridgeModule::balance_of(context, alice()), Ok(u128::max_value()));
+		// the nonce of the source isn't increased
+		assert_eq!(
+			module_evm::Module::<Runtime>::account_basic(&H160::default()).nonce,
+			U256::zero()
+		);
+		// the used gas is charged as the weight of the block
+		assert!(System::block_weight().total() > weight);
+	});
+}
+
+#[test]
+fn transact_should_pay_gas_fee_and_fit_in_block() {
+	ExtBuilder::default().build().execute_with(|| {
+		let context = InvokeContext {
+			contract: erc20_address(),
+			source: alice(),
+		};
+		let alice_account = EvmAddressMapping::to_account(&alice());
+		let weight = System::block_weight().total();
+
+		// the source pays the fee of the used gas at the gas price of 1
+		assert_ok!(EvmBridgeModule::transfer(context, bob(), 100));
+		let used_gas = System::block_weight().total() - weight;
+		assert!(used_gas > 0);
+		assert_eq!(
+			Balances::free_balance(&alice_account),
+			INITIAL_BALANCE - u128::from(used_gas)
+		);
+
+		// the source can't pay the fee
+		assert_noop!(
+			EvmBridgeModule::transfer(
+				InvokeContext {
+					contract: erc20_address(),
+					source: H160::from_low_u64_be(3),
+				},
+				bob(),
+				100
+			),
+			pallet_balances::Error::<Runtime>::InsufficientBalance
+		);
+
+		// the gas limit of the transfer exceeds the weight left in the block
+		System::register_extra_weight_unchecked(
+			u64::from(MaximumBlockWeight::get()) - System::block_weight().total() - 1_000_000,
+			DispatchClass::Normal,
+		);
+		assert_noop!(
+			EvmBridgeModule::transfer(context, bob(), 100),
+			Error::<Runtime>::ExceedBlockWeight
+		);
+		assert_eq!(EvmBridgeModule::balance_of(context, bob()), Ok(100));
+	});
+}
+
+#[test]
+fn erc20_allowances_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let ausd = CurrencyId::Token(TokenSymbol::AUSD);
+		let allowance = <EvmBridgeModule as Erc20Allowances<CurrencyId, u128>>::allowance;
+		let set_allowance = <EvmBridgeModule as Erc20Allowances<CurrencyId, u128>>::set_allowance;
+		assert_eq!(allowance(ausd, alice(), bob()), 0);
+
+		set_allowance(ausd, alice(), bob(), 100);
+		assert_eq!(allowance(ausd, alice(), bob()), 100);
+		assert_eq!(allowance(ausd, bob(), alice()), 0);
+		assert_eq!(allowance(CurrencyId::Token(TokenSymbol::DOT), alice(), bob()), 0);
+
+		set_allowance(ausd, alice(), bob(), 0);
+		assert!(!Allowances::<Runtime>::contains_key(ausd, (alice(), bob())));
+	});
+}
@@ -1,15 +1,18 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::all)]
 
-use ethereum_types::H160;
-use primitives::evm::{CallInfo, CreateInfo};
+use ethereum_types::{H160, H256, U256};
+use primitives::evm::{Account, CallInfo, CreateInfo, ExecutionReceipt, TraceResult, TracerKind};
 use sp_runtime::{
 	codec::Codec,
-	traits::{MaybeDisplay, MaybeFromStr},
+	traits::{Block as BlockT, MaybeDisplay, MaybeFromStr},
 };
 use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
+	/// Version 2 adds the calls from `chain_id` for the Ethereum compatible
+	/// RPC, the RPC checks the version before calling them.
+	#[api_version(2)]
 	pub trait EVMRuntimeRPCApi<Balance> where
 		Balance: Codec + MaybeDisplay + MaybeFromStr,
 	{
@@ -29,5 +32,45 @@ sp_api::decl_runtime_apis! {
 			gas_limit: u32,
 			estimate: bool,
 		) -> Result<CreateInfo, sp_runtime::DispatchError>;
+
+		/// The chain id of EVM.
+		fn chain_id() -> u64;
+
+		/// The gas price of EVM calls.
+		fn gas_price() -> U256;
+
+		/// The nonce and free balance of `address`.
+		fn account_basic(address: H160) -> Account;
+
+		/// The code of contract at `address`, empty if not a contract.
+		fn get_code(address: H160) -> Vec<u8>;
+
+		/// The value of storage `index` of contract at `address`.
+		fn get_storage_at(address: H160, index: H256) -> H256;
+
+		/// The receipts of EVM transactions in the block.
+		fn block_receipts() -> Vec<ExecutionReceipt>;
+
+		/// The timestamp of the block, in seconds.
+		fn block_timestamp() -> u64;
+
+		/// Convert the RLP encoded signed Ethereum transaction to an unsigned
+		/// extrinsic, `None` if it can't be decoded.
+		fn convert_transaction(transaction: Vec<u8>) -> Option<<Block as BlockT>::Extrinsic>;
+	}
+
+	/// Replay an extrinsic with the EVM tracer. The tracer is only compiled into
+	/// the native runtime with the `evm-tracing` feature, otherwise an error is
+	/// returned.
+	pub trait EVMTraceApi {
+		/// Replay the extrinsics of the block of `header` and trace the one at
+		/// `index`. Must be called at the parent block.
+		#[skip_initialize_block]
+		fn trace_extrinsic(
+			header: <Block as BlockT>::Header,
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			index: u32,
+			tracer: TracerKind,
+		) -> Result<TraceResult, sp_runtime::DispatchError>;
 	}
 }
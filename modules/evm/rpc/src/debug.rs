@@ -0,0 +1,98 @@
+//! Debug rpc implementation of EVM transactions.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use ethereum_types::H256;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use module_evm::{TraceResult, TracerKind};
+use sc_client_api::BlockBackend;
+use sc_rpc::DenyUnsafe;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT},
+	SaturatedConversion,
+};
+
+use crate::{
+	debug_api::DebugApi as DebugApiT,
+	eth::{find_extrinsic, runtime_err},
+	internal_err,
+	types::{StructLogs, Trace, TraceOptions},
+	EVMTraceApi,
+};
+
+pub struct DebugApi<B, C> {
+	client: Arc<C>,
+	deny_unsafe: DenyUnsafe,
+	_marker: PhantomData<B>,
+}
+
+impl<B, C> DebugApi<B, C> {
+	pub fn new(client: Arc<C>, deny_unsafe: DenyUnsafe) -> Self {
+		Self {
+			client,
+			deny_unsafe,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<B, C> DebugApiT for DebugApi<B, C>
+where
+	B: BlockT<Hash = H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockBackend<B> + Send + Sync + 'static,
+	C::Api: EVMTraceApi<B>,
+{
+	fn trace_transaction(&self, hash: H256, options: Option<TraceOptions>) -> Result<Trace> {
+		// replaying a block is expensive
+		self.deny_unsafe.check_if_safe()?;
+
+		let tracer = match options.and_then(|options| options.tracer).as_deref() {
+			None => TracerKind::OpcodeTracer,
+			Some("callTracer") => TracerKind::CallTracer,
+			Some(tracer) => {
+				return Err(Error {
+					code: ErrorCode::InvalidParams,
+					message: format!("unsupported tracer: {}", tracer),
+					data: None,
+				})
+			}
+		};
+
+		let not_found = || Error {
+			code: ErrorCode::InvalidParams,
+			message: format!("transaction not found: {:?}", hash),
+			data: None,
+		};
+		let (number, index) = find_extrinsic(&*self.client, hash)?.ok_or_else(not_found)?;
+		let block = self
+			.client
+			.block(&BlockId::Number(number.saturated_into()))
+			.map_err(|err| internal_err(format!("fetch block failed: {:?}", err)))?
+			.ok_or_else(not_found)?
+			.block;
+
+		// replay the block on the state of its parent
+		let (header, extrinsics) = block.deconstruct();
+		let parent = BlockId::Hash(*header.parent_hash());
+		let trace = self
+			.client
+			.runtime_api()
+			.trace_extrinsic(&parent, header, extrinsics, index as u32, tracer)
+			.map_err(runtime_err)?
+			.map_err(|err| internal_err(format!("trace failed: {}", Into::<&str>::into(err))))?;
+
+		match trace {
+			TraceResult::Calls(calls) => calls
+				.into_iter()
+				.next()
+				.map(|call| Trace::Call(call.into()))
+				.ok_or_else(|| internal_err("not an EVM transaction")),
+			TraceResult::Steps(steps) => Ok(Trace::Opcodes(StructLogs {
+				struct_logs: steps.into_iter().map(Into::into).collect(),
+			})),
+		}
+	}
+}
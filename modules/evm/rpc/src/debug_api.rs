@@ -0,0 +1,18 @@
+//! Debug rpc interface of EVM transactions.
+
+use ethereum_types::H256;
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+pub use rpc_impl_DebugApi::gen_server::DebugApi as DebugApiServer;
+
+use crate::types::{Trace, TraceOptions};
+
+/// Debug rpc interface of EVM transactions.
+#[rpc(server)]
+pub trait DebugApi {
+	/// Replay the transaction in recent blocks and returns the trace of the
+	/// first EVM execution of the extrinsic.
+	#[rpc(name = "debug_traceTransaction")]
+	fn trace_transaction(&self, _: H256, _: Option<TraceOptions>) -> Result<Trace>;
+}
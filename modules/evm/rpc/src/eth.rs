@@ -0,0 +1,685 @@
+//! Ethereum compatible rpc implementation.
+
+use std::{
+	collections::BTreeMap,
+	marker::PhantomData,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+use ethereum_types::{H160, H256, U256};
+use futures::{compat::Compat, TryFutureExt};
+use jsonrpc_core::{futures::future, BoxFuture, Error, ErrorCode, Result};
+use module_evm::ExecutionReceipt;
+use parking_lot::Mutex;
+use sc_client_api::{AuxStore, BlockBackend};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{
+	codec::Codec,
+	generic::BlockId,
+	traits::{
+		BlakeTwo256, Block as BlockT, Hash, Header as HeaderT, MaybeDisplay, MaybeFromStr, UniqueSaturatedInto, Zero,
+	},
+	SaturatedConversion,
+};
+use sp_transaction_pool::{TransactionPool, TransactionSource};
+
+use crate::{
+	eth_api::EthApi as EthApiT,
+	internal_err,
+	log_index::LogIndex,
+	types::{logs_bloom, Block, BlockNumber, BlockTransactions, Filter, Log, Receipt, Transaction},
+	EVMRuntimeRPCApi, BLOCK_NOT_FOUND_ERROR,
+};
+
+/// The max number of recent blocks searched for a transaction, documented by
+/// `eth_getTransactionReceipt`.
+const MAX_RECEIPT_LOOKBACK: u64 = 256;
+/// The version of `EVMRuntimeRPCApi` with the calls of the Ethereum compatible
+/// RPC.
+const ETH_API_VERSION: u32 = 2;
+/// The max number of installed filters.
+const MAX_FILTERS: usize = 500;
+/// The filters not polled for the duration are uninstalled.
+const FILTER_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+pub(crate) fn runtime_err<T: std::fmt::Debug>(err: T) -> Error {
+	internal_err(format!("runtime error: {:?}", err))
+}
+
+fn to_number<N: UniqueSaturatedInto<u64>>(number: N) -> u64 {
+	number.unique_saturated_into()
+}
+
+/// Whether the runtime at the block has the calls of the Ethereum compatible
+/// RPC, they are added by version `ETH_API_VERSION` of `EVMRuntimeRPCApi`.
+fn has_eth_api<B, C, Balance>(client: &C, id: &BlockId<B>) -> Result<bool>
+where
+	B: BlockT<Hash = H256>,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EVMRuntimeRPCApi<B, Balance>,
+	Balance: Codec,
+{
+	client
+		.runtime_api()
+		.has_api_with::<dyn EVMRuntimeRPCApi<B, Balance, Error = sp_blockchain::Error>, _>(id, |version| {
+			version >= ETH_API_VERSION
+		})
+		.map_err(runtime_err)
+}
+
+/// Returns error if the runtime at the block doesn't have the calls of the
+/// Ethereum compatible RPC.
+fn ensure_eth_api<B, C, Balance>(client: &C, id: &BlockId<B>) -> Result<()>
+where
+	B: BlockT<Hash = H256>,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EVMRuntimeRPCApi<B, Balance>,
+	Balance: Codec,
+{
+	if has_eth_api::<B, C, Balance>(client, id)? {
+		Ok(())
+	} else {
+		Err(internal_err(format!(
+			"the runtime at {:?} doesn't support the Ethereum RPC, EVMRuntimeRPCApi version {} is required",
+			id, ETH_API_VERSION
+		)))
+	}
+}
+
+/// The logs of the transactions, the log index counts from the first
+/// transaction of the block.
+pub(crate) fn transaction_logs(
+	block_hash: H256,
+	block_number: u64,
+	transactions: &[(H256, ExecutionReceipt)],
+) -> Vec<Vec<Log>> {
+	let mut log_index = 0u64;
+	transactions
+		.iter()
+		.enumerate()
+		.map(|(transaction_index, (transaction_hash, receipt))| {
+			receipt
+				.logs
+				.iter()
+				.enumerate()
+				.map(|(transaction_log_index, log)| {
+					log_index += 1;
+					Log {
+						address: log.address,
+						topics: log.topics.clone(),
+						data: Bytes(log.data.clone()),
+						block_hash,
+						block_number: block_number.into(),
+						transaction_hash: *transaction_hash,
+						transaction_index: transaction_index.into(),
+						log_index: (log_index - 1).into(),
+						transaction_log_index: transaction_log_index.into(),
+						removed: false,
+					}
+				})
+				.collect()
+		})
+		.collect()
+}
+
+/// The Ethereum transaction of the EVM transaction at `index` of the block.
+pub(crate) fn build_transaction(
+	block_hash: H256,
+	block_number: u64,
+	index: usize,
+	transaction_hash: H256,
+	receipt: &ExecutionReceipt,
+) -> Transaction {
+	Transaction {
+		hash: transaction_hash,
+		block_hash,
+		block_number: block_number.into(),
+		transaction_index: index.into(),
+		from: receipt.from,
+		to: receipt.to,
+		creates: receipt.contract_address,
+	}
+}
+
+/// The receipt of the EVM transaction at `index` of the block, the cumulative
+/// gas used counts from the first transaction of the block.
+pub(crate) fn build_receipt(
+	block_hash: H256,
+	block_number: u64,
+	transactions: &[(H256, ExecutionReceipt)],
+	index: usize,
+) -> Option<Receipt> {
+	let (transaction_hash, receipt) = transactions.get(index)?;
+	let logs = transaction_logs(block_hash, block_number, transactions)
+		.into_iter()
+		.nth(index)
+		.unwrap_or_default();
+	let cumulative_gas_used = transactions[..=index]
+		.iter()
+		.fold(U256::zero(), |gas, (_, receipt)| gas.saturating_add(receipt.used_gas));
+
+	Some(Receipt {
+		transaction_hash: *transaction_hash,
+		transaction_index: index.into(),
+		block_hash,
+		block_number: block_number.into(),
+		from: receipt.from,
+		to: receipt.to,
+		cumulative_gas_used,
+		gas_used: receipt.used_gas,
+		contract_address: receipt.contract_address,
+		logs_bloom: logs_bloom(logs.iter()),
+		logs,
+		status: if receipt.status { U256::one() } else { U256::zero() },
+	})
+}
+
+/// Find the extrinsic of `hash` in recent blocks, returns the number of the
+/// block and the index of the extrinsic.
+pub(crate) fn find_extrinsic<B, C>(client: &C, hash: H256) -> Result<Option<(u64, usize)>>
+where
+	B: BlockT<Hash = H256>,
+	C: HeaderBackend<B> + BlockBackend<B>,
+{
+	let best_number = to_number(client.info().best_number);
+
+	for number in (best_number.saturating_sub(MAX_RECEIPT_LOOKBACK)..=best_number).rev() {
+		let index = client
+			.block_body(&BlockId::Number(number.saturated_into()))
+			.map_err(|err| internal_err(format!("fetch block failed: {:?}", err)))?
+			.and_then(|extrinsics| {
+				extrinsics
+					.iter()
+					.position(|extrinsic| BlakeTwo256::hash_of(extrinsic) == hash)
+			});
+		if let Some(index) = index {
+			return Ok(Some((number, index)));
+		}
+	}
+
+	Ok(None)
+}
+
+/// The header and the EVM transactions of the block, each transaction is
+/// identified by the hash of its extrinsic. The blocks of the runtimes without
+/// the Ethereum compatible calls have no EVM transactions.
+pub(crate) fn block_transactions<B, C, Balance>(
+	client: &C,
+	id: &BlockId<B>,
+) -> Result<Option<(B::Header, Vec<(H256, ExecutionReceipt)>)>>
+where
+	B: BlockT<Hash = H256>,
+	C: ProvideRuntimeApi<B> + BlockBackend<B>,
+	C::Api: EVMRuntimeRPCApi<B, Balance>,
+	Balance: Codec,
+{
+	let block = match client
+		.block(id)
+		.map_err(|err| internal_err(format!("fetch block failed: {:?}", err)))?
+	{
+		Some(signed_block) => signed_block.block,
+		None => return Ok(None),
+	};
+	let (header, extrinsics) = block.deconstruct();
+	if !has_eth_api::<B, C, Balance>(client, id)? {
+		return Ok(Some((header, vec![])));
+	}
+	let receipts = client.runtime_api().block_receipts(id).map_err(runtime_err)?;
+
+	let transactions = receipts
+		.into_iter()
+		.filter_map(|receipt| {
+			extrinsics
+				.get(receipt.extrinsic_index as usize)
+				.map(|extrinsic| (BlakeTwo256::hash_of(extrinsic), receipt))
+		})
+		.collect();
+
+	Ok(Some((header, transactions)))
+}
+
+/// A filter installed by `eth_newFilter`.
+struct InstalledFilter {
+	filter: Filter,
+	/// The best block when the filter is polled last time.
+	last_block: u64,
+	/// The time when the filter is polled last time.
+	last_poll: Instant,
+}
+
+#[derive(Default)]
+struct Filters {
+	next_id: u64,
+	filters: BTreeMap<U256, InstalledFilter>,
+}
+
+impl Filters {
+	/// Uninstall the filters not polled for `FILTER_TIMEOUT`.
+	fn remove_expired(&mut self) {
+		let now = Instant::now();
+		self.filters
+			.retain(|_, installed| now.duration_since(installed.last_poll) < FILTER_TIMEOUT);
+	}
+
+	/// The filter of the id, polled now.
+	fn poll(&mut self, id: U256) -> Result<&mut InstalledFilter> {
+		self.remove_expired();
+		let installed = self.filters.get_mut(&id).ok_or_else(|| filter_not_found(id))?;
+		installed.last_poll = Instant::now();
+		Ok(installed)
+	}
+}
+
+pub struct EthApi<B, C, P, Balance> {
+	client: Arc<C>,
+	pool: Arc<P>,
+	log_index: LogIndex<B, C, Balance>,
+	filters: Mutex<Filters>,
+	_marker: PhantomData<(B, Balance)>,
+}
+
+impl<B, C, P, Balance> EthApi<B, C, P, Balance> {
+	pub fn new(client: Arc<C>, pool: Arc<P>, log_index: LogIndex<B, C, Balance>) -> Self {
+		Self {
+			client,
+			pool,
+			log_index,
+			filters: Default::default(),
+			_marker: Default::default(),
+		}
+	}
+}
+
+fn filter_not_found(id: U256) -> Error {
+	Error {
+		code: ErrorCode::InvalidParams,
+		message: format!("filter not found: {}", id),
+		data: None,
+	}
+}
+
+impl<B, C, P, Balance> EthApi<B, C, P, Balance>
+where
+	B: BlockT<Hash = H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockBackend<B> + AuxStore + Send + Sync + 'static,
+	C::Api: EVMRuntimeRPCApi<B, Balance>,
+	P: TransactionPool<Block = B, Hash = H256> + 'static,
+	Balance: Codec + MaybeDisplay + MaybeFromStr + Send + Sync + 'static,
+{
+	fn block_id(&self, number: Option<BlockNumber>) -> BlockId<B> {
+		match number.unwrap_or_default() {
+			BlockNumber::Num(number) => BlockId::Number(number.saturated_into()),
+			BlockNumber::Hash(hash) => BlockId::Hash(hash),
+			BlockNumber::Earliest => BlockId::Number(Zero::zero()),
+			BlockNumber::Latest | BlockNumber::Pending => BlockId::Hash(self.client.info().best_hash),
+		}
+	}
+
+	fn ensure_eth_api(&self, id: &BlockId<B>) -> Result<()> {
+		ensure_eth_api::<B, C, Balance>(&*self.client, id)
+	}
+
+	fn best_number(&self) -> u64 {
+		to_number(self.client.info().best_number)
+	}
+
+	/// Resolve the block argument of a filter to the number, `latest` by
+	/// default.
+	fn filter_number(&self, number: Option<BlockNumber>) -> Result<u64> {
+		let best_number = self.best_number();
+		match number.unwrap_or_default() {
+			BlockNumber::Num(number) => Ok(number.min(best_number)),
+			BlockNumber::Hash(hash) => self
+				.client
+				.number(hash)
+				.map_err(|err| internal_err(format!("fetch header failed: {:?}", err)))?
+				.map(to_number)
+				.ok_or_else(|| Error {
+					code: ErrorCode::ServerError(BLOCK_NOT_FOUND_ERROR),
+					message: format!("block not found: {:?}", hash),
+					data: None,
+				}),
+			BlockNumber::Earliest => Ok(0),
+			BlockNumber::Latest | BlockNumber::Pending => Ok(best_number),
+		}
+	}
+
+	fn transactions(&self, id: &BlockId<B>) -> Result<Option<(B::Header, Vec<(H256, ExecutionReceipt)>)>> {
+		block_transactions::<B, C, Balance>(&*self.client, id)
+	}
+
+	fn block(&self, id: BlockId<B>, full: bool) -> Result<Option<Block>> {
+		let (header, transactions) = match self.transactions(&id)? {
+			Some(block) => block,
+			None => return Ok(None),
+		};
+		self.ensure_eth_api(&id)?;
+		let timestamp = self.client.runtime_api().block_timestamp(&id).map_err(runtime_err)?;
+
+		let hash = header.hash();
+		let number = to_number(*header.number());
+		let logs = transaction_logs(hash, number, &transactions);
+		let gas_used = transactions
+			.iter()
+			.fold(U256::zero(), |gas, (_, receipt)| gas.saturating_add(receipt.used_gas));
+
+		let transactions = if full {
+			BlockTransactions::Full(
+				transactions
+					.iter()
+					.enumerate()
+					.map(|(index, (transaction_hash, receipt))| {
+						build_transaction(hash, number, index, *transaction_hash, receipt)
+					})
+					.collect(),
+			)
+		} else {
+			BlockTransactions::Hashes(transactions.into_iter().map(|(hash, _)| hash).collect())
+		};
+
+		Ok(Some(Block {
+			hash,
+			parent_hash: *header.parent_hash(),
+			number: number.into(),
+			state_root: *header.state_root(),
+			transactions_root: *header.extrinsics_root(),
+			logs_bloom: logs_bloom(logs.iter().flatten()),
+			timestamp: timestamp.into(),
+			miner: Default::default(),
+			difficulty: Default::default(),
+			gas_limit: Default::default(),
+			gas_used,
+			extra_data: Bytes(vec![]),
+			uncles: vec![],
+			transactions,
+		}))
+	}
+}
+
+impl<B, C, P, Balance> EthApiT for EthApi<B, C, P, Balance>
+where
+	B: BlockT<Hash = H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockBackend<B> + AuxStore + Send + Sync + 'static,
+	C::Api: EVMRuntimeRPCApi<B, Balance>,
+	P: TransactionPool<Block = B, Hash = H256> + 'static,
+	Balance: Codec + MaybeDisplay + MaybeFromStr + Send + Sync + 'static,
+{
+	fn chain_id(&self) -> Result<U256> {
+		let id = BlockId::Hash(self.client.info().best_hash);
+		self.ensure_eth_api(&id)?;
+		let chain_id = self.client.runtime_api().chain_id(&id).map_err(runtime_err)?;
+		Ok(chain_id.into())
+	}
+
+	fn block_number(&self) -> Result<U256> {
+		Ok(self.best_number().into())
+	}
+
+	fn gas_price(&self) -> Result<U256> {
+		let id = BlockId::Hash(self.client.info().best_hash);
+		self.ensure_eth_api(&id)?;
+		self.client.runtime_api().gas_price(&id).map_err(runtime_err)
+	}
+
+	fn balance(&self, address: H160, number: Option<BlockNumber>) -> Result<U256> {
+		let id = self.block_id(number);
+		self.ensure_eth_api(&id)?;
+		let account = self
+			.client
+			.runtime_api()
+			.account_basic(&id, address)
+			.map_err(runtime_err)?;
+		Ok(account.balance)
+	}
+
+	fn code_at(&self, address: H160, number: Option<BlockNumber>) -> Result<Bytes> {
+		let id = self.block_id(number);
+		self.ensure_eth_api(&id)?;
+		let code = self.client.runtime_api().get_code(&id, address).map_err(runtime_err)?;
+		Ok(Bytes(code))
+	}
+
+	fn storage_at(&self, address: H160, index: U256, number: Option<BlockNumber>) -> Result<H256> {
+		let id = self.block_id(number);
+		self.ensure_eth_api(&id)?;
+		let mut key = [0u8; 32];
+		index.to_big_endian(&mut key);
+		self.client
+			.runtime_api()
+			.get_storage_at(&id, address, H256::from(key))
+			.map_err(runtime_err)
+	}
+
+	fn transaction_count(&self, address: H160, number: Option<BlockNumber>) -> Result<U256> {
+		let id = self.block_id(number);
+		self.ensure_eth_api(&id)?;
+		let account = self
+			.client
+			.runtime_api()
+			.account_basic(&id, address)
+			.map_err(runtime_err)?;
+		Ok(account.nonce)
+	}
+
+	fn block_by_hash(&self, hash: H256, full: bool) -> Result<Option<Block>> {
+		self.block(BlockId::Hash(hash), full)
+	}
+
+	fn block_by_number(&self, number: BlockNumber, full: bool) -> Result<Option<Block>> {
+		self.block(self.block_id(Some(number)), full)
+	}
+
+	fn transaction_receipt(&self, hash: H256) -> Result<Option<Receipt>> {
+		let number = match find_extrinsic(&*self.client, hash)? {
+			Some((number, _)) => number,
+			None => return Ok(None),
+		};
+
+		let (header, transactions) = match self.transactions(&BlockId::Number(number.saturated_into()))? {
+			Some(block) => block,
+			None => return Ok(None),
+		};
+		let index = match transactions
+			.iter()
+			.position(|(transaction_hash, _)| *transaction_hash == hash)
+		{
+			Some(index) => index,
+			// not an EVM transaction
+			None => return Ok(None),
+		};
+
+		Ok(build_receipt(header.hash(), number, &transactions, index))
+	}
+
+	fn logs(&self, filter: Filter) -> Result<Vec<Log>> {
+		if let Some(hash) = filter.block_hash {
+			return self.log_index.logs_of_block(hash, &filter);
+		}
+
+		let from = self.filter_number(filter.from_block)?;
+		let to = self.filter_number(filter.to_block)?;
+		self.log_index.logs(from, to, &filter)
+	}
+
+	fn new_filter(&self, filter: Filter) -> Result<U256> {
+		let mut filters = self.filters.lock();
+		filters.remove_expired();
+		if filters.filters.len() >= MAX_FILTERS {
+			return Err(Error {
+				code: ErrorCode::InvalidParams,
+				message: format!("too many filters, max: {}", MAX_FILTERS),
+				data: None,
+			});
+		}
+
+		let id = U256::from(filters.next_id);
+		filters.next_id = filters.next_id.wrapping_add(1);
+		filters.filters.insert(
+			id,
+			InstalledFilter {
+				filter,
+				last_block: self.best_number(),
+				last_poll: Instant::now(),
+			},
+		);
+		Ok(id)
+	}
+
+	fn filter_changes(&self, id: U256) -> Result<Vec<Log>> {
+		let best_number = self.best_number();
+		let (filter, last_block) = {
+			let mut filters = self.filters.lock();
+			let installed = filters.poll(id)?;
+			(installed.filter.clone(), installed.last_block)
+		};
+		if filter.block_hash.is_some() {
+			return Ok(vec![]);
+		}
+
+		let from = self.filter_number(filter.from_block)?.max(last_block + 1);
+		let to = self.filter_number(filter.to_block)?.min(best_number);
+		let logs = self.log_index.logs(from, to, &filter)?;
+
+		// only advance the filter once the logs are read, a failed poll can be retried
+		if let Some(installed) = self.filters.lock().filters.get_mut(&id) {
+			installed.last_block = installed.last_block.max(best_number);
+		}
+		Ok(logs)
+	}
+
+	fn filter_logs(&self, id: U256) -> Result<Vec<Log>> {
+		let filter = self.filters.lock().poll(id)?.filter.clone();
+		self.logs(filter)
+	}
+
+	fn uninstall_filter(&self, id: U256) -> Result<bool> {
+		Ok(self.filters.lock().filters.remove(&id).is_some())
+	}
+
+	fn send_raw_transaction(&self, transaction: Bytes) -> BoxFuture<H256> {
+		let id = BlockId::Hash(self.client.info().best_hash);
+		let extrinsic = match self.ensure_eth_api(&id).and_then(|_| {
+			self.client
+				.runtime_api()
+				.convert_transaction(&id, transaction.0)
+				.map_err(runtime_err)?
+				.ok_or_else(|| Error {
+					code: ErrorCode::InvalidParams,
+					message: "invalid transaction encoding".into(),
+					data: None,
+				})
+		}) {
+			Ok(extrinsic) => extrinsic,
+			Err(err) => return Box::new(future::err(err)),
+		};
+
+		// the pool validates the signature, chain id, nonce and balance of the sender
+		Box::new(Compat::new(
+			self.pool
+				.submit_one(&id, TransactionSource::External, extrinsic)
+				.map_err(|err| internal_err(format!("submit transaction failed: {:?}", err))),
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use module_evm::Log as EvmLog;
+
+	fn receipt(from: u64, to: Option<u64>, used_gas: u64, logs: usize) -> ExecutionReceipt {
+		ExecutionReceipt {
+			extrinsic_index: 0,
+			from: H160::from_low_u64_be(from),
+			to: to.map(H160::from_low_u64_be),
+			contract_address: if to.is_none() {
+				Some(H160::from_low_u64_be(100))
+			} else {
+				None
+			},
+			status: true,
+			used_gas: used_gas.into(),
+			logs: (0..logs)
+				.map(|_| EvmLog {
+					address: H160::from_low_u64_be(200),
+					topics: vec![],
+					data: vec![],
+				})
+				.collect(),
+		}
+	}
+
+	#[test]
+	fn build_transaction_works() {
+		let block_hash = H256::repeat_byte(1);
+		let transaction = build_transaction(block_hash, 10, 2, H256::repeat_byte(2), &receipt(1, Some(2), 21000, 0));
+		assert_eq!(
+			transaction,
+			Transaction {
+				hash: H256::repeat_byte(2),
+				block_hash,
+				block_number: 10.into(),
+				transaction_index: 2.into(),
+				from: H160::from_low_u64_be(1),
+				to: Some(H160::from_low_u64_be(2)),
+				creates: None,
+			}
+		);
+
+		let transaction = build_transaction(block_hash, 10, 0, H256::repeat_byte(2), &receipt(1, None, 21000, 0));
+		assert_eq!(transaction.to, None);
+		assert_eq!(transaction.creates, Some(H160::from_low_u64_be(100)));
+	}
+
+	#[test]
+	fn build_receipt_works() {
+		let block_hash = H256::repeat_byte(1);
+		let transactions = vec![
+			(H256::repeat_byte(2), receipt(1, Some(2), 21000, 1)),
+			(H256::repeat_byte(3), receipt(3, None, 50000, 2)),
+		];
+
+		let first = build_receipt(block_hash, 10, &transactions, 0).unwrap();
+		assert_eq!(first.from, H160::from_low_u64_be(1));
+		assert_eq!(first.gas_used, 21000.into());
+		assert_eq!(first.cumulative_gas_used, 21000.into());
+		assert_eq!(first.logs.len(), 1);
+		assert_eq!(first.status, U256::one());
+
+		let second = build_receipt(block_hash, 10, &transactions, 1).unwrap();
+		assert_eq!(second.transaction_hash, H256::repeat_byte(3));
+		assert_eq!(second.transaction_index, 1.into());
+		assert_eq!(second.from, H160::from_low_u64_be(3));
+		assert_eq!(second.gas_used, 50000.into());
+		assert_eq!(second.cumulative_gas_used, 71000.into());
+		assert_eq!(second.contract_address, Some(H160::from_low_u64_be(100)));
+		// the log index counts from the first transaction of the block
+		assert_eq!(
+			second.logs.iter().map(|log| log.log_index).collect::<Vec<_>>(),
+			vec![U256::from(1), U256::from(2)]
+		);
+
+		assert_eq!(build_receipt(block_hash, 10, &transactions, 2), None);
+	}
+
+	#[test]
+	fn filters_expire() {
+		let mut filters = Filters::default();
+		let installed = |last_poll: Instant| InstalledFilter {
+			filter: serde_json::from_str("{}").unwrap(),
+			last_block: 0,
+			last_poll,
+		};
+		filters.filters.insert(U256::from(0), installed(Instant::now()));
+		filters
+			.filters
+			.insert(U256::from(1), installed(Instant::now() - FILTER_TIMEOUT));
+
+		assert!(filters.poll(U256::from(0)).is_ok());
+		assert!(filters.poll(U256::from(1)).is_err());
+		assert_eq!(filters.filters.len(), 1);
+	}
+}
@@ -0,0 +1,79 @@
+//! Ethereum compatible rpc interface.
+
+use ethereum_types::{H160, H256, U256};
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+use sp_core::Bytes;
+
+pub use rpc_impl_EthApi::gen_server::EthApi as EthApiServer;
+
+use crate::types::{Block, BlockNumber, Filter, Log, Receipt};
+
+/// Ethereum compatible rpc interface.
+#[rpc(server)]
+pub trait EthApi {
+	/// Returns the chain id of EVM.
+	#[rpc(name = "eth_chainId")]
+	fn chain_id(&self) -> Result<U256>;
+
+	/// Returns the number of the best block.
+	#[rpc(name = "eth_blockNumber")]
+	fn block_number(&self) -> Result<U256>;
+
+	/// Returns the current gas price.
+	#[rpc(name = "eth_gasPrice")]
+	fn gas_price(&self) -> Result<U256>;
+
+	/// Returns the free balance of the account.
+	#[rpc(name = "eth_getBalance")]
+	fn balance(&self, _: H160, _: Option<BlockNumber>) -> Result<U256>;
+
+	/// Returns the code of the contract.
+	#[rpc(name = "eth_getCode")]
+	fn code_at(&self, _: H160, _: Option<BlockNumber>) -> Result<Bytes>;
+
+	/// Returns the value of the storage of the contract.
+	#[rpc(name = "eth_getStorageAt")]
+	fn storage_at(&self, _: H160, _: U256, _: Option<BlockNumber>) -> Result<H256>;
+
+	/// Returns the nonce of the account.
+	#[rpc(name = "eth_getTransactionCount")]
+	fn transaction_count(&self, _: H160, _: Option<BlockNumber>) -> Result<U256>;
+
+	/// Returns the block of the hash, with full transactions if `true`.
+	#[rpc(name = "eth_getBlockByHash")]
+	fn block_by_hash(&self, _: H256, _: bool) -> Result<Option<Block>>;
+
+	/// Returns the block of the number, with full transactions if `true`.
+	#[rpc(name = "eth_getBlockByNumber")]
+	fn block_by_number(&self, _: BlockNumber, _: bool) -> Result<Option<Block>>;
+
+	/// Returns the receipt of the transaction in the latest 256 blocks, older
+	/// transactions are not searched and return `null`.
+	#[rpc(name = "eth_getTransactionReceipt")]
+	fn transaction_receipt(&self, _: H256) -> Result<Option<Receipt>>;
+
+	/// Returns the logs matching the filter.
+	#[rpc(name = "eth_getLogs")]
+	fn logs(&self, _: Filter) -> Result<Vec<Log>>;
+
+	/// Install the log filter, returns the id of the filter.
+	#[rpc(name = "eth_newFilter")]
+	fn new_filter(&self, _: Filter) -> Result<U256>;
+
+	/// Returns the logs of the filter in the blocks since the last poll.
+	#[rpc(name = "eth_getFilterChanges")]
+	fn filter_changes(&self, _: U256) -> Result<Vec<Log>>;
+
+	/// Returns all the logs of the filter.
+	#[rpc(name = "eth_getFilterLogs")]
+	fn filter_logs(&self, _: U256) -> Result<Vec<Log>>;
+
+	/// Uninstall the filter, returns `true` if the filter is found.
+	#[rpc(name = "eth_uninstallFilter")]
+	fn uninstall_filter(&self, _: U256) -> Result<bool>;
+
+	/// Submit the RLP encoded signed transaction, returns the extrinsic hash.
+	#[rpc(name = "eth_sendRawTransaction")]
+	fn send_raw_transaction(&self, _: Bytes) -> BoxFuture<H256>;
+}
@@ -7,16 +7,18 @@ use sp_core::Bytes;
 
 pub use rpc_impl_EVMApi::gen_server::EVMApi as EVMApiServer;
 
-use crate::call_request::CallRequest;
+use crate::{call_request::CallRequest, types::BlockNumber};
 
 /// EVM rpc interface.
 #[rpc(server)]
-pub trait EVMApi<BlockHash> {
-	/// Call contract, returning the output data.
+pub trait EVMApi {
+	/// Call contract at the block of the number or hash, returning the output
+	/// data.
 	#[rpc(name = "evm_call")]
-	fn call(&self, _: CallRequest, _: Option<BlockHash>) -> Result<Bytes>;
+	fn call(&self, _: CallRequest, _: Option<BlockNumber>) -> Result<Bytes>;
 
-	/// Estimate gas needed for execution of given contract.
+	/// Estimate gas needed for execution of given contract at the block of the
+	/// number or hash.
 	#[rpc(name = "evm_estimateGas")]
-	fn estimate_gas(&self, _: CallRequest, _: Option<BlockHash>) -> Result<U256>;
+	fn estimate_gas(&self, _: CallRequest, _: Option<BlockNumber>) -> Result<U256>;
 }
@@ -1,8 +1,9 @@
 use std::{marker::PhantomData, sync::Arc};
 
-use ethereum_types::U256;
+use ethereum_types::{H256, U256};
 use jsonrpc_core::{Error, ErrorCode, Result, Value};
 use rustc_hex::ToHex;
+use sc_client_api::Backend;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_core::Bytes;
@@ -10,19 +11,36 @@ use sp_rpc::number::NumberOrHex;
 use sp_runtime::{
 	codec::Codec,
 	generic::BlockId,
-	traits::{Block as BlockT, MaybeDisplay, MaybeFromStr},
+	traits::{Block as BlockT, Header as HeaderT, MaybeDisplay, MaybeFromStr, Zero},
 	SaturatedConversion,
 };
 use std::convert::{TryFrom, TryInto};
 
 use call_request::CallRequest;
 pub use module_evm::ExitReason;
-pub use module_evm_rpc_runtime_api::EVMRuntimeRPCApi;
+pub use module_evm_rpc_runtime_api::{EVMRuntimeRPCApi, EVMTraceApi};
+use types::BlockNumber;
 
+pub use crate::debug::DebugApi;
+pub use crate::debug_api::{DebugApi as DebugApiT, DebugApiServer};
+pub use crate::eth::EthApi;
+pub use crate::eth_api::{EthApi as EthApiT, EthApiServer};
 pub use crate::evm_api::{EVMApi as EVMApiT, EVMApiServer};
+pub use crate::log_index::LogIndex;
 
 mod call_request;
+mod debug;
+mod debug_api;
+mod eth;
+mod eth_api;
 mod evm_api;
+mod log_index;
+pub mod types;
+
+/// The block of the argument is not found.
+const BLOCK_NOT_FOUND_ERROR: i64 = -32001;
+/// The state of the block is pruned.
+const STATE_UNAVAILABLE_ERROR: i64 = -32002;
 
 fn internal_err<T: ToString>(message: T) -> Error {
 	Error {
@@ -32,6 +50,42 @@ fn internal_err<T: ToString>(message: T) -> Error {
 	}
 }
 
+/// Resolve the block argument to the hash of the block, `latest` by
+/// default. Returns error if the block is not found or its state is pruned.
+fn block_hash_with_state<B, C, BE>(client: &C, backend: &BE, number: Option<BlockNumber>) -> Result<H256>
+where
+	B: BlockT<Hash = H256>,
+	C: HeaderBackend<B>,
+	BE: Backend<B>,
+{
+	let id = match number.unwrap_or_default() {
+		BlockNumber::Num(number) => BlockId::Number(number.saturated_into()),
+		BlockNumber::Hash(hash) => BlockId::Hash(hash),
+		BlockNumber::Earliest => BlockId::Number(Zero::zero()),
+		BlockNumber::Latest | BlockNumber::Pending => BlockId::Hash(client.info().best_hash),
+	};
+
+	let header = client
+		.header(id)
+		.map_err(|err| internal_err(format!("fetch header failed: {:?}", err)))?
+		.ok_or_else(|| Error {
+			code: ErrorCode::ServerError(BLOCK_NOT_FOUND_ERROR),
+			message: format!("block not found: {:?}", number.unwrap_or_default()),
+			data: None,
+		})?;
+
+	let hash = header.hash();
+	if !backend.have_state_at(&hash, *header.number()) {
+		return Err(Error {
+			code: ErrorCode::ServerError(STATE_UNAVAILABLE_ERROR),
+			message: format!("state is pruned at block: {:?}", hash),
+			data: None,
+		});
+	}
+
+	Ok(hash)
+}
+
 #[allow(dead_code)]
 fn error_on_execution_failure(reason: &ExitReason, data: &[u8]) -> Result<()> {
 	match reason {
@@ -73,15 +127,17 @@ fn decode_revert_message(data: &[u8]) -> Option<String> {
 	None
 }
 
-pub struct EVMApi<B, C, Balance> {
+pub struct EVMApi<B, C, BE, Balance> {
 	client: Arc<C>,
+	backend: Arc<BE>,
 	_marker: PhantomData<(B, Balance)>,
 }
 
-impl<B, C, Balance> EVMApi<B, C, Balance> {
-	pub fn new(client: Arc<C>) -> Self {
+impl<B, C, BE, Balance> EVMApi<B, C, BE, Balance> {
+	pub fn new(client: Arc<C>, backend: Arc<BE>) -> Self {
 		Self {
 			client,
+			backend,
 			_marker: Default::default(),
 		}
 	}
@@ -91,15 +147,16 @@ fn to_u128(val: NumberOrHex) -> std::result::Result<u128, ()> {
 	val.into_u256().try_into().map_err(|_| ())
 }
 
-impl<B, C, Balance> EVMApiT<B> for EVMApi<B, C, Balance>
+impl<B, C, BE, Balance> EVMApiT for EVMApi<B, C, BE, Balance>
 where
-	B: BlockT,
+	B: BlockT<Hash = H256>,
 	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
 	C::Api: EVMRuntimeRPCApi<B, Balance>,
+	BE: Backend<B> + 'static,
 	Balance: Codec + MaybeDisplay + MaybeFromStr + Default + Send + Sync + 'static + TryFrom<u128>,
 {
-	fn call(&self, request: CallRequest, _: Option<B>) -> Result<Bytes> {
-		let hash = self.client.info().best_hash;
+	fn call(&self, request: CallRequest, number: Option<BlockNumber>) -> Result<Bytes> {
+		let hash = block_hash_with_state(&*self.client, &*self.backend, number)?;
 
 		let CallRequest {
 			from,
@@ -165,8 +222,8 @@ where
 		}
 	}
 
-	fn estimate_gas(&self, request: CallRequest, _: Option<B>) -> Result<U256> {
-		let hash = self.client.info().best_hash;
+	fn estimate_gas(&self, request: CallRequest, number: Option<BlockNumber>) -> Result<U256> {
+		let hash = block_hash_with_state(&*self.client, &*self.backend, number)?;
 
 		let CallRequest {
 			from,
@@ -0,0 +1,421 @@
+//! Client-side index of the EVM logs.
+//!
+//! The logs of every imported block are stored in the aux storage of the
+//! client, with the numbers of the blocks containing logs of each address and
+//! topic. A filter only reads the logs of the candidate blocks, the blocks of
+//! forks are dropped by checking the canonical hash of the number. The
+//! canonical blocks missed by the index, e.g. imported while the index isn't
+//! running, are indexed on start and before the next imported block.
+
+use std::{collections::BTreeSet, marker::PhantomData, sync::Arc};
+
+use codec::{Decode, Encode};
+use ethereum_types::{H160, H256};
+use futures::StreamExt;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use module_evm_rpc_runtime_api::EVMRuntimeRPCApi;
+use sc_client_api::{AuxStore, BlockBackend, BlockchainEvents};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{
+	codec::Codec,
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto},
+	SaturatedConversion,
+};
+
+use crate::{
+	eth::{block_transactions, transaction_logs},
+	internal_err,
+	types::{Filter, Log},
+};
+
+/// The max number of blocks read by a filter.
+pub const MAX_FILTER_BLOCKS: usize = 1024;
+
+const LOGS_PREFIX: &[u8] = b"evm_log_index:logs:";
+const ADDRESS_PREFIX: &[u8] = b"evm_log_index:address:";
+const TOPIC_PREFIX: &[u8] = b"evm_log_index:topic:";
+/// The number of the first indexed block.
+const START_KEY: &[u8] = b"evm_log_index:start";
+/// The highest number of the indexed blocks.
+const LAST_KEY: &[u8] = b"evm_log_index:last";
+
+fn key(prefix: &[u8], value: &[u8]) -> Vec<u8> {
+	let mut key = prefix.to_vec();
+	key.extend_from_slice(value);
+	key
+}
+
+/// A log stored in the index, without the block.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+struct IndexedLog {
+	address: H160,
+	topics: Vec<H256>,
+	data: Vec<u8>,
+	transaction_hash: H256,
+	transaction_index: u32,
+	log_index: u32,
+	transaction_log_index: u32,
+}
+
+impl IndexedLog {
+	fn into_log(self, block_hash: H256, block_number: u64) -> Log {
+		Log {
+			address: self.address,
+			topics: self.topics,
+			data: Bytes(self.data),
+			block_hash,
+			block_number: block_number.into(),
+			transaction_hash: self.transaction_hash,
+			transaction_index: self.transaction_index.into(),
+			log_index: self.log_index.into(),
+			transaction_log_index: self.transaction_log_index.into(),
+			removed: false,
+		}
+	}
+}
+
+impl From<Log> for IndexedLog {
+	fn from(log: Log) -> Self {
+		Self {
+			address: log.address,
+			topics: log.topics,
+			data: log.data.0,
+			transaction_hash: log.transaction_hash,
+			transaction_index: log.transaction_index.low_u32(),
+			log_index: log.log_index.low_u32(),
+			transaction_log_index: log.transaction_log_index.low_u32(),
+		}
+	}
+}
+
+/// The candidate blocks in `from..=to` of the keys, `None` if any block is
+/// a candidate.
+fn candidates(
+	from: u64,
+	to: u64,
+	filter: &Filter,
+	numbers_of: impl Fn(&[u8], &[u8]) -> Result<Vec<u64>>,
+) -> Result<Option<BTreeSet<u64>>> {
+	let mut sets: Vec<BTreeSet<u64>> = vec![];
+
+	let mut union = |prefix: &[u8], values: Vec<&[u8]>| -> Result<()> {
+		// empty matches any value
+		if values.is_empty() {
+			return Ok(());
+		}
+		let mut set = BTreeSet::new();
+		for value in values {
+			set.extend(
+				numbers_of(prefix, value)?
+					.into_iter()
+					.filter(|number| (from..=to).contains(number)),
+			);
+		}
+		sets.push(set);
+		Ok(())
+	};
+
+	if let Some(address) = &filter.address {
+		union(ADDRESS_PREFIX, address.values().iter().map(|a| &a[..]).collect())?;
+	}
+	for topic in filter.topics.iter().flatten().flatten() {
+		union(TOPIC_PREFIX, topic.values().iter().map(|t| &t[..]).collect())?;
+	}
+
+	Ok(sets
+		.into_iter()
+		.fold(None, |result: Option<BTreeSet<u64>>, set| match result {
+			Some(result) => Some(result.intersection(&set).cloned().collect()),
+			None => Some(set),
+		}))
+}
+
+/// Index of the EVM logs by address and topics.
+pub struct LogIndex<B, C, Balance> {
+	client: Arc<C>,
+	_marker: PhantomData<(B, Balance)>,
+}
+
+impl<B, C, Balance> Clone for LogIndex<B, C, Balance> {
+	fn clone(&self) -> Self {
+		Self {
+			client: self.client.clone(),
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<B, C, Balance> LogIndex<B, C, Balance> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<B, C, Balance> LogIndex<B, C, Balance>
+where
+	B: BlockT<Hash = H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockBackend<B> + AuxStore + Send + Sync + 'static,
+	C::Api: EVMRuntimeRPCApi<B, Balance>,
+	Balance: Codec + Send + Sync + 'static,
+{
+	/// Index the logs of the imported blocks, runs until the client stops.
+	pub async fn run(self)
+	where
+		C: BlockchainEvents<B>,
+	{
+		let mut notifications = self.client.import_notification_stream();
+		let best_number = UniqueSaturatedInto::<u64>::unique_saturated_into(self.client.info().best_number);
+		if let Err(err) = self.catch_up(best_number) {
+			log::warn!(target: "evm-log-index", "index blocks up to {:?} failed: {:?}", best_number, err);
+		}
+
+		while let Some(notification) = notifications.next().await {
+			let number = UniqueSaturatedInto::<u64>::unique_saturated_into(*notification.header.number());
+			if let Err(err) = self
+				.catch_up(number.saturating_sub(1))
+				.and_then(|_| self.index_block(notification.hash))
+			{
+				log::warn!(target: "evm-log-index", "index block {:?} failed: {:?}", notification.hash, err);
+			}
+		}
+	}
+
+	/// Index the canonical blocks after the last indexed block up to `to`.
+	pub fn catch_up(&self, to: u64) -> Result<()> {
+		// nothing is indexed, the index starts from the next imported block
+		let last = match self.aux::<u64>(LAST_KEY)? {
+			Some(last) => last,
+			None => return Ok(()),
+		};
+
+		for number in last.saturating_add(1)..=to {
+			match self
+				.client
+				.hash(number.saturated_into())
+				.map_err(|err| internal_err(format!("fetch header failed: {:?}", err)))?
+			{
+				Some(hash) => self.index_block(hash)?,
+				None => break,
+			}
+		}
+		Ok(())
+	}
+
+	fn aux<T: Decode>(&self, key: &[u8]) -> Result<Option<T>> {
+		match self
+			.client
+			.get_aux(key)
+			.map_err(|err| internal_err(format!("read log index failed: {:?}", err)))?
+		{
+			Some(value) => T::decode(&mut &value[..])
+				.map(Some)
+				.map_err(|err| internal_err(format!("decode log index failed: {:?}", err))),
+			None => Ok(None),
+		}
+	}
+
+	fn numbers_of(&self, prefix: &[u8], value: &[u8]) -> Result<Vec<u64>> {
+		Ok(self.aux(&key(prefix, value))?.unwrap_or_default())
+	}
+
+	/// The logs of the block, read from the block if it's not indexed.
+	fn block_logs(&self, hash: H256) -> Result<Option<Vec<IndexedLog>>> {
+		if let Some(logs) = self.aux(&key(LOGS_PREFIX, &hash[..]))? {
+			return Ok(Some(logs));
+		}
+
+		Ok(
+			block_transactions::<B, C, Balance>(&*self.client, &BlockId::Hash(hash))?.map(|(header, transactions)| {
+				let number = UniqueSaturatedInto::<u64>::unique_saturated_into(*header.number());
+				transaction_logs(hash, number, &transactions)
+					.into_iter()
+					.flatten()
+					.map(Into::into)
+					.collect()
+			}),
+		)
+	}
+
+	/// Store the logs of the block and add the block to the lists of the
+	/// addresses and topics.
+	pub fn index_block(&self, hash: H256) -> Result<()> {
+		let (header, transactions) = match block_transactions::<B, C, Balance>(&*self.client, &BlockId::Hash(hash))? {
+			Some(block) => block,
+			None => return Ok(()),
+		};
+		let number = UniqueSaturatedInto::<u64>::unique_saturated_into(*header.number());
+		let logs: Vec<IndexedLog> = transaction_logs(hash, number, &transactions)
+			.into_iter()
+			.flatten()
+			.map(Into::into)
+			.collect();
+
+		let mut keys = BTreeSet::new();
+		for log in logs.iter() {
+			keys.insert(key(ADDRESS_PREFIX, &log.address[..]));
+			for topic in log.topics.iter() {
+				keys.insert(key(TOPIC_PREFIX, &topic[..]));
+			}
+		}
+
+		let mut insert = vec![(key(LOGS_PREFIX, &hash[..]), logs.encode())];
+		for index_key in keys {
+			let mut numbers: Vec<u64> = self.aux(&index_key)?.unwrap_or_default();
+			// the blocks of forks can have the same number
+			if numbers.last() != Some(&number) {
+				numbers.push(number);
+				numbers.sort_unstable();
+				numbers.dedup();
+			}
+			insert.push((index_key, numbers.encode()));
+		}
+		if self.aux::<u64>(START_KEY)?.is_none() {
+			insert.push((START_KEY.to_vec(), number.encode()));
+		}
+		if self.aux::<u64>(LAST_KEY)?.map_or(true, |last| number > last) {
+			insert.push((LAST_KEY.to_vec(), number.encode()));
+		}
+
+		self.client
+			.insert_aux(
+				insert
+					.iter()
+					.map(|(key, value)| (&key[..], &value[..]))
+					.collect::<Vec<_>>()
+					.iter(),
+				std::iter::empty(),
+			)
+			.map_err(|err| internal_err(format!("write log index failed: {:?}", err)))
+	}
+
+	/// The logs of the block `hash` matching the filter.
+	pub fn logs_of_block(&self, hash: H256, filter: &Filter) -> Result<Vec<Log>> {
+		let number = match self
+			.client
+			.number(hash)
+			.map_err(|err| internal_err(format!("fetch header failed: {:?}", err)))?
+		{
+			Some(number) => UniqueSaturatedInto::<u64>::unique_saturated_into(number),
+			None => return Ok(vec![]),
+		};
+
+		Ok(self
+			.block_logs(hash)?
+			.unwrap_or_default()
+			.into_iter()
+			.filter(|log| filter.matches(&log.address, &log.topics))
+			.map(|log| log.into_log(hash, number))
+			.collect())
+	}
+
+	/// The logs of the canonical blocks in `from..=to` matching the filter.
+	pub fn logs(&self, from: u64, to: u64, filter: &Filter) -> Result<Vec<Log>> {
+		let too_many_blocks = |count: u64| -> Result<()> {
+			if count > MAX_FILTER_BLOCKS as u64 {
+				return Err(Error {
+					code: ErrorCode::InvalidParams,
+					message: format!("query returned more than {} blocks", MAX_FILTER_BLOCKS),
+					data: None,
+				});
+			}
+			Ok(())
+		};
+		if from > to {
+			return Ok(vec![]);
+		}
+
+		let start = self.aux::<u64>(START_KEY)?.unwrap_or(u64::max_value());
+		let mut numbers = BTreeSet::new();
+		// the blocks before the index are all candidates
+		if from < start {
+			let unindexed_to = to.min(start - 1);
+			too_many_blocks(unindexed_to - from + 1)?;
+			numbers.extend(from..=unindexed_to);
+		}
+		if to >= start {
+			let indexed_from = from.max(start);
+			match candidates(indexed_from, to, filter, |prefix, value| self.numbers_of(prefix, value))? {
+				Some(indexed) => numbers.extend(indexed),
+				None => {
+					too_many_blocks(to - indexed_from + 1)?;
+					numbers.extend(indexed_from..=to);
+				}
+			}
+		}
+		too_many_blocks(numbers.len() as u64)?;
+
+		let mut result = vec![];
+		for number in numbers {
+			let hash = match self
+				.client
+				.hash(number.saturated_into())
+				.map_err(|err| internal_err(format!("fetch header failed: {:?}", err)))?
+			{
+				Some(hash) => hash,
+				None => continue,
+			};
+			if let Some(logs) = self.block_logs(hash)? {
+				result.extend(
+					logs.into_iter()
+						.filter(|log| filter.matches(&log.address, &log.topics))
+						.map(|log| log.into_log(hash, number)),
+				);
+			}
+		}
+
+		Ok(result)
+	}
+}
+
+#[test]
+fn candidates_should_work() {
+	use std::collections::BTreeMap;
+
+	let address = H160::from_low_u64_be(1);
+	let topic = H256::from_low_u64_be(2);
+	let mut index = BTreeMap::new();
+	index.insert(key(ADDRESS_PREFIX, &address[..]), vec![1u64, 3, 5, 7]);
+	index.insert(key(TOPIC_PREFIX, &topic[..]), vec![3u64, 4, 7]);
+	let numbers_of = |prefix: &[u8], value: &[u8]| Ok(index.get(&key(prefix, value)).cloned().unwrap_or_default());
+	let filter = |json: &str| -> Filter { serde_json::from_str(json).unwrap() };
+
+	assert_eq!(candidates(0, 10, &filter("{}"), numbers_of).unwrap(), None);
+	assert_eq!(
+		candidates(
+			2,
+			10,
+			&filter(r#"{"address":"0x0000000000000000000000000000000000000001"}"#),
+			numbers_of
+		)
+		.unwrap(),
+		Some(vec![3, 5, 7].into_iter().collect())
+	);
+	assert_eq!(
+		candidates(
+			0,
+			10,
+			&filter(
+				r#"{"address":"0x0000000000000000000000000000000000000001","topics":[null,"0x0000000000000000000000000000000000000000000000000000000000000002"]}"#
+			),
+			numbers_of
+		)
+		.unwrap(),
+		Some(vec![3, 7].into_iter().collect())
+	);
+	assert_eq!(
+		candidates(
+			0,
+			10,
+			&filter(r#"{"address":["0x0000000000000000000000000000000000000001","0x0000000000000000000000000000000000000009"],"topics":[["0x0000000000000000000000000000000000000000000000000000000000000003"]]}"#),
+			numbers_of
+		)
+		.unwrap(),
+		Some(BTreeSet::new())
+	);
+}
@@ -0,0 +1,383 @@
+//! Ethereum JSON-RPC types.
+
+use ethereum_types::{Bloom, BloomInput, H160, H256, U256};
+use module_evm::{CallTrace, CallType, ExitReason, TraceStep};
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize};
+use sp_core::Bytes;
+
+/// The block argument of eth rpc.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockNumber {
+	/// The block of the number.
+	Num(u64),
+	/// The block of the hash.
+	Hash(H256),
+	/// The best block.
+	Latest,
+	/// The genesis block.
+	Earliest,
+	/// The best block, there is no pending block.
+	Pending,
+}
+
+impl Default for BlockNumber {
+	fn default() -> Self {
+		BlockNumber::Latest
+	}
+}
+
+impl<'de> Deserialize<'de> for BlockNumber {
+	fn deserialize<D>(deserializer: D) -> Result<BlockNumber, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let value = String::deserialize(deserializer)?;
+		match value.as_str() {
+			"latest" => Ok(BlockNumber::Latest),
+			"earliest" => Ok(BlockNumber::Earliest),
+			"pending" => Ok(BlockNumber::Pending),
+			_ => {
+				let invalid = || D::Error::custom(format!("invalid block number or hash: {}", value));
+				let hex = value.strip_prefix("0x").ok_or_else(invalid)?;
+				if hex.len() == 64 {
+					hex.parse::<H256>().map(BlockNumber::Hash).map_err(|_| invalid())
+				} else {
+					u64::from_str_radix(hex, 16)
+						.map(BlockNumber::Num)
+						.map_err(|_| invalid())
+				}
+			}
+		}
+	}
+}
+
+/// The transactions of a block, hashes only or full transactions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum BlockTransactions {
+	Hashes(Vec<H256>),
+	Full(Vec<Transaction>),
+}
+
+/// An Ethereum block. The EVM transactions of the substrate block are the
+/// transactions, fields without substrate counterparts are zero.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Block {
+	pub hash: H256,
+	pub parent_hash: H256,
+	pub number: U256,
+	pub state_root: H256,
+	pub transactions_root: H256,
+	pub logs_bloom: Bloom,
+	pub timestamp: U256,
+	pub miner: H160,
+	pub difficulty: U256,
+	pub gas_limit: U256,
+	pub gas_used: U256,
+	pub extra_data: Bytes,
+	pub uncles: Vec<H256>,
+	pub transactions: BlockTransactions,
+}
+
+/// An EVM transaction. The hash is the extrinsic hash.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+	pub hash: H256,
+	pub block_hash: H256,
+	pub block_number: U256,
+	pub transaction_index: U256,
+	pub from: H160,
+	pub to: Option<H160>,
+	pub creates: Option<H160>,
+}
+
+/// A log emitted by contract.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Log {
+	pub address: H160,
+	pub topics: Vec<H256>,
+	pub data: Bytes,
+	pub block_hash: H256,
+	pub block_number: U256,
+	pub transaction_hash: H256,
+	pub transaction_index: U256,
+	/// The index of the log in the block.
+	pub log_index: U256,
+	/// The index of the log in the transaction.
+	pub transaction_log_index: U256,
+	pub removed: bool,
+}
+
+/// The receipt of an EVM transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Receipt {
+	pub transaction_hash: H256,
+	pub transaction_index: U256,
+	pub block_hash: H256,
+	pub block_number: U256,
+	pub from: H160,
+	pub to: Option<H160>,
+	pub cumulative_gas_used: U256,
+	pub gas_used: U256,
+	pub contract_address: Option<H160>,
+	pub logs: Vec<Log>,
+	pub logs_bloom: Bloom,
+	/// 1 for success, 0 for failure.
+	pub status: U256,
+}
+
+/// One or more values of a filter field.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum VariadicValue<T> {
+	Single(T),
+	Multiple(Vec<T>),
+}
+
+impl<T: PartialEq> VariadicValue<T> {
+	/// The values, empty matches any value.
+	pub(crate) fn values(&self) -> &[T] {
+		match self {
+			VariadicValue::Single(v) => std::slice::from_ref(v),
+			VariadicValue::Multiple(values) => values,
+		}
+	}
+
+	fn contains(&self, value: &T) -> bool {
+		match self {
+			VariadicValue::Single(v) => v == value,
+			VariadicValue::Multiple(values) => values.is_empty() || values.contains(value),
+		}
+	}
+}
+
+/// The filter of `eth_getLogs`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Filter {
+	pub from_block: Option<BlockNumber>,
+	pub to_block: Option<BlockNumber>,
+	/// Only the logs of the block, exclusive with `from_block` and `to_block`.
+	pub block_hash: Option<H256>,
+	pub address: Option<VariadicValue<H160>>,
+	/// The topics by position, `None` matches any topic.
+	pub topics: Option<Vec<Option<VariadicValue<H256>>>>,
+}
+
+impl Filter {
+	/// Whether the log of contract `address` with `topics` matches the filter.
+	pub fn matches(&self, address: &H160, topics: &[H256]) -> bool {
+		if let Some(filter_address) = &self.address {
+			if !filter_address.contains(address) {
+				return false;
+			}
+		}
+
+		if let Some(filter_topics) = &self.topics {
+			for (position, filter_topic) in filter_topics.iter().enumerate() {
+				if let Some(filter_topic) = filter_topic {
+					match topics.get(position) {
+						Some(topic) if filter_topic.contains(topic) => {}
+						_ => return false,
+					}
+				}
+			}
+		}
+
+		true
+	}
+}
+
+/// The bloom filter of the logs.
+pub fn logs_bloom<'a>(logs: impl IntoIterator<Item = &'a Log>) -> Bloom {
+	let mut bloom = Bloom::default();
+	for log in logs {
+		bloom.accrue(BloomInput::Raw(&log.address[..]));
+		for topic in log.topics.iter() {
+			bloom.accrue(BloomInput::Raw(&topic[..]));
+		}
+	}
+	bloom
+}
+
+/// The options of `debug_traceTransaction`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceOptions {
+	/// `callTracer` for the call tree, the opcodes are traced by default.
+	pub tracer: Option<String>,
+}
+
+/// A call frame of `callTracer`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+	/// `CALL`, `STATICCALL`, `DELEGATECALL` or `CREATE`.
+	#[serde(rename = "type")]
+	pub call_type: String,
+	pub from: H160,
+	pub to: H160,
+	pub value: U256,
+	pub gas: U256,
+	pub gas_used: U256,
+	pub input: Bytes,
+	pub output: Bytes,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub calls: Vec<CallFrame>,
+}
+
+impl From<CallTrace> for CallFrame {
+	fn from(trace: CallTrace) -> Self {
+		let call_type = match trace.call_type {
+			CallType::Call => "CALL",
+			CallType::StaticCall => "STATICCALL",
+			CallType::DelegateCall => "DELEGATECALL",
+			CallType::Create => "CREATE",
+		};
+		let error = match trace.exit_reason {
+			Some(ExitReason::Succeed(_)) => None,
+			Some(ExitReason::Revert(_)) => Some("execution reverted".into()),
+			Some(ExitReason::Error(e)) => Some(format!("{:?}", e)),
+			Some(ExitReason::Fatal(e)) => Some(format!("{:?}", e)),
+			None => Some("execution aborted".into()),
+		};
+
+		CallFrame {
+			call_type: call_type.into(),
+			from: trace.from,
+			to: trace.to,
+			value: trace.value,
+			gas: trace.gas.into(),
+			gas_used: trace.gas_used.into(),
+			input: Bytes(trace.input),
+			output: Bytes(trace.output),
+			error,
+			calls: trace.calls.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// An executed opcode. The program counter and memory are not recorded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+	pub depth: u32,
+	pub op: String,
+	pub gas: u64,
+	pub gas_cost: u64,
+	pub stack: Vec<H256>,
+}
+
+impl From<TraceStep> for StructLog {
+	fn from(step: TraceStep) -> Self {
+		StructLog {
+			depth: step.depth,
+			op: String::from_utf8_lossy(&step.op).into_owned(),
+			gas: step.gas,
+			gas_cost: step.gas_cost,
+			stack: step.stack,
+		}
+	}
+}
+
+/// The opcodes of the transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogs {
+	pub struct_logs: Vec<StructLog>,
+}
+
+/// The result of `debug_traceTransaction`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Trace {
+	Call(CallFrame),
+	Opcodes(StructLogs),
+}
+
+#[test]
+fn deserialize_block_number_should_work() {
+	let parse = |value: &str| serde_json::from_str::<BlockNumber>(value);
+	assert_eq!(parse(r#""latest""#).unwrap(), BlockNumber::Latest);
+	assert_eq!(parse(r#""earliest""#).unwrap(), BlockNumber::Earliest);
+	assert_eq!(parse(r#""pending""#).unwrap(), BlockNumber::Pending);
+	assert_eq!(parse(r#""0x1f""#).unwrap(), BlockNumber::Num(31));
+	assert_eq!(
+		parse(r#""0x0000000000000000000000000000000000000000000000000000000000000001""#).unwrap(),
+		BlockNumber::Hash(H256::from_low_u64_be(1))
+	);
+	assert!(parse(r#""1f""#).is_err());
+	assert!(parse(r#""0xzz""#).is_err());
+}
+
+#[test]
+fn filter_matches_should_work() {
+	let address = H160::from_low_u64_be(1);
+	let topics = vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)];
+
+	let filter: Filter = serde_json::from_str(r#"{"fromBlock":"0x1","toBlock":"latest"}"#).unwrap();
+	assert_eq!(filter.from_block, Some(BlockNumber::Num(1)));
+	assert!(filter.matches(&address, &topics));
+
+	let filter: Filter = serde_json::from_str(
+		r#"{"address":["0x0000000000000000000000000000000000000001","0x0000000000000000000000000000000000000002"]}"#,
+	)
+	.unwrap();
+	assert!(filter.matches(&address, &topics));
+	assert!(!filter.matches(&H160::from_low_u64_be(3), &topics));
+
+	let filter: Filter = serde_json::from_str(
+		r#"{"topics":[null,"0x0000000000000000000000000000000000000000000000000000000000000002"]}"#,
+	)
+	.unwrap();
+	assert!(filter.matches(&address, &topics));
+	assert!(!filter.matches(&address, &topics[..1]));
+
+	let filter: Filter =
+		serde_json::from_str(r#"{"topics":["0x0000000000000000000000000000000000000000000000000000000000000002"]}"#)
+			.unwrap();
+	assert!(!filter.matches(&address, &topics));
+}
+
+#[test]
+fn serialize_call_frame_should_work() {
+	use module_evm::{ExitRevert, ExitSucceed};
+
+	let frame = |call_type, exit_reason, calls| CallTrace {
+		call_type,
+		from: H160::from_low_u64_be(1),
+		to: H160::from_low_u64_be(2),
+		value: U256::zero(),
+		gas: 100,
+		gas_used: 50,
+		input: vec![1],
+		output: vec![],
+		exit_reason: Some(exit_reason),
+		precompile: false,
+		calls,
+	};
+	let trace = frame(
+		CallType::Call,
+		ExitReason::Succeed(ExitSucceed::Stopped),
+		vec![frame(
+			CallType::StaticCall,
+			ExitReason::Revert(ExitRevert::Reverted),
+			vec![],
+		)],
+	);
+
+	let value = serde_json::to_value(CallFrame::from(trace)).unwrap();
+	assert_eq!(value["type"], "CALL");
+	assert_eq!(value["gasUsed"], "0x32");
+	assert_eq!(value["input"], "0x01");
+	assert!(value.get("error").is_none());
+	assert_eq!(value["calls"][0]["type"], "STATICCALL");
+	assert_eq!(value["calls"][0]["error"], "execution reverted");
+	assert!(value["calls"][0].get("calls").is_none());
+}
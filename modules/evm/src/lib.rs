@@ -4,6 +4,7 @@
 
 pub mod precompiles;
 pub mod runner;
+pub mod transaction;
 
 mod default_weight;
 mod mock;
@@ -11,8 +12,12 @@ mod tests;
 
 pub use crate::precompiles::{Precompile, Precompiles};
 pub use crate::runner::Runner;
+pub use crate::transaction::{EthereumTransaction, TransactionAction};
 pub use evm::{Context, ExitError, ExitFatal, ExitReason, ExitRevert, ExitSucceed};
-pub use primitives::evm::{Account, CallInfo, CreateInfo, Log, Vicinity};
+pub use primitives::evm::{
+	Account, CallInfo, CallTrace, CallType, CreateInfo, ExecutionReceipt, Log, TraceResult, TraceStep, TracerKind,
+	Vicinity,
+};
 
 use codec::{Decode, Encode};
 use evm::Config as EvmConfig;
@@ -20,24 +25,35 @@ use frame_support::{
 	decl_error, decl_event, decl_module, decl_storage,
 	dispatch::{DispatchError, DispatchResult, DispatchResultWithPostInfo},
 	ensure,
-	traits::{BalanceStatus, Currency, EnsureOrigin, ExistenceRequirement, Get, OnKilledAccount, ReservableCurrency},
+	traits::{
+		BalanceStatus, Currency, EnsureOrigin, ExistenceRequirement, Get, Imbalance, OnKilledAccount, OnUnbalanced,
+		ReservableCurrency, WithdrawReasons,
+	},
 	transactional,
+	unsigned::{TransactionSource, TransactionValidity, ValidTransaction},
 	weights::{Pays, PostDispatchInfo, Weight},
 	RuntimeDebug,
 };
-use frame_system::ensure_signed;
+use frame_system::{ensure_none, ensure_root, ensure_signed};
 use orml_traits::account::MergeAccount;
 use primitives::evm::AddressMapping;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
 use sp_core::{H160, H256, U256};
-use sp_runtime::traits::{CheckedAdd, CheckedSub, Convert, One, Saturating, UniqueSaturatedInto, Zero};
-use sp_std::{marker::PhantomData, vec::Vec};
+use sp_runtime::{
+	traits::{CheckedAdd, CheckedSub, Convert, One, Saturating, UniqueSaturatedInto, Zero},
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	FixedPointNumber, FixedU128, TransactionOutcome,
+};
+use sp_std::convert::TryInto;
+use sp_std::{collections::btree_map::BTreeMap, marker::PhantomData, vec, vec::Vec};
 use support::EVM as EVMTrait;
 
 /// Type alias for currency balance.
 pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+pub type NegativeImbalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
 
 /// Substrate system chain ID.
 pub struct SystemChainId;
@@ -55,10 +71,20 @@ pub trait WeightInfo {
 	fn cancel_transfer_maintainer() -> Weight;
 	fn confirm_transfer_maintainer() -> Weight;
 	fn reject_transfer_maintainer() -> Weight;
+	fn collect_rent(s: u32) -> Weight;
+	fn on_initialize(c: u32) -> Weight;
+	fn restore_contract(s: u32) -> Weight;
+	fn set_code(c: u32) -> Weight;
+	fn apply_code() -> Weight;
+	fn add_deployer() -> Weight;
+	fn remove_deployer() -> Weight;
+	fn set_open_deployment() -> Weight;
+	fn enable_developer_mode() -> Weight;
+	fn disable_developer_mode() -> Weight;
 }
 
 // Initially based on Istanbul hard fork configuration.
-static ACALA_CONFIG: EvmConfig = EvmConfig {
+const ACALA_CONFIG: EvmConfig = EvmConfig {
 	gas_ext_code: 700,
 	gas_ext_code_hash: 700,
 	gas_balance: 700,
@@ -96,6 +122,59 @@ static ACALA_CONFIG: EvmConfig = EvmConfig {
 	estimate: false,
 };
 
+// Istanbul SSTORE net gas metering, EIP-2200.
+const EIP2200_CONFIG: EvmConfig = EvmConfig {
+	refund_sstore_clears: 15000,
+	sstore_gas_metering: true,
+	sstore_revert_under_stipend: true,
+	..ACALA_CONFIG
+};
+
+/// The max size of contract code, EIP-170.
+pub const MAX_CODE_SIZE: u32 = 0x6000;
+
+/// The cost of the first access of a storage in a transaction, EIP-2929.
+pub const COLD_SLOAD_COST: usize = 2100;
+/// The cost of the following accesses of a storage in a transaction, EIP-2929.
+pub const WARM_STORAGE_READ_COST: usize = 100;
+
+// EIP-2200 with the warm storage costs of EIP-2929, the cold surcharges are
+// charged by the handler.
+const EIP2929_CONFIG: EvmConfig = EvmConfig {
+	gas_sload: WARM_STORAGE_READ_COST,
+	gas_sstore_reset: 5000 - COLD_SLOAD_COST,
+	..EIP2200_CONFIG
+};
+
+/// The gas metering of contract storage.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum StorageGasMetering {
+	/// Fixed SSTORE costs without refunds.
+	Acala,
+	/// SSTORE net gas metering with refunds, EIP-2200.
+	Eip2200,
+	/// EIP-2200 with cold and warm storage access costs, EIP-2929. The
+	/// accessed storages are kept warm when a call reverts.
+	Eip2929,
+}
+
+impl StorageGasMetering {
+	/// The EVM config of the metering.
+	pub fn config(self) -> &'static EvmConfig {
+		match self {
+			StorageGasMetering::Acala => &ACALA_CONFIG,
+			StorageGasMetering::Eip2200 => &EIP2200_CONFIG,
+			StorageGasMetering::Eip2929 => &EIP2929_CONFIG,
+		}
+	}
+}
+
+impl Default for StorageGasMetering {
+	fn default() -> Self {
+		StorageGasMetering::Acala
+	}
+}
+
 /// EVM module trait
 pub trait Config: frame_system::Config + pallet_timestamp::Config {
 	/// Mapping from address to account id.
@@ -112,6 +191,21 @@ pub trait Config: frame_system::Config + pallet_timestamp::Config {
 	type StorageDepositPerByte: Get<BalanceOf<Self>>;
 	/// Storage quota default value.
 	type StorageDefaultQuota: Get<u32>;
+	/// Rent of per byte of contract code and storage per block, paid by the
+	/// maintainer.
+	type RentPerBytePerBlock: Get<BalanceOf<Self>>;
+	/// The blocks a contract can stay with overdue rent before it can be
+	/// evicted.
+	type RentGracePeriod: Get<Self::BlockNumber>;
+	/// The blocks between the rent collections of a contract.
+	type RentCollectionPeriod: Get<Self::BlockNumber>;
+	/// The max number of contracts whose rent is collected in a block.
+	type MaxRentCollections: Get<u32>;
+	/// Handler for the collected rent.
+	type OnRentPayment: OnUnbalanced<NegativeImbalanceOf<Self>>;
+	/// The blocks between the maintainer setting the new code of a contract
+	/// and the code taking effect. Zero to set the code immediately.
+	type CodeUpgradeDelay: Get<Self::BlockNumber>;
 
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
@@ -124,15 +218,28 @@ pub trait Config: frame_system::Config + pallet_timestamp::Config {
 	/// Convert gas to weight.
 	type GasToWeight: Convert<u32, Weight>;
 
+	/// The gas metering of contract storage.
+	type StorageMetering: Get<StorageGasMetering>;
+
 	/// EVM config used in the module.
 	fn config() -> &'static EvmConfig {
-		&ACALA_CONFIG
+		Self::StorageMetering::get().config()
 	}
 
 	/// Required origin for creating system contract.
 	type NetworkContractOrigin: EnsureOrigin<Self::Origin>;
 	/// The EVM address for creating system contract.
 	type NetworkContractSource: Get<H160>;
+	/// Handler for the fees of Ethereum transactions.
+	type OnTransactionPayment: OnUnbalanced<NegativeImbalanceOf<Self>>;
+	/// The gas price of EVM calls when the chain isn't congested.
+	type MinGasPrice: Get<BalanceOf<Self>>;
+	/// Multiplier of the min gas price, follows the congestion of the chain.
+	type GasPriceMultiplier: Get<FixedU128>;
+	/// Required origin for managing the deployers and the open deployment.
+	type DeploymentOrigin: EnsureOrigin<Self::Origin>;
+	/// Deposit for enabling the developer mode.
+	type DeveloperDeposit: Get<BalanceOf<Self>>;
 	/// Weight information for the extrinsics in this module.
 	type WeightInfo: WeightInfo;
 }
@@ -200,6 +307,55 @@ impl<T: Config> AccountInfo<T> {
 	}
 }
 
+/// The rent state of a contract.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub struct RentInfo<BlockNumber> {
+	/// The rent is paid up to the block.
+	pub paid_until: BlockNumber,
+	/// The block since which the maintainer failed to pay the rent.
+	pub overdue_since: Option<BlockNumber>,
+	/// The block the rent is collected next.
+	pub next_collection: BlockNumber,
+}
+
+/// The remains of an evicted contract, the contract can be restored with
+/// the code and storage matching the hashes.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub struct Tombstone<T: Config> {
+	pub nonce: T::Index,
+	pub maintainer: H160,
+	pub code_hash: H256,
+	/// The hash of the storage sorted by index, see `storage_hash`.
+	pub storage_hash: H256,
+	/// The rent unpaid on eviction, paid on restoring.
+	pub unpaid_rent: BalanceOf<T>,
+}
+
+/// A scheduled code upgrade of a contract.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub struct CodeUpgrade<T: Config> {
+	pub code: Vec<u8>,
+	pub apply_at: T::BlockNumber,
+	/// The maintainer reserving the deposit of the code.
+	pub depositor: T::AccountId,
+	/// `StorageDepositPerByte` of the code, unreserved once the upgrade is
+	/// applied or replaced.
+	pub deposit: BalanceOf<T>,
+}
+
+/// A call scheduled by a contract.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub struct ScheduledCall<Balance> {
+	/// The contract scheduling the call, the source of the call.
+	pub source: H160,
+	pub target: H160,
+	pub input: Vec<u8>,
+	pub value: Balance,
+	pub gas_limit: u32,
+	/// The fee of `gas_limit` reserved from the contract.
+	pub reserved_fee: Balance,
+}
+
 #[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
 pub struct CodeInfo {
 	pub code_size: u32,
@@ -222,8 +378,8 @@ pub struct GenesisAccount<Balance, Index> {
 
 decl_storage! {
 	trait Store for Module<T: Config> as EVM {
-		Accounts get(fn accounts): map hasher(twox_64_concat) H160 => Option<AccountInfo<T>>;
-		AccountStorages get(fn account_storages):
+		pub Accounts get(fn accounts): map hasher(twox_64_concat) H160 => Option<AccountInfo<T>>;
+		pub AccountStorages get(fn account_storages):
 			double_map hasher(twox_64_concat) H160, hasher(blake2_128_concat) H256 => H256;
 
 		Codes get(fn codes): map hasher(identity) H256 => Vec<u8>;
@@ -233,6 +389,28 @@ decl_storage! {
 
 		/// Next available system contract address.
 		NetworkContractIndex get(fn network_contract_index) config(): u64;
+
+		/// The rent state of contracts: contract => RentInfo
+		pub ContractRents get(fn contract_rents): map hasher(twox_64_concat) H160 => Option<RentInfo<T::BlockNumber>>;
+		/// The contracts whose rent is collected at the block: block => Vec<H160>
+		pub RentCollections get(fn rent_collections): map hasher(twox_64_concat) T::BlockNumber => Vec<H160>;
+		/// The evicted contracts: contract => Tombstone
+		pub Tombstones get(fn tombstones): map hasher(twox_64_concat) H160 => Option<Tombstone<T>>;
+		/// The scheduled code upgrades: contract => CodeUpgrade
+		PendingCodeUpgrades get(fn pending_code_upgrades): map hasher(twox_64_concat) H160 => Option<CodeUpgrade<T>>;
+
+		/// Anyone can deploy contracts if enabled, otherwise only the deployers
+		/// and the developers can.
+		OpenDeployment get(fn open_deployment) config(): bool = true;
+		/// The deployers allowed by governance: address => bool
+		Deployers get(fn deployers): map hasher(twox_64_concat) H160 => bool;
+		/// The accounts in developer mode: account => DeveloperDeposit
+		Developers get(fn developers): map hasher(twox_64_concat) T::AccountId => Option<BalanceOf<T>>;
+
+		/// The calls scheduled by the contracts: schedule id => ScheduledCall
+		ScheduledCalls get(fn scheduled_calls): map hasher(twox_64_concat) u64 => Option<ScheduledCall<BalanceOf<T>>>;
+		/// The id of the next scheduled call.
+		NextScheduledCallId get(fn next_scheduled_call_id): u64;
 	}
 
 	add_extra_genesis {
@@ -262,6 +440,8 @@ decl_event! {
 	/// EVM events
 	pub enum Event<T> where
 		<T as frame_system::Config>::AccountId,
+		<T as frame_system::Config>::BlockNumber,
+		Balance = BalanceOf<T>,
 	{
 		/// Ethereum events from contracts.
 		Log(Log),
@@ -273,6 +453,12 @@ decl_event! {
 		Executed(H160),
 		/// A contract has been executed with errors. States are reverted with only gas fees applied. \[contract, exit_reason, output\]
 		ExecutedFailed(H160, ExitReason, Vec<u8>),
+		/// An EVM transaction has been executed. \[from, used_gas\]
+		TransactionExecuted(H160, U256),
+		/// An EVM transaction failed before the execution, all its gas is
+		/// charged. A failed scheduled call is refunded instead. \[from,
+		/// error\]
+		TransactionFailed(H160, DispatchError),
 		/// A deposit has been made at a given address. \[sender, address, value\]
 		BalanceDeposit(AccountId, H160, U256),
 		/// A withdrawal has been made from a given address. \[sender, address, value\]
@@ -289,6 +475,34 @@ decl_event! {
 		ConfirmedTransferMaintainer(H160, H160),
 		/// Rejected the transfer maintainer. \[contract, address\]
 		RejectedTransferMaintainer(H160, H160),
+		/// The rent of a contract has been collected from the maintainer. \[contract, amount\]
+		RentCollected(H160, Balance),
+		/// The maintainer failed to pay the rent of a contract. \[contract\]
+		RentOverdue(H160),
+		/// A contract with overdue rent has been evicted. \[contract, evictor\]
+		ContractEvicted(H160, AccountId),
+		/// An evicted contract has been restored. \[contract\]
+		ContractRestored(H160),
+		/// Scheduled the new code of the contract. \[contract, code_hash, apply_at\]
+		ScheduledSetCode(H160, H256, BlockNumber),
+		/// Set the new code of the contract. \[contract, code_hash\]
+		ContractSetCode(H160, H256),
+		/// Added a deployer. \[address\]
+		DeployerAdded(H160),
+		/// Removed a deployer. \[address\]
+		DeployerRemoved(H160),
+		/// Set the open deployment. \[enabled\]
+		OpenDeploymentSet(bool),
+		/// Enabled the developer mode. \[who\]
+		DeveloperModeEnabled(AccountId),
+		/// Disabled the developer mode. \[who\]
+		DeveloperModeDisabled(AccountId),
+		/// A contract scheduled a call. \[contract, schedule_id\]
+		CallScheduled(H160, u64),
+		/// A contract canceled a scheduled call. \[contract, schedule_id\]
+		ScheduledCallCanceled(H160, u64),
+		/// A scheduled call has been dispatched. \[contract, schedule_id\]
+		ScheduledCallDispatched(H160, u64),
 	}
 }
 
@@ -310,6 +524,41 @@ decl_error! {
 		PendingTransferMaintainersExists,
 		/// Pending transfer maintainers not exists
 		PendingTransferMaintainersNotExists,
+		/// Invalid signature of Ethereum transaction
+		InvalidSignature,
+		/// Value of Ethereum transaction out of bound
+		InvalidValue,
+		/// The contract is the maintainer of other contracts
+		ContractIsMaintainer,
+		/// The contract already exists
+		ContractAlreadyExists,
+		/// Tombstone of the contract not found
+		TombstoneNotFound,
+		/// The code doesn't match the tombstone
+		InvalidRestoreCode,
+		/// The storage doesn't match the tombstone
+		InvalidRestoreStorage,
+		/// No code upgrade scheduled for the contract
+		CodeUpgradeNotFound,
+		/// The scheduled code upgrade can't be applied yet
+		CodeUpgradeNotDue,
+		/// No permission to deploy contracts
+		NoDeploymentPermission,
+		/// Developer mode already enabled
+		DeveloperModeAlreadyEnabled,
+		/// Developer mode not enabled
+		DeveloperModeNotEnabled,
+		/// Gas price is lower than the current gas price
+		GasPriceTooLow,
+		/// Scheduled call not found
+		ScheduledCallNotFound,
+		/// The weight of the dispatch doesn't cover the gas limit of the
+		/// scheduled call
+		ScheduledCallGasLimitTooHigh,
+		/// The storage count of the contract exceeds the limit
+		StorageCountExceedsLimit,
+		/// The code size exceeds `MAX_CODE_SIZE`
+		ContractExceedsMaxCodeSize,
 	}
 }
 
@@ -327,8 +576,38 @@ decl_module! {
 		const StorageDepositPerByte: BalanceOf<T> = T::StorageDepositPerByte::get();
 		/// Storage quota default value.
 		const StorageDefaultQuota: u32 = T::StorageDefaultQuota::get();
+		/// Rent of per byte of contract code and storage per block.
+		const RentPerBytePerBlock: BalanceOf<T> = T::RentPerBytePerBlock::get();
+		/// The blocks a contract can stay with overdue rent before it can be evicted.
+		const RentGracePeriod: T::BlockNumber = T::RentGracePeriod::get();
+		/// The blocks between the rent collections of a contract.
+		const RentCollectionPeriod: T::BlockNumber = T::RentCollectionPeriod::get();
+		/// The max number of contracts whose rent is collected in a block.
+		const MaxRentCollections: u32 = T::MaxRentCollections::get();
+		/// The blocks between setting the new code of a contract and the code taking effect.
+		const CodeUpgradeDelay: T::BlockNumber = T::CodeUpgradeDelay::get();
+		/// Deposit for enabling the developer mode.
+		const DeveloperDeposit: BalanceOf<T> = T::DeveloperDeposit::get();
+		/// The gas price of EVM calls when the chain isn't congested.
+		const MinGasPrice: BalanceOf<T> = T::MinGasPrice::get();
+
+		/// Collect the rent of the contracts scheduled at this block, and
+		/// schedule their next collections. The overdue contracts are evicted
+		/// by `collect_rent`.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let contracts = RentCollections::<T>::take(now);
+			let count = contracts.len() as u32;
+			for contract in contracts {
+				Self::collect_scheduled_rent(contract, now);
+			}
+			<T as Config>::WeightInfo::on_initialize(count)
+		}
 
 		/// Issue an EVM call operation. This is similar to a message call transaction in Ethereum.
+		///
+		/// The gas is paid at the current `gas_price`, which is also the `GASPRICE`
+		/// of the execution, the unused gas is refunded. The tip of the extrinsic
+		/// is paid on top of it and only raises the priority.
 		#[weight = T::GasToWeight::convert(*gas_limit)]
 		pub fn call(
 			origin,
@@ -340,24 +619,24 @@ decl_module! {
 			let who = ensure_signed(origin)?;
 			let source = T::AddressMapping::to_evm_address(&who).ok_or(Error::<T>::AddressNotMapped)?;
 
-			let info = T::Runner::call(source, target, input, value, gas_limit, T::config())?;
-
-			if info.exit_reason.is_succeed() {
-				Module::<T>::deposit_event(Event::<T>::Executed(target));
-			} else {
-				Module::<T>::deposit_event(Event::<T>::ExecutedFailed(target, info.exit_reason, info.output));
-			}
+			Self::execute_with_gas_fee(&who, gas_limit, |gas_price| {
+				let info = T::Runner::call(source, target, input, value, gas_limit, Some(gas_price), T::config())?;
 
-			let used_gas: u32 = info.used_gas.unique_saturated_into();
+				Module::<T>::deposit_event(Event::<T>::TransactionExecuted(source, info.used_gas));
+				if info.exit_reason.is_succeed() {
+					Module::<T>::deposit_event(Event::<T>::Executed(target));
+				} else {
+					Module::<T>::deposit_event(Event::<T>::ExecutedFailed(target, info.exit_reason, info.output));
+				}
 
-			Ok(PostDispatchInfo {
-				actual_weight: Some(T::GasToWeight::convert(used_gas)),
-				pays_fee: Pays::Yes
+				Ok(info.used_gas.unique_saturated_into())
 			})
 		}
 
 		/// Issue an EVM create operation. This is similar to a contract creation transaction in
 		/// Ethereum.
+		///
+		/// The gas is paid the same as `call`.
 		#[weight = T::GasToWeight::convert(*gas_limit)]
 		pub fn create(
 			origin,
@@ -367,24 +646,25 @@ decl_module! {
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 			let source = T::AddressMapping::to_evm_address(&who).ok_or(Error::<T>::AddressNotMapped)?;
+			ensure!(Self::can_deploy(&source), Error::<T>::NoDeploymentPermission);
 
-			let info = T::Runner::create(source, init, value, gas_limit, T::config())?;
+			Self::execute_with_gas_fee(&who, gas_limit, |gas_price| {
+				let info = T::Runner::create(source, init, value, gas_limit, Some(gas_price), T::config())?;
 
-			if info.exit_reason.is_succeed() {
-				Module::<T>::deposit_event(Event::<T>::Created(info.address));
-			} else {
-				Module::<T>::deposit_event(Event::<T>::CreatedFailed(info.address, info.exit_reason, info.output));
-			}
-
-			let used_gas: u32 = info.used_gas.unique_saturated_into();
+				Module::<T>::deposit_event(Event::<T>::TransactionExecuted(source, info.used_gas));
+				if info.exit_reason.is_succeed() {
+					Module::<T>::deposit_event(Event::<T>::Created(info.address));
+				} else {
+					Module::<T>::deposit_event(Event::<T>::CreatedFailed(info.address, info.exit_reason, info.output));
+				}
 
-			Ok(PostDispatchInfo {
-				actual_weight: Some(T::GasToWeight::convert(used_gas)),
-				pays_fee: Pays::Yes
+				Ok(info.used_gas.unique_saturated_into())
 			})
 		}
 
 		/// Issue an EVM create2 operation.
+		///
+		/// The gas is paid the same as `call`.
 		#[weight = T::GasToWeight::convert(*gas_limit)]
 		pub fn create2(
 			origin,
@@ -395,20 +675,19 @@ decl_module! {
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 			let source = T::AddressMapping::to_evm_address(&who).ok_or(Error::<T>::AddressNotMapped)?;
+			ensure!(Self::can_deploy(&source), Error::<T>::NoDeploymentPermission);
 
-			let info = T::Runner::create2(source, init, salt, value, gas_limit, T::config())?;
-
-			if info.exit_reason.is_succeed() {
-				Module::<T>::deposit_event(Event::<T>::Created(info.address));
-			} else {
-				Module::<T>::deposit_event(Event::<T>::CreatedFailed(info.address, info.exit_reason, info.output));
-			}
+			Self::execute_with_gas_fee(&who, gas_limit, |gas_price| {
+				let info = T::Runner::create2(source, init, salt, value, gas_limit, Some(gas_price), T::config())?;
 
-			let used_gas: u32 = info.used_gas.unique_saturated_into();
+				Module::<T>::deposit_event(Event::<T>::TransactionExecuted(source, info.used_gas));
+				if info.exit_reason.is_succeed() {
+					Module::<T>::deposit_event(Event::<T>::Created(info.address));
+				} else {
+					Module::<T>::deposit_event(Event::<T>::CreatedFailed(info.address, info.exit_reason, info.output));
+				}
 
-			Ok(PostDispatchInfo {
-				actual_weight: Some(T::GasToWeight::convert(used_gas)),
-				pays_fee: Pays::Yes
+				Ok(info.used_gas.unique_saturated_into())
 			})
 		}
 
@@ -424,10 +703,11 @@ decl_module! {
 
 			let source = T::NetworkContractSource::get();
 			let address = H160::from_low_u64_be(Self::network_contract_index());
-			let info = T::Runner::create_at_address(source, init, value, address, gas_limit, T::config())?;
+			let info = T::Runner::create_at_address(source, init, value, address, gas_limit, None, T::config())?;
 
 			NetworkContractIndex::mutate(|v| *v = v.saturating_add(One::one()));
 
+			Module::<T>::deposit_event(Event::<T>::TransactionExecuted(source, info.used_gas));
 			if info.exit_reason.is_succeed() {
 				Module::<T>::deposit_event(Event::<T>::Created(info.address));
 			} else {
@@ -442,6 +722,62 @@ decl_module! {
 			})
 		}
 
+		/// Execute a signed Ethereum transaction, the fee is paid by the account
+		/// mapped from the sender at `gas_price` per gas.
+		///
+		/// The dispatch origin of this call must be _None_.
+		///
+		/// Verify the signature, chain id and nonce by `validate_unsigned`.
+		#[weight = T::GasToWeight::convert(transaction.gas_limit.unique_saturated_into())]
+		pub fn transact(origin, transaction: EthereumTransaction) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+			let source = transaction.recover_signer().ok_or(Error::<T>::InvalidSignature)?;
+			let who = T::AddressMapping::to_account(&source);
+
+			let gas_limit: u32 = transaction.gas_limit.unique_saturated_into();
+			let gas_price = Self::to_balance(transaction.gas_price)?;
+			ensure!(gas_price >= Self::gas_price(), Error::<T>::GasPriceTooLow);
+			let value = Self::to_balance(transaction.value)?;
+
+			let max_fee = gas_price.saturating_mul(gas_limit.into());
+			let fee = T::Currency::withdraw(
+				&who,
+				max_fee,
+				WithdrawReasons::TRANSACTION_PAYMENT,
+				ExistenceRequirement::AllowDeath,
+			)?;
+
+			// the fee and the nonce are kept out of the rollback of a failed execution,
+			// otherwise the transaction could be replayed for free
+			let result = frame_support::storage::with_transaction(|| {
+				match Self::execute_transaction(source, transaction, value, gas_limit, gas_price) {
+					Ok(used_gas) => TransactionOutcome::Commit(Ok(used_gas)),
+					Err(e) => TransactionOutcome::Rollback(Err(e)),
+				}
+			});
+			let used_gas = match result {
+				Ok(used_gas) => used_gas,
+				Err(e) => {
+					Self::inc_nonce(&source);
+					Module::<T>::deposit_event(Event::<T>::TransactionExecuted(source, gas_limit.into()));
+					Module::<T>::deposit_event(Event::<T>::TransactionFailed(source, e));
+					gas_limit
+				}
+			};
+
+			// refund the fee of unused gas
+			let actual_fee = gas_price.saturating_mul(used_gas.into());
+			let refund = T::Currency::deposit_creating(&who, max_fee.saturating_sub(actual_fee));
+			if let Ok(fee) = fee.offset(refund) {
+				T::OnTransactionPayment::on_unbalanced(fee);
+			}
+
+			Ok(PostDispatchInfo {
+				actual_weight: Some(T::GasToWeight::convert(used_gas)),
+				pays_fee: Pays::No
+			})
+		}
+
 		#[weight = <T as Config>::WeightInfo::add_storage_quota()]
 		#[transactional]
 		pub fn add_storage_quota(origin, contract: H160, bytes: u32) {
@@ -499,10 +835,285 @@ decl_module! {
 
 			Module::<T>::deposit_event(Event::<T>::RejectedTransferMaintainer(contract, invalid_maintainer));
 		}
+
+		/// Collect the rent of the contract from its maintainer. The contract is
+		/// evicted if the rent has been overdue for `RentGracePeriod`, and the
+		/// caller is rewarded with the existential deposit of the contract.
+		///
+		/// The rent is also collected every `RentCollectionPeriod` in
+		/// `on_initialize`, which leaves the eviction to this call.
+		///
+		/// - `max_storage_count`: the max storage count of the contract to evict,
+		///   the weight of the eviction is bounded by it.
+		#[weight = <T as Config>::WeightInfo::collect_rent(*max_storage_count)]
+		#[transactional]
+		pub fn collect_rent(origin, contract: H160, max_storage_count: u32) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let evicted_storage_count = Self::do_collect_rent(who, contract, max_storage_count)?;
+			Ok(Some(<T as Config>::WeightInfo::collect_rent(evicted_storage_count)).into())
+		}
+
+		/// Restore the evicted contract with its code and storage, the caller
+		/// pays the unpaid rent and the existential deposit of the contract.
+		#[weight = <T as Config>::WeightInfo::restore_contract(storage.len() as u32)]
+		#[transactional]
+		pub fn restore_contract(origin, contract: H160, code: Vec<u8>, storage: Vec<(H256, H256)>) {
+			let who = ensure_signed(origin)?;
+			Self::do_restore_contract(who, contract, code, storage)?;
+
+			Module::<T>::deposit_event(Event::<T>::ContractRestored(contract));
+		}
+
+		/// Replace the code of the contract, the storage is kept. The new code
+		/// takes effect after `CodeUpgradeDelay` by `apply_code`, the maintainer
+		/// reserves `StorageDepositPerByte` of the code until then.
+		#[weight = <T as Config>::WeightInfo::set_code(code.len() as u32)]
+		#[transactional]
+		pub fn set_code(origin, contract: H160, code: Vec<u8>) {
+			let who = ensure_signed(origin)?;
+			Self::do_schedule_set_code(who, contract, code)?;
+		}
+
+		/// Apply the scheduled code of the contract once `CodeUpgradeDelay`
+		/// has passed.
+		#[weight = <T as Config>::WeightInfo::apply_code()]
+		#[transactional]
+		pub fn apply_code(origin, contract: H160) {
+			ensure_signed(origin)?;
+
+			let code_upgrade = PendingCodeUpgrades::<T>::take(contract).ok_or(Error::<T>::CodeUpgradeNotFound)?;
+			ensure!(
+				frame_system::Module::<T>::block_number() >= code_upgrade.apply_at,
+				Error::<T>::CodeUpgradeNotDue
+			);
+			T::Currency::unreserve(&code_upgrade.depositor, code_upgrade.deposit);
+			Self::do_set_code(contract, code_upgrade.code)?;
+		}
+
+		/// Allow the address to deploy contracts when the open deployment is
+		/// disabled.
+		#[weight = <T as Config>::WeightInfo::add_deployer()]
+		pub fn add_deployer(origin, address: H160) {
+			T::DeploymentOrigin::ensure_origin(origin)?;
+			Deployers::insert(address, true);
+			Module::<T>::deposit_event(Event::<T>::DeployerAdded(address));
+		}
+
+		#[weight = <T as Config>::WeightInfo::remove_deployer()]
+		pub fn remove_deployer(origin, address: H160) {
+			T::DeploymentOrigin::ensure_origin(origin)?;
+			Deployers::remove(address);
+			Module::<T>::deposit_event(Event::<T>::DeployerRemoved(address));
+		}
+
+		#[weight = <T as Config>::WeightInfo::set_open_deployment()]
+		pub fn set_open_deployment(origin, enabled: bool) {
+			T::DeploymentOrigin::ensure_origin(origin)?;
+			OpenDeployment::put(enabled);
+			Module::<T>::deposit_event(Event::<T>::OpenDeploymentSet(enabled));
+		}
+
+		/// Enable the developer mode by reserving `DeveloperDeposit`, the
+		/// developers can deploy contracts when the open deployment is
+		/// disabled.
+		#[weight = <T as Config>::WeightInfo::enable_developer_mode()]
+		#[transactional]
+		pub fn enable_developer_mode(origin) {
+			let who = ensure_signed(origin)?;
+			ensure!(!Developers::<T>::contains_key(&who), Error::<T>::DeveloperModeAlreadyEnabled);
+
+			let deposit = T::DeveloperDeposit::get();
+			T::Currency::reserve(&who, deposit)?;
+			Developers::<T>::insert(&who, deposit);
+
+			Module::<T>::deposit_event(Event::<T>::DeveloperModeEnabled(who));
+		}
+
+		/// Disable the developer mode and unreserve the deposit.
+		#[weight = <T as Config>::WeightInfo::disable_developer_mode()]
+		#[transactional]
+		pub fn disable_developer_mode(origin) {
+			let who = ensure_signed(origin)?;
+			let deposit = Developers::<T>::take(&who).ok_or(Error::<T>::DeveloperModeNotEnabled)?;
+			T::Currency::unreserve(&who, deposit);
+
+			Module::<T>::deposit_event(Event::<T>::DeveloperModeDisabled(who));
+		}
+
+		/// Dispatch the call `schedule_id` scheduled by a contract, the fee is
+		/// paid by the reserved fee of the contract, the unused part is
+		/// refunded. `gas_limit` is the weight of the dispatch, which must
+		/// cover the gas limit of the scheduled call.
+		///
+		/// The scheduled call is removed once dispatched, the failures of the
+		/// fee or the execution are deposited in `TransactionFailed` with the
+		/// fee refunded.
+		///
+		/// The dispatch origin of this call must be _Root_.
+		#[weight = T::GasToWeight::convert(*gas_limit)]
+		#[transactional]
+		pub fn scheduled_call(origin, schedule_id: u64, gas_limit: u32) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let call = Self::scheduled_calls(schedule_id).ok_or(Error::<T>::ScheduledCallNotFound)?;
+			ensure!(call.gas_limit <= gas_limit, Error::<T>::ScheduledCallGasLimitTooHigh);
+			ScheduledCalls::<T>::remove(schedule_id);
+
+			let source = call.source;
+			let used_gas = Self::dispatch_scheduled_call(call).unwrap_or_else(|e| {
+				Module::<T>::deposit_event(Event::<T>::TransactionFailed(source, e));
+				0
+			});
+			Module::<T>::deposit_event(Event::<T>::ScheduledCallDispatched(source, schedule_id));
+
+			Ok(PostDispatchInfo {
+				actual_weight: Some(T::GasToWeight::convert(used_gas)),
+				pays_fee: Pays::No
+			})
+		}
 	}
 }
 
 impl<T: Config> Module<T> {
+	/// Emit an EVM log, used by the precompiles acting as contracts.
+	pub fn deposit_log(log: Log) {
+		Self::deposit_event(Event::<T>::Log(log));
+	}
+
+	/// Schedule the call of `target` by `contract`, the fee of `gas_limit` at
+	/// the current gas price is reserved. Returns the schedule id.
+	pub fn reserve_scheduled_call(
+		contract: H160,
+		target: H160,
+		input: Vec<u8>,
+		value: BalanceOf<T>,
+		gas_limit: u32,
+	) -> Result<u64, DispatchError> {
+		let who = T::AddressMapping::to_account(&contract);
+		let fee = Self::gas_price().saturating_mul(gas_limit.into());
+		T::Currency::reserve(&who, fee)?;
+
+		let schedule_id = NextScheduledCallId::try_mutate(|id| -> Result<u64, DispatchError> {
+			let current_id = *id;
+			*id = id.checked_add(1).ok_or(Error::<T>::NumOutOfBound)?;
+			Ok(current_id)
+		})?;
+		ScheduledCalls::<T>::insert(
+			schedule_id,
+			ScheduledCall {
+				source: contract,
+				target,
+				input,
+				value,
+				gas_limit,
+				reserved_fee: fee,
+			},
+		);
+
+		Self::deposit_event(Event::<T>::CallScheduled(contract, schedule_id));
+		Ok(schedule_id)
+	}
+
+	/// Cancel a call scheduled by `contract`, the reserved fee is refunded.
+	pub fn cancel_scheduled_call(contract: H160, schedule_id: u64) -> DispatchResult {
+		let call = Self::scheduled_calls(schedule_id).ok_or(Error::<T>::ScheduledCallNotFound)?;
+		ensure!(call.source == contract, Error::<T>::NoPermission);
+
+		ScheduledCalls::<T>::remove(schedule_id);
+		T::Currency::unreserve(&T::AddressMapping::to_account(&contract), call.reserved_fee);
+
+		Self::deposit_event(Event::<T>::ScheduledCallCanceled(contract, schedule_id));
+		Ok(())
+	}
+
+	/// Withdraw the fee of `gas_limit` at the current `gas_price` from `who`,
+	/// execute with that gas price and refund the fee of unused gas. The fee
+	/// is returned if the execution fails.
+	///
+	/// The weight fee charged by the transaction payment is refunded, except
+	/// the tip, so the gas is only paid once.
+	fn execute_with_gas_fee(
+		who: &T::AccountId,
+		gas_limit: u32,
+		execute: impl FnOnce(BalanceOf<T>) -> Result<u32, DispatchError>,
+	) -> DispatchResultWithPostInfo {
+		let gas_price = Self::gas_price();
+		let max_fee = gas_price.saturating_mul(gas_limit.into());
+		let fee = T::Currency::withdraw(
+			who,
+			max_fee,
+			WithdrawReasons::TRANSACTION_PAYMENT,
+			ExistenceRequirement::AllowDeath,
+		)?;
+
+		let used_gas = match execute(gas_price) {
+			Ok(used_gas) => used_gas,
+			Err(e) => {
+				T::Currency::resolve_creating(who, fee);
+				return Err(e.into());
+			}
+		};
+
+		// refund the fee of unused gas
+		let actual_fee = gas_price.saturating_mul(used_gas.into());
+		let refund = T::Currency::deposit_creating(who, max_fee.saturating_sub(actual_fee));
+		if let Ok(fee) = fee.offset(refund) {
+			T::OnTransactionPayment::on_unbalanced(fee);
+		}
+
+		Ok(PostDispatchInfo {
+			actual_weight: Some(T::GasToWeight::convert(used_gas)),
+			pays_fee: Pays::No,
+		})
+	}
+
+	/// Pay the fee and execute the scheduled call, returns the used gas. The
+	/// reserved fee is unreserved, and the withdrawn fee is refunded if the
+	/// execution fails.
+	fn dispatch_scheduled_call(call: ScheduledCall<BalanceOf<T>>) -> Result<u32, DispatchError> {
+		let who = T::AddressMapping::to_account(&call.source);
+
+		T::Currency::unreserve(&who, call.reserved_fee);
+		let fee = T::Currency::withdraw(
+			&who,
+			call.reserved_fee,
+			WithdrawReasons::TRANSACTION_PAYMENT,
+			ExistenceRequirement::AllowDeath,
+		)?;
+		let gas_price = call.reserved_fee / call.gas_limit.max(1).into();
+
+		let info = match T::Runner::call(
+			call.source,
+			call.target,
+			call.input,
+			call.value,
+			call.gas_limit,
+			Some(gas_price),
+			T::config(),
+		) {
+			Ok(info) => info,
+			Err(e) => {
+				T::Currency::resolve_creating(&who, fee);
+				return Err(e);
+			}
+		};
+
+		Module::<T>::deposit_event(Event::<T>::TransactionExecuted(call.source, info.used_gas));
+		if info.exit_reason.is_succeed() {
+			Module::<T>::deposit_event(Event::<T>::Executed(call.target));
+		} else {
+			Module::<T>::deposit_event(Event::<T>::ExecutedFailed(call.target, info.exit_reason, info.output));
+		}
+
+		// refund the fee of unused gas
+		let used_gas: u32 = info.used_gas.unique_saturated_into();
+		let actual_fee = gas_price.saturating_mul(used_gas.into());
+		let refund = T::Currency::deposit_creating(&who, call.reserved_fee.saturating_sub(actual_fee));
+		if let Ok(fee) = fee.offset(refund) {
+			T::OnTransactionPayment::on_unbalanced(fee);
+		}
+		Ok(used_gas)
+	}
+
 	/// Remove an account.
 	pub fn remove_account(address: &H160) -> Result<(), ExitError> {
 		// Deref code, and remove it if ref count is zero.
@@ -524,10 +1135,77 @@ impl<T: Config> Module<T> {
 
 		<Accounts<T>>::remove(address);
 		AccountStorages::remove_prefix(address);
+		ContractRents::<T>::remove(address);
+		if let Some(code_upgrade) = PendingCodeUpgrades::<T>::take(address) {
+			T::Currency::unreserve(&code_upgrade.depositor, code_upgrade.deposit);
+		}
 
 		Ok(())
 	}
 
+	/// Execute the action of the Ethereum transaction, returns the used gas.
+	fn execute_transaction(
+		source: H160,
+		transaction: EthereumTransaction,
+		value: BalanceOf<T>,
+		gas_limit: u32,
+		gas_price: BalanceOf<T>,
+	) -> Result<u32, DispatchError> {
+		match transaction.action {
+			TransactionAction::Call(target) => {
+				let info = T::Runner::call(
+					source,
+					target,
+					transaction.input,
+					value,
+					gas_limit,
+					Some(gas_price),
+					T::config(),
+				)?;
+
+				Module::<T>::deposit_event(Event::<T>::TransactionExecuted(source, info.used_gas));
+				if info.exit_reason.is_succeed() {
+					Module::<T>::deposit_event(Event::<T>::Executed(target));
+				} else {
+					Module::<T>::deposit_event(Event::<T>::ExecutedFailed(target, info.exit_reason, info.output));
+				}
+				Ok(info.used_gas.unique_saturated_into())
+			}
+			TransactionAction::Create => {
+				ensure!(Self::can_deploy(&source), Error::<T>::NoDeploymentPermission);
+				let info = T::Runner::create(
+					source,
+					transaction.input,
+					value,
+					gas_limit,
+					Some(gas_price),
+					T::config(),
+				)?;
+
+				Module::<T>::deposit_event(Event::<T>::TransactionExecuted(source, info.used_gas));
+				if info.exit_reason.is_succeed() {
+					Module::<T>::deposit_event(Event::<T>::Created(info.address));
+				} else {
+					Module::<T>::deposit_event(Event::<T>::CreatedFailed(info.address, info.exit_reason, info.output));
+				}
+				Ok(info.used_gas.unique_saturated_into())
+			}
+		}
+	}
+
+	/// Increment the nonce of `address`, create the account if not exists.
+	fn inc_nonce(address: &H160) {
+		Accounts::<T>::mutate(address, |maybe_account| {
+			if let Some(account) = maybe_account.as_mut() {
+				account.nonce += One::one()
+			} else {
+				let mut account_info = <AccountInfo<T>>::new(Default::default());
+				account_info.nonce += One::one();
+				*maybe_account = Some(account_info);
+			}
+		});
+	}
+
 	/// Get the account basic in EVM format.
 	pub fn account_basic(address: &H160) -> Account {
 		let account_id = T::AddressMapping::to_account(address);
@@ -559,6 +1237,109 @@ impl<T: Config> Module<T> {
 		Self::codes(&Self::code_hash_at_address(address))
 	}
 
+	fn to_balance(value: U256) -> Result<BalanceOf<T>, Error<T>> {
+		if value > U256::from(u128::max_value()) {
+			return Err(Error::<T>::InvalidValue);
+		}
+		value.low_u128().try_into().map_err(|_| Error::<T>::InvalidValue)
+	}
+
+	/// The gas price of EVM calls, the min gas price adjusted by the
+	/// congestion of the chain. Ethereum transactions must pay at least this
+	/// price, the excess is the priority fee.
+	pub fn gas_price() -> BalanceOf<T> {
+		let min_gas_price = T::MinGasPrice::get();
+		let gas_price: BalanceOf<T> = T::GasPriceMultiplier::get()
+			.saturating_mul_int(UniqueSaturatedInto::<u128>::unique_saturated_into(min_gas_price))
+			.unique_saturated_into();
+		gas_price.max(min_gas_price)
+	}
+
+	/// Check the signature, chain id, nonce and gas price of the Ethereum
+	/// transaction, and that the sender can afford the value and the max fee.
+	/// The transaction must be signed with the chain id (EIP-155).
+	/// A nonce higher than the sender's is valid, the transaction waits for the
+	/// previous ones. Returns the sender and its nonce.
+	pub fn validate_transaction(transaction: &EthereumTransaction) -> Result<(H160, U256), InvalidTransaction> {
+		// legacy transactions without chain id could be replayed on other chains
+		if transaction.chain_id() != Some(T::ChainId::get()) {
+			return Err(InvalidTransaction::BadProof);
+		}
+		let source = transaction.recover_signer().ok_or(InvalidTransaction::BadProof)?;
+
+		let nonce = Self::account_basic(&source).nonce;
+		if transaction.nonce < nonce {
+			return Err(InvalidTransaction::Stale);
+		}
+
+		let gas_limit: u32 = transaction.gas_limit.unique_saturated_into();
+		let gas_price = Self::to_balance(transaction.gas_price).map_err(|_| InvalidTransaction::Payment)?;
+		if gas_price < Self::gas_price() {
+			return Err(InvalidTransaction::Payment);
+		}
+		let value = Self::to_balance(transaction.value).map_err(|_| InvalidTransaction::Payment)?;
+		let cost = gas_price.saturating_mul(gas_limit.into()).saturating_add(value);
+		if T::Currency::free_balance(&T::AddressMapping::to_account(&source)) < cost {
+			return Err(InvalidTransaction::Payment);
+		}
+		if transaction.action == TransactionAction::Create && !Self::can_deploy(&source) {
+			return Err(InvalidTransaction::Call);
+		}
+
+		Ok((source, nonce))
+	}
+
+	/// Build the receipts of EVM transactions from the EVM events of a block,
+	/// each event is paired with the index of the extrinsic emitting it.
+	pub fn receipts_from_events(events: impl IntoIterator<Item = (u32, Event<T>)>) -> Vec<ExecutionReceipt> {
+		let mut receipts: BTreeMap<u32, ExecutionReceipt> = BTreeMap::new();
+		let mut logs: BTreeMap<u32, Vec<Log>> = BTreeMap::new();
+		let mut transactions: BTreeMap<u32, (H160, U256)> = BTreeMap::new();
+
+		for (extrinsic_index, event) in events {
+			let (to, contract_address, status) = match event {
+				RawEvent::Log(log) => {
+					logs.entry(extrinsic_index).or_insert_with(Vec::new).push(log);
+					continue;
+				}
+				RawEvent::TransactionExecuted(from, used_gas) => {
+					transactions.insert(extrinsic_index, (from, used_gas));
+					continue;
+				}
+				RawEvent::Executed(contract) => (Some(contract), None, true),
+				RawEvent::ExecutedFailed(contract, _, _) => (Some(contract), None, false),
+				RawEvent::Created(contract) => (None, Some(contract), true),
+				RawEvent::CreatedFailed(contract, _, _) => (None, Some(contract), false),
+				RawEvent::TransactionFailed(_, _) => (None, None, false),
+				_ => continue,
+			};
+			receipts.insert(
+				extrinsic_index,
+				ExecutionReceipt {
+					extrinsic_index,
+					from: Default::default(),
+					to,
+					contract_address,
+					status,
+					used_gas: Default::default(),
+					logs: vec![],
+				},
+			);
+		}
+
+		receipts
+			.into_iter()
+			.map(|(extrinsic_index, mut receipt)| {
+				receipt.logs = logs.remove(&extrinsic_index).unwrap_or_default();
+				if let Some((from, used_gas)) = transactions.remove(&extrinsic_index) {
+					receipt.from = from;
+					receipt.used_gas = used_gas;
+				}
+				receipt
+			})
+			.collect()
+	}
+
 	/// Handler on new contract initialization.
 	///
 	/// - Create new account for the contract.
@@ -623,7 +1404,21 @@ impl<T: Config> Module<T> {
 				*maybe_account_info = Some(account_info);
 				Ok(())
 			}
-		})
+		})?;
+
+		let now = frame_system::Module::<T>::block_number();
+		ContractRents::<T>::insert(
+			address,
+			RentInfo {
+				paid_until: now,
+				overdue_since: None,
+				next_collection: Self::schedule_rent_collection(
+					*address,
+					now.saturating_add(T::RentCollectionPeriod::get()),
+				),
+			},
+		);
+		Ok(())
 	}
 
 	/// Set account storage.
@@ -917,6 +1712,315 @@ impl<T: Config> Module<T> {
 		)
 	}
 
+	/// The rent of the code and storage of the contract for `blocks`.
+	fn rent_of(contract_info: &ContractInfo<T>, blocks: T::BlockNumber) -> BalanceOf<T> {
+		let code_size = CodeInfos::get(contract_info.code_hash).map_or(0, |code_info| code_info.code_size);
+		let bytes = contract_info.total_storage_size().saturating_add(code_size);
+		let blocks: u32 = blocks.unique_saturated_into();
+
+		T::RentPerBytePerBlock::get()
+			.saturating_mul(bytes.into())
+			.saturating_mul(blocks.into())
+	}
+
+	/// Add the contract to the rent collections of the first block from `at`
+	/// which isn't full, returns the block.
+	fn schedule_rent_collection(contract: H160, mut at: T::BlockNumber) -> T::BlockNumber {
+		let max_rent_collections = T::MaxRentCollections::get().max(1) as usize;
+		while RentCollections::<T>::decode_len(at).unwrap_or(0) >= max_rent_collections {
+			at = at.saturating_add(One::one());
+		}
+		RentCollections::<T>::append(at, contract);
+		at
+	}
+
+	/// Charge the rent since `paid_until` from the maintainer of the contract.
+	/// The rent becomes overdue if the maintainer can't pay, the unpaid rent
+	/// is returned.
+	fn charge_rent(
+		contract: H160,
+		contract_info: &ContractInfo<T>,
+		rent_info: &mut RentInfo<T::BlockNumber>,
+		now: T::BlockNumber,
+	) -> Result<(), BalanceOf<T>> {
+		let rent = Self::rent_of(contract_info, now.saturating_sub(rent_info.paid_until));
+
+		let maintainer_account = T::AddressMapping::to_account(&contract_info.maintainer);
+		match T::Currency::withdraw(
+			&maintainer_account,
+			rent,
+			WithdrawReasons::FEE,
+			ExistenceRequirement::KeepAlive,
+		) {
+			Ok(imbalance) => {
+				T::OnRentPayment::on_unbalanced(imbalance);
+				rent_info.paid_until = now;
+				rent_info.overdue_since = None;
+				Module::<T>::deposit_event(Event::<T>::RentCollected(contract, rent));
+				Ok(())
+			}
+			Err(_) => {
+				rent_info.overdue_since.get_or_insert(now);
+				Err(rent)
+			}
+		}
+	}
+
+	/// Charge the rent of the contract scheduled at `now` and schedule the
+	/// next collection. The contracts evicted in the meantime are skipped.
+	fn collect_scheduled_rent(contract: H160, now: T::BlockNumber) {
+		let contract_info = match Accounts::<T>::get(contract).and_then(|account_info| account_info.contract_info) {
+			Some(contract_info) => contract_info,
+			None => return,
+		};
+		let mut rent_info = match ContractRents::<T>::get(contract) {
+			// a restored contract is scheduled again, the collection of the
+			// evicted one is outdated
+			Some(rent_info) if rent_info.next_collection == now => rent_info,
+			_ => return,
+		};
+
+		if Self::charge_rent(contract, &contract_info, &mut rent_info, now).is_err() {
+			Module::<T>::deposit_event(Event::<T>::RentOverdue(contract));
+		}
+		rent_info.next_collection =
+			Self::schedule_rent_collection(contract, now.saturating_add(T::RentCollectionPeriod::get()));
+		ContractRents::<T>::insert(contract, rent_info);
+	}
+
+	/// Returns the storage count of the evicted contract, zero if the contract
+	/// isn't evicted.
+	fn do_collect_rent(who: T::AccountId, contract: H160, max_storage_count: u32) -> Result<u32, DispatchError> {
+		let account_info = Accounts::<T>::get(contract).ok_or(Error::<T>::ContractNotFound)?;
+		let contract_info = account_info
+			.contract_info
+			.as_ref()
+			.ok_or(Error::<T>::ContractNotFound)?;
+
+		let now = frame_system::Module::<T>::block_number();
+		let mut rent_info = match ContractRents::<T>::get(contract) {
+			Some(rent_info) => rent_info,
+			// contracts created before the rent are charged and collected from now on
+			None => RentInfo {
+				paid_until: now,
+				overdue_since: None,
+				next_collection: Self::schedule_rent_collection(
+					contract,
+					now.saturating_add(T::RentCollectionPeriod::get()),
+				),
+			},
+		};
+
+		if let Err(unpaid_rent) = Self::charge_rent(contract, contract_info, &mut rent_info, now) {
+			let overdue_since = rent_info.overdue_since.unwrap_or(now);
+			if now >= overdue_since.saturating_add(T::RentGracePeriod::get()) {
+				let storage_count = contract_info.storage_count;
+				ensure!(storage_count <= max_storage_count, Error::<T>::StorageCountExceedsLimit);
+				Self::evict_contract(who, contract, account_info, unpaid_rent)?;
+				return Ok(storage_count);
+			}
+			Module::<T>::deposit_event(Event::<T>::RentOverdue(contract));
+		}
+
+		ContractRents::<T>::insert(contract, rent_info);
+		Ok(0)
+	}
+
+	/// Remove the code and storage of the contract and leave a tombstone.
+	fn evict_contract(
+		evictor: T::AccountId,
+		contract: H160,
+		account_info: AccountInfo<T>,
+		unpaid_rent: BalanceOf<T>,
+	) -> DispatchResult {
+		let contract_info = account_info.contract_info.ok_or(Error::<T>::ContractNotFound)?;
+		ensure!(account_info.storage_usage.is_zero(), Error::<T>::ContractIsMaintainer);
+
+		let mut storage: Vec<(H256, H256)> = AccountStorages::iter_prefix(contract).collect();
+		storage.sort_by_key(|(index, _)| *index);
+
+		// release the storage usage and the storage deposit of the maintainer
+		let additional_storage = Self::additional_storage(contract);
+		if !additional_storage.is_zero() {
+			Self::do_update_maintainer_storage_usage(&contract_info.maintainer, additional_storage, 0)?;
+		}
+		let maintainer_account = T::AddressMapping::to_account(&contract_info.maintainer);
+		T::Currency::unreserve(&maintainer_account, account_info.storage_rent_deposit);
+
+		for (requester, deposit) in PendingTransferMaintainers::<T>::drain_prefix(contract) {
+			T::Currency::unreserve(&T::AddressMapping::to_account(&requester), deposit);
+		}
+
+		// reward the evictor with the existential deposit of the contract
+		let contract_account = T::AddressMapping::to_account(&contract);
+		T::Currency::repatriate_reserved(
+			&contract_account,
+			&evictor,
+			contract_info.existential_deposit,
+			BalanceStatus::Free,
+		)?;
+
+		Self::remove_account(&contract).map_err(|_| Error::<T>::ContractNotFound)?;
+		Tombstones::<T>::insert(
+			contract,
+			Tombstone {
+				nonce: account_info.nonce,
+				maintainer: contract_info.maintainer,
+				code_hash: contract_info.code_hash,
+				storage_hash: storage_hash(&storage),
+				unpaid_rent,
+			},
+		);
+
+		Module::<T>::deposit_event(Event::<T>::ContractEvicted(contract, evictor));
+		Ok(())
+	}
+
+	fn do_restore_contract(
+		who: T::AccountId,
+		contract: H160,
+		code: Vec<u8>,
+		mut storage: Vec<(H256, H256)>,
+	) -> DispatchResult {
+		let tombstone = Tombstones::<T>::take(contract).ok_or(Error::<T>::TombstoneNotFound)?;
+		ensure!(
+			Accounts::<T>::get(contract).map_or(true, |account_info| account_info.contract_info.is_none()),
+			Error::<T>::ContractAlreadyExists
+		);
+		ensure!(code_hash(&code) == tombstone.code_hash, Error::<T>::InvalidRestoreCode);
+		storage.sort_by_key(|(index, _)| *index);
+		ensure!(
+			storage_hash(&storage) == tombstone.storage_hash,
+			Error::<T>::InvalidRestoreStorage
+		);
+
+		// pay the unpaid rent and deposit the existential deposit again
+		let rent = T::Currency::withdraw(
+			&who,
+			tombstone.unpaid_rent,
+			WithdrawReasons::FEE,
+			ExistenceRequirement::AllowDeath,
+		)?;
+		T::OnRentPayment::on_unbalanced(rent);
+		let contract_account = T::AddressMapping::to_account(&contract);
+		T::Currency::transfer(
+			&who,
+			&contract_account,
+			T::ContractExistentialDeposit::get(),
+			ExistenceRequirement::AllowDeath,
+		)?;
+		T::Currency::reserve(&contract_account, T::ContractExistentialDeposit::get())?;
+
+		for (index, value) in storage.iter() {
+			AccountStorages::insert(contract, index, value);
+		}
+		Self::on_contract_initialization(&contract, &tombstone.maintainer, code, Some(storage.len() as u32))
+			.map_err(|_| Error::<T>::StorageQuotaNotEnough)?;
+		Accounts::<T>::mutate(contract, |maybe_account_info| {
+			if let Some(account_info) = maybe_account_info.as_mut() {
+				account_info.nonce = tombstone.nonce;
+			}
+		});
+
+		Ok(())
+	}
+
+	/// Whether the address can deploy contracts by extrinsics. Contracts
+	/// created by contracts are not restricted.
+	pub fn can_deploy(address: &H160) -> bool {
+		Self::open_deployment()
+			|| Self::deployers(address)
+			|| Developers::<T>::contains_key(T::AddressMapping::to_account(address))
+	}
+
+	fn do_schedule_set_code(who: T::AccountId, contract: H160, code: Vec<u8>) -> DispatchResult {
+		let contract_info = Accounts::<T>::get(contract)
+			.and_then(|account_info| account_info.contract_info)
+			.ok_or(Error::<T>::ContractNotFound)?;
+		let maintainer_account = T::AddressMapping::to_account(&contract_info.maintainer);
+		ensure!(who == maintainer_account, Error::<T>::NoPermission);
+		ensure!(
+			code.len() as u32 <= MAX_CODE_SIZE,
+			Error::<T>::ContractExceedsMaxCodeSize
+		);
+
+		// a new schedule replaces the pending one
+		if let Some(code_upgrade) = PendingCodeUpgrades::<T>::take(contract) {
+			T::Currency::unreserve(&code_upgrade.depositor, code_upgrade.deposit);
+		}
+
+		let delay = T::CodeUpgradeDelay::get();
+		if delay.is_zero() {
+			return Self::do_set_code(contract, code);
+		}
+
+		let deposit = T::StorageDepositPerByte::get().saturating_mul((code.len() as u32).into());
+		T::Currency::reserve(&who, deposit)?;
+
+		let apply_at = frame_system::Module::<T>::block_number().saturating_add(delay);
+		let code_hash = code_hash(&code);
+		PendingCodeUpgrades::<T>::insert(
+			contract,
+			CodeUpgrade {
+				code,
+				apply_at,
+				depositor: who,
+				deposit,
+			},
+		);
+
+		Module::<T>::deposit_event(Event::<T>::ScheduledSetCode(contract, code_hash, apply_at));
+		Ok(())
+	}
+
+	/// Replace the code of the contract, and update the code refs and the
+	/// storage usage of the maintainer.
+	fn do_set_code(contract: H160, code: Vec<u8>) -> DispatchResult {
+		let pre_additional_storage = Self::additional_storage(contract);
+		let new_code_hash = code_hash(&code);
+		let code_size = code.len() as u32;
+
+		let maintainer = Accounts::<T>::mutate(contract, |maybe_account_info| -> Result<H160, DispatchError> {
+			let contract_info = maybe_account_info
+				.as_mut()
+				.and_then(|account_info| account_info.contract_info.as_mut())
+				.ok_or(Error::<T>::ContractNotFound)?;
+
+			// ref the new code before deref the old one, they can be the same
+			CodeInfos::mutate_exists(&new_code_hash, |maybe_code_info| {
+				if let Some(code_info) = maybe_code_info.as_mut() {
+					code_info.ref_count = code_info.ref_count.saturating_add(1);
+				} else {
+					*maybe_code_info = Some(CodeInfo {
+						code_size,
+						ref_count: 1,
+					});
+					Codes::insert(&new_code_hash, code);
+				}
+			});
+			CodeInfos::mutate_exists(&contract_info.code_hash, |maybe_code_info| {
+				if let Some(code_info) = maybe_code_info.as_mut() {
+					code_info.ref_count = code_info.ref_count.saturating_sub(1);
+					if code_info.ref_count == 0 {
+						Codes::remove(&contract_info.code_hash);
+						*maybe_code_info = None;
+					}
+				}
+			});
+
+			contract_info.code_hash = new_code_hash;
+			Ok(contract_info.maintainer)
+		})?;
+
+		let additional_storage = Self::additional_storage(contract);
+		if additional_storage != pre_additional_storage {
+			Self::do_update_maintainer_storage_usage(&maintainer, pre_additional_storage, additional_storage)?;
+		}
+
+		Module::<T>::deposit_event(Event::<T>::ContractSetCode(contract, new_code_hash));
+		Ok(())
+	}
+
 	fn do_update_maintainer_storage_usage(
 		maintainer: &H160,
 		pre_storage_usage: u32,
@@ -973,6 +2077,7 @@ impl<T: Config> EVMTrait for Module<T> {
 			input,
 			value,
 			gas_limit,
+			None,
 			config.as_ref().unwrap_or(T::config()),
 		)?;
 
@@ -988,13 +2093,21 @@ impl<T: Config> EVMTrait for Module<T> {
 
 		Ok(info)
 	}
+
+	fn gas_price() -> BalanceOf<T> {
+		Module::<T>::gas_price()
+	}
 }
 
 pub struct CallKillAccount<T>(PhantomData<T>);
 impl<T: Config> OnKilledAccount<T::AccountId> for CallKillAccount<T> {
 	fn on_killed_account(who: &T::AccountId) {
 		if let Some(address) = T::AddressMapping::to_evm_address(who) {
-			let _ = Module::<T>::remove_account(&address);
+			// the nonce of an external account is kept, the transactions signed by
+			// its key could be replayed otherwise
+			if Module::<T>::accounts(&address).map_or(false, |account_info| account_info.contract_info.is_some()) {
+				let _ = Module::<T>::remove_account(&address);
+			}
 		}
 	}
 }
@@ -1002,3 +2115,50 @@ impl<T: Config> OnKilledAccount<T::AccountId> for CallKillAccount<T> {
 pub fn code_hash(code: &[u8]) -> H256 {
 	H256::from_slice(Keccak256::digest(code).as_slice())
 }
+
+/// The hash of the contract storage sorted by index.
+pub fn storage_hash(storage: &[(H256, H256)]) -> H256 {
+	H256::from_slice(Keccak256::digest(&storage.encode()).as_slice())
+}
+
+impl<T: Config> frame_support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+		if let Call::transact(transaction) = call {
+			let (source, nonce) = Self::validate_transaction(transaction)?;
+
+			// the max fee per the share of the block weight, the same as the signed
+			// extrinsics, so the priority fee raises the priority
+			let gas_limit: u32 = transaction.gas_limit.unique_saturated_into();
+			let max_fee = transaction.gas_price.saturating_mul(gas_limit.into());
+			let weight = T::GasToWeight::convert(gas_limit).max(1);
+			let block_share = <T as frame_system::Config>::MaximumBlockWeight::get() / weight;
+
+			let mut builder = ValidTransaction::with_tag_prefix("EVMTransact")
+				.priority(max_fee.saturating_mul(block_share.into()).unique_saturated_into())
+				.and_provides((source, transaction.nonce))
+				.longevity(64_u64)
+				.propagate(true);
+			// a future transaction requires the previous one of the sender
+			if transaction.nonce > nonce {
+				builder = builder.and_requires((source, transaction.nonce - 1));
+			}
+			builder.build()
+		} else {
+			InvalidTransaction::Call.into()
+		}
+	}
+
+	fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+		if let Call::transact(transaction) = call {
+			let (_, nonce) = Self::validate_transaction(transaction)?;
+			if transaction.nonce > nonce {
+				return Err(InvalidTransaction::Future.into());
+			}
+			Ok(())
+		} else {
+			Err(InvalidTransaction::Call.into())
+		}
+	}
+}
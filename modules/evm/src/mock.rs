@@ -12,7 +12,7 @@ use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup},
 	AccountId32, Perbill,
 };
-use std::{collections::BTreeMap, str::FromStr};
+use std::{cell::RefCell, collections::BTreeMap, str::FromStr};
 
 /// Mock address mapping.
 pub struct MockAddressMapping<T>(sp_std::marker::PhantomData<T>);
@@ -36,6 +36,10 @@ where
 			None
 		}
 	}
+
+	fn get_or_create_evm_address(account_id: &AccountId32) -> H160 {
+		Self::to_evm_address(account_id).unwrap_or_default()
+	}
 }
 
 impl_outer_origin! {
@@ -169,6 +173,60 @@ ord_parameter_types! {
 	pub const TransferMaintainerDeposit: u64 = 1;
 	pub const StorageDepositPerByte: u64 = 10;
 	pub const StorageDefaultQuota: u32 = 400;
+	pub const RentPerBytePerBlock: u64 = 1;
+	pub const RentGracePeriod: u64 = 10;
+	pub const RentCollectionPeriod: u64 = 10;
+	pub const MaxRentCollections: u32 = 2;
+	pub const DeveloperDeposit: u64 = 1000;
+	pub const MinGasPrice: u64 = 1;
+}
+
+thread_local! {
+	static STORAGE_METERING: RefCell<StorageGasMetering> = RefCell::new(StorageGasMetering::Acala);
+}
+
+pub struct MockStorageMetering;
+impl MockStorageMetering {
+	pub fn set(metering: StorageGasMetering) {
+		STORAGE_METERING.with(|v| *v.borrow_mut() = metering);
+	}
+}
+impl Get<StorageGasMetering> for MockStorageMetering {
+	fn get() -> StorageGasMetering {
+		STORAGE_METERING.with(|v| *v.borrow())
+	}
+}
+
+thread_local! {
+	static CODE_UPGRADE_DELAY: RefCell<u64> = RefCell::new(0);
+}
+
+pub struct MockCodeUpgradeDelay;
+impl MockCodeUpgradeDelay {
+	pub fn set(delay: u64) {
+		CODE_UPGRADE_DELAY.with(|v| *v.borrow_mut() = delay);
+	}
+}
+impl Get<u64> for MockCodeUpgradeDelay {
+	fn get() -> u64 {
+		CODE_UPGRADE_DELAY.with(|v| *v.borrow())
+	}
+}
+
+thread_local! {
+	static GAS_PRICE_MULTIPLIER: RefCell<FixedU128> = RefCell::new(FixedU128::one());
+}
+
+pub struct MockGasPriceMultiplier;
+impl MockGasPriceMultiplier {
+	pub fn set(multiplier: FixedU128) {
+		GAS_PRICE_MULTIPLIER.with(|v| *v.borrow_mut() = multiplier);
+	}
+}
+impl Get<FixedU128> for MockGasPriceMultiplier {
+	fn get() -> FixedU128 {
+		GAS_PRICE_MULTIPLIER.with(|v| *v.borrow())
+	}
 }
 
 impl Config for Test {
@@ -179,15 +237,27 @@ impl Config for Test {
 	type TransferMaintainerDeposit = TransferMaintainerDeposit;
 	type StorageDepositPerByte = StorageDepositPerByte;
 	type StorageDefaultQuota = StorageDefaultQuota;
+	type RentPerBytePerBlock = RentPerBytePerBlock;
+	type RentGracePeriod = RentGracePeriod;
+	type RentCollectionPeriod = RentCollectionPeriod;
+	type MaxRentCollections = MaxRentCollections;
+	type OnRentPayment = ();
+	type CodeUpgradeDelay = MockCodeUpgradeDelay;
 
 	type Event = Event<Test>;
 	type Precompiles = ();
 	type ChainId = SystemChainId;
 	type Runner = crate::runner::native::Runner<Self>;
 	type GasToWeight = GasToWeight;
+	type StorageMetering = MockStorageMetering;
 
 	type NetworkContractOrigin = EnsureSignedBy<NetworkContractAccount, AccountId32>;
 	type NetworkContractSource = NetworkContractSource;
+	type OnTransactionPayment = ();
+	type MinGasPrice = MinGasPrice;
+	type GasPriceMultiplier = MockGasPriceMultiplier;
+	type DeploymentOrigin = EnsureSignedBy<NetworkContractAccount, AccountId32>;
+	type DeveloperDeposit = DeveloperDeposit;
 	type WeightInfo = ();
 }
 
@@ -244,6 +314,7 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	GenesisConfig::<Test> {
 		accounts,
 		network_contract_index: NETWORK_CONTRACT_INDEX,
+		open_deployment: true,
 	}
 	.assimilate_storage(&mut t)
 	.unwrap();
@@ -14,11 +14,15 @@ pub trait Precompiles {
 	/// Otherwise, calculate the amount of gas needed with given `input` and
 	/// `target_gas`. Return `Some(Ok(status, output, gas_used))` if the
 	/// execution is successful. Otherwise return `Some(Err(_))`.
+	///
+	/// `is_static` is true in the static calls, which must not change the
+	/// state.
 	fn execute(
 		address: H160,
 		input: &[u8],
 		target_gas: Option<usize>,
 		context: &Context,
+		is_static: bool,
 	) -> Option<core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError>>;
 }
 
@@ -27,10 +31,14 @@ pub trait Precompile {
 	/// Try to execute the precompile. Calculate the amount of gas needed with
 	/// given `input` and `target_gas`. Return `Ok(status, output, gas_used)` if
 	/// the execution is successful. Otherwise return `Err(_)`.
+	///
+	/// `is_static` is true in the static calls, which must not change the
+	/// state.
 	fn execute(
 		input: &[u8],
 		target_gas: Option<usize>,
 		context: &Context,
+		is_static: bool,
 	) -> core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError>;
 }
 
@@ -44,13 +52,14 @@ impl Precompiles for Tuple {
 		input: &[u8],
 		target_gas: Option<usize>,
 		context: &Context,
+		is_static: bool,
 	) -> Option<core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError>> {
 		let mut index = 0;
 
 		for_tuples!( #(
 			index += 1;
 			if address == H160::from_low_u64_be(index) {
-				return Some(Tuple::execute(input, target_gas, context))
+				return Some(Tuple::execute(input, target_gas, context, is_static))
 			}
 		)* );
 
@@ -84,6 +93,7 @@ impl Precompile for Identity {
 		input: &[u8],
 		target_gas: Option<usize>,
 		_context: &Context,
+		_is_static: bool,
 	) -> core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError> {
 		let cost = ensure_linear_cost(target_gas, input.len(), 15, 3)?;
 
@@ -99,6 +109,7 @@ impl Precompile for ECRecover {
 		i: &[u8],
 		target_gas: Option<usize>,
 		_context: &Context,
+		_is_static: bool,
 	) -> core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError> {
 		let cost = ensure_linear_cost(target_gas, i.len(), 3000, 0)?;
 
@@ -130,6 +141,7 @@ impl Precompile for Ripemd160 {
 		input: &[u8],
 		target_gas: Option<usize>,
 		_context: &Context,
+		_is_static: bool,
 	) -> core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError> {
 		let cost = ensure_linear_cost(target_gas, input.len(), 600, 120)?;
 
@@ -147,6 +159,7 @@ impl Precompile for Sha256 {
 		input: &[u8],
 		target_gas: Option<usize>,
 		_context: &Context,
+		_is_static: bool,
 	) -> core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError> {
 		let cost = ensure_linear_cost(target_gas, input.len(), 60, 12)?;
 
@@ -1,8 +1,8 @@
 #![allow(clippy::type_complexity)]
 
 use crate::{
-	AccountInfo, AccountStorages, Accounts, AddressMapping, BalanceOf, Codes, Config, Event, Log, MergeAccount, Module,
-	Vicinity,
+	runner::tracing, AccountInfo, AccountStorages, Accounts, AddressMapping, BalanceOf, Codes, Config, Event, Log,
+	MergeAccount, Module, StorageGasMetering, Vicinity, COLD_SLOAD_COST, WARM_STORAGE_READ_COST,
 };
 use evm::{
 	Capture, Context, CreateScheme, ExitError, ExitReason, ExitSucceed, ExternalOpcode, Opcode, Runtime, Stack,
@@ -15,21 +15,41 @@ use frame_support::{
 	storage::{StorageDoubleMap, StorageMap},
 	traits::{Currency, ExistenceRequirement, Get, ReservableCurrency},
 };
+use primitives::evm::CallType;
 use sha3::{Digest, Keccak256};
 use sp_core::{H160, H256, U256};
 use sp_runtime::{
 	traits::{One, UniqueSaturatedInto, Zero},
 	SaturatedConversion, TransactionOutcome,
 };
-use sp_std::{cmp::min, convert::Infallible, marker::PhantomData, rc::Rc, vec::Vec};
+use sp_std::{
+	cell::RefCell,
+	cmp::min,
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	convert::Infallible,
+	marker::PhantomData,
+	rc::Rc,
+	vec::Vec,
+};
+
+/// The state shared by the handlers of a transaction.
+#[derive(Default)]
+pub struct TransactionState {
+	/// The values of storages before the transaction, recorded on the first
+	/// write.
+	pub original_storages: BTreeMap<(H160, H256), H256>,
+	/// The storages accessed in the transaction, for EIP-2929 metering.
+	pub accessed_storages: BTreeSet<(H160, H256)>,
+}
 
 pub struct Handler<'vicinity, 'config, T: Config> {
 	pub vicinity: &'vicinity Vicinity,
 	pub config: &'config EvmRuntimeConfig,
 	pub gasometer: Gasometer<'config>,
 	pub precompile:
-		fn(H160, &[u8], Option<usize>, &Context) -> Option<Result<(ExitSucceed, Vec<u8>, usize), ExitError>>,
+		fn(H160, &[u8], Option<usize>, &Context, bool) -> Option<Result<(ExitSucceed, Vec<u8>, usize), ExitError>>,
 	pub is_static: bool,
+	pub state: Rc<RefCell<TransactionState>>,
 	pub _marker: PhantomData<T>,
 }
 
@@ -49,6 +69,7 @@ impl<'vicinity, 'config, T: Config> Handler<'vicinity, 'config, T> {
 			&[u8],
 			Option<usize>,
 			&Context,
+			bool,
 		) -> Option<Result<(ExitSucceed, Vec<u8>, usize), ExitError>>,
 	) -> Self {
 		Self {
@@ -57,6 +78,20 @@ impl<'vicinity, 'config, T: Config> Handler<'vicinity, 'config, T> {
 			is_static,
 			gasometer: Gasometer::new(gas_limit, config),
 			precompile,
+			state: Default::default(),
+			_marker: PhantomData,
+		}
+	}
+
+	/// Create a handler for a nested call or create of the transaction.
+	fn substate(&self, gas_limit: usize, is_static: bool) -> Self {
+		Self {
+			vicinity: self.vicinity,
+			config: self.config,
+			is_static,
+			gasometer: Gasometer::new(gas_limit, self.config),
+			precompile: self.precompile,
+			state: self.state.clone(),
 			_marker: PhantomData,
 		}
 	}
@@ -225,11 +260,14 @@ impl<'vicinity, 'config, T: Config> HandlerT for Handler<'vicinity, 'config, T>
 		AccountStorages::get(address, index)
 	}
 
-	fn original_storage(&self, _address: H160, _index: H256) -> H256 {
-		// We do not have the concept of original storage in the native runner, so we
-		// always return empty value. This only affects gas calculation in the current
-		// EVM specification.
-		H256::default()
+	fn original_storage(&self, address: H160, index: H256) -> H256 {
+		// the storage is not written in the transaction if not recorded
+		self.state
+			.borrow()
+			.original_storages
+			.get(&(address, index))
+			.copied()
+			.unwrap_or_else(|| AccountStorages::get(address, index))
 	}
 
 	fn gas_left(&self) -> U256 {
@@ -294,6 +332,12 @@ impl<'vicinity, 'config, T: Config> HandlerT for Handler<'vicinity, 'config, T>
 			return Err(ExitError::OutOfGas);
 		}
 
+		self.state
+			.borrow_mut()
+			.original_storages
+			.entry((address, index))
+			.or_insert_with(|| AccountStorages::get(address, index));
+
 		<Module<T>>::set_storage(address, index, value)
 	}
 
@@ -387,8 +431,7 @@ impl<'vicinity, 'config, T: Config> HandlerT for Handler<'vicinity, 'config, T>
 		target_gas = min(target_gas, after_gas);
 		try_or_fail!(self.gasometer.record_cost(target_gas));
 
-		let mut substate =
-			Self::new_with_precompile(self.vicinity, target_gas, self.is_static, self.config, self.precompile);
+		let mut substate = self.substate(target_gas, self.is_static);
 
 		let address = self.create_address(scheme);
 		substate.inc_nonce(caller);
@@ -414,7 +457,9 @@ impl<'vicinity, 'config, T: Config> HandlerT for Handler<'vicinity, 'config, T>
 			try_or_rollback!(self.transfer(contract_existential_deposit));
 			try_or_rollback!(self.reserve(address, T::ContractExistentialDeposit::get()));
 
+			tracing::enter(CallType::Create, caller, address, value, &init_code, target_gas, false);
 			let (reason, out) = substate.execute(caller, address, value, init_code, Vec::new());
+			tracing::exit(&reason, &out, substate.gasometer.total_used_gas());
 
 			match reason {
 				ExitReason::Succeed(s) => match self.gasometer.record_deposit(out.len()) {
@@ -479,29 +524,56 @@ impl<'vicinity, 'config, T: Config> HandlerT for Handler<'vicinity, 'config, T>
 		let code = self.code(code_address);
 
 		frame_support::storage::with_transaction(|| {
-			let mut substate = Self::new_with_precompile(
-				self.vicinity,
-				target_gas,
-				self.is_static || is_static,
-				self.config,
-				self.precompile,
-			);
+			let mut substate = self.substate(target_gas, self.is_static || is_static);
 
 			if let Some(transfer) = transfer {
 				try_or_rollback!(self.transfer(transfer));
 			}
 
-			if let Some(ret) = (substate.precompile)(code_address, &input, Some(target_gas), &context) {
+			let call_type = if is_static {
+				CallType::StaticCall
+			} else if context.address != code_address {
+				CallType::DelegateCall
+			} else {
+				CallType::Call
+			};
+
+			if let Some(ret) =
+				(substate.precompile)(code_address, &input, Some(target_gas), &context, substate.is_static)
+			{
+				tracing::enter(
+					call_type,
+					context.caller,
+					code_address,
+					context.apparent_value,
+					&input,
+					target_gas,
+					true,
+				);
 				return match ret {
 					Ok((s, out, cost)) => {
+						tracing::exit(&ExitReason::Succeed(s.clone()), &out, cost);
 						try_or_rollback!(self.gasometer.record_cost(cost));
 						TransactionOutcome::Commit(Capture::Exit((s.into(), out)))
 					}
-					Err(e) => TransactionOutcome::Rollback(Capture::Exit((e.into(), Vec::new()))),
+					Err(e) => {
+						tracing::exit(&ExitReason::Error(e.clone()), &[], target_gas);
+						TransactionOutcome::Rollback(Capture::Exit((e.into(), Vec::new())))
+					}
 				};
 			}
 
+			tracing::enter(
+				call_type,
+				context.caller,
+				code_address,
+				context.apparent_value,
+				&input,
+				target_gas,
+				false,
+			);
 			let (reason, out) = substate.execute(context.caller, context.address, context.apparent_value, code, input);
+			tracing::exit(&reason, &out, substate.gasometer.total_used_gas());
 
 			match reason {
 				ExitReason::Succeed(s) => {
@@ -526,11 +598,33 @@ impl<'vicinity, 'config, T: Config> HandlerT for Handler<'vicinity, 'config, T>
 		opcode: Result<Opcode, ExternalOpcode>,
 		stack: &Stack,
 	) -> Result<(), ExitError> {
+		let gas = self.gasometer.gas();
 		let (gas_cost, memory_cost) =
 			gasometer::opcode_cost(context.address, opcode, stack, self.is_static, &self.config, self)?;
 
 		self.gasometer.record_opcode(gas_cost, memory_cost)?;
 
+		if T::StorageMetering::get() == StorageGasMetering::Eip2929 {
+			let cold_cost = match opcode {
+				Err(ExternalOpcode::SLoad) => COLD_SLOAD_COST - WARM_STORAGE_READ_COST,
+				Err(ExternalOpcode::SStore) => COLD_SLOAD_COST,
+				_ => 0,
+			};
+			if cold_cost > 0 {
+				let index = stack.peek(0)?;
+				if self
+					.state
+					.borrow_mut()
+					.accessed_storages
+					.insert((context.address, index))
+				{
+					self.gasometer.record_cost(cold_cost)?;
+				}
+			}
+		}
+
+		tracing::step(opcode, stack, gas, gas - self.gasometer.gas());
+
 		Ok(())
 	}
 }
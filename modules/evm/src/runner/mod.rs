@@ -1,5 +1,6 @@
 pub mod handler;
 pub mod native;
+pub mod tracing;
 
 use crate::{BalanceOf, Config};
 use primitives::evm::{CallInfo, CreateInfo};
@@ -7,6 +8,8 @@ use sp_core::{H160, H256};
 use sp_runtime::DispatchError;
 use sp_std::vec::Vec;
 
+/// Execute the EVM calls. The `gas_price` is returned by the `GASPRICE`
+/// opcode, `Module::gas_price` by default.
 pub trait Runner<T: Config> {
 	fn call(
 		source: H160,
@@ -14,6 +17,7 @@ pub trait Runner<T: Config> {
 		input: Vec<u8>,
 		value: BalanceOf<T>,
 		gas_limit: u32,
+		gas_price: Option<BalanceOf<T>>,
 		config: &evm::Config,
 	) -> Result<CallInfo, DispatchError>;
 
@@ -22,6 +26,7 @@ pub trait Runner<T: Config> {
 		init: Vec<u8>,
 		value: BalanceOf<T>,
 		gas_limit: u32,
+		gas_price: Option<BalanceOf<T>>,
 		config: &evm::Config,
 	) -> Result<CreateInfo, DispatchError>;
 
@@ -31,6 +36,7 @@ pub trait Runner<T: Config> {
 		salt: H256,
 		value: BalanceOf<T>,
 		gas_limit: u32,
+		gas_price: Option<BalanceOf<T>>,
 		config: &evm::Config,
 	) -> Result<CreateInfo, DispatchError>;
 
@@ -40,6 +46,7 @@ pub trait Runner<T: Config> {
 		value: BalanceOf<T>,
 		assigned_address: H160,
 		gas_limit: u32,
+		gas_price: Option<BalanceOf<T>>,
 		config: &evm::Config,
 	) -> Result<CreateInfo, DispatchError>;
 }
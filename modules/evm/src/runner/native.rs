@@ -1,17 +1,18 @@
 //! Native EVM runner.
 #![allow(clippy::type_complexity)]
 
-use crate::runner::handler::Handler;
+use crate::runner::{handler::Handler, tracing};
 use crate::{
 	precompiles::Precompiles, AddressMapping, BalanceOf, CallInfo, Config, CreateInfo, Module, Runner as RunnerT,
 	Vicinity,
 };
-use evm::CreateScheme;
+use evm::{Context, CreateScheme, ExitReason};
 use evm_runtime::Handler as HandlerT;
 use frame_support::{
 	debug,
 	traits::{Currency, ExistenceRequirement, Get, ReservableCurrency},
 };
+use primitives::evm::CallType;
 use sha3::{Digest, Keccak256};
 use sp_core::{H160, H256, U256};
 use sp_runtime::{DispatchError, SaturatedConversion, TransactionOutcome};
@@ -28,6 +29,7 @@ impl<T: Config> Runner<T> {
 		init: Vec<u8>,
 		value: BalanceOf<T>,
 		gas_limit: u32,
+		gas_price: Option<BalanceOf<T>>,
 		assigned_address: Option<H160>,
 		salt: Option<H256>,
 		tag: &'static str,
@@ -42,7 +44,7 @@ impl<T: Config> Runner<T> {
 		);
 
 		let vicinity = Vicinity {
-			gas_price: U256::one(),
+			gas_price: Self::gas_price(gas_price),
 			origin: source,
 			creating: true,
 		};
@@ -77,13 +79,18 @@ impl<T: Config> Runner<T> {
 				return TransactionOutcome::Rollback(Err(e));
 			}
 
-			let (reason, out) = substate.execute(
+			let value = U256::from(value.saturated_into::<u128>());
+			tracing::enter(
+				CallType::Create,
 				source,
 				address,
-				U256::from(value.saturated_into::<u128>()),
-				init,
-				Vec::new(),
+				value,
+				&init,
+				gas_limit as usize,
+				false,
 			);
+			let (reason, out) = substate.execute(source, address, value, init, Vec::new());
+			tracing::exit(&reason, &out, substate.used_gas());
 
 			let mut create_info = CreateInfo {
 				exit_reason: reason.clone(),
@@ -122,6 +129,11 @@ impl<T: Config> Runner<T> {
 		})
 	}
 
+	fn gas_price(gas_price: Option<BalanceOf<T>>) -> U256 {
+		let gas_price = gas_price.unwrap_or_else(Module::<T>::gas_price);
+		U256::from(gas_price.saturated_into::<u128>())
+	}
+
 	fn transfer(source: H160, target: H160, value: BalanceOf<T>) -> Result<(), DispatchError> {
 		let from = T::AddressMapping::to_account(&source);
 		let to = T::AddressMapping::to_account(&target);
@@ -148,6 +160,7 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 		input: Vec<u8>,
 		value: BalanceOf<T>,
 		gas_limit: u32,
+		gas_price: Option<BalanceOf<T>>,
 		config: &evm::Config,
 	) -> Result<CallInfo, DispatchError> {
 		debug::debug!(
@@ -159,7 +172,7 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 		);
 
 		let vicinity = Vicinity {
-			gas_price: U256::one(),
+			gas_price: Self::gas_price(gas_price),
 			origin: source,
 			creating: false,
 		};
@@ -175,8 +188,32 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 			}
 
 			let code = substate.code(target);
-			let (reason, out) =
-				substate.execute(source, target, U256::from(value.saturated_into::<u128>()), code, input);
+			let value = U256::from(value.saturated_into::<u128>());
+			let context = Context {
+				caller: source,
+				address: target,
+				apparent_value: value,
+			};
+			// the precompiles acting as contracts can be called by the transactions
+			let precompile = T::Precompiles::execute(target, &input, Some(gas_limit as usize), &context, false);
+			tracing::enter(
+				CallType::Call,
+				source,
+				target,
+				value,
+				&input,
+				gas_limit as usize,
+				precompile.is_some(),
+			);
+			let (reason, out) = match precompile {
+				Some(Ok((s, out, cost))) => match substate.gasometer.record_cost(cost) {
+					Ok(()) => (ExitReason::Succeed(s), out),
+					Err(e) => (ExitReason::Error(e), Vec::new()),
+				},
+				Some(Err(e)) => (ExitReason::Error(e), Vec::new()),
+				None => substate.execute(source, target, value, code, input),
+			};
+			tracing::exit(&reason, &out, substate.used_gas());
 
 			let call_info = CallInfo {
 				exit_reason: reason.clone(),
@@ -203,9 +240,10 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 		init: Vec<u8>,
 		value: BalanceOf<T>,
 		gas_limit: u32,
+		gas_price: Option<BalanceOf<T>>,
 		config: &evm::Config,
 	) -> Result<CreateInfo, DispatchError> {
-		Self::inner_create(source, init, value, gas_limit, None, None, "create", config)
+		Self::inner_create(source, init, value, gas_limit, gas_price, None, None, "create", config)
 	}
 
 	fn create2(
@@ -214,9 +252,20 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 		salt: H256,
 		value: BalanceOf<T>,
 		gas_limit: u32,
+		gas_price: Option<BalanceOf<T>>,
 		config: &evm::Config,
 	) -> Result<CreateInfo, DispatchError> {
-		Self::inner_create(source, init, value, gas_limit, None, Some(salt), "create2", config)
+		Self::inner_create(
+			source,
+			init,
+			value,
+			gas_limit,
+			gas_price,
+			None,
+			Some(salt),
+			"create2",
+			config,
+		)
 	}
 
 	fn create_at_address(
@@ -225,6 +274,7 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 		value: BalanceOf<T>,
 		assigned_address: H160,
 		gas_limit: u32,
+		gas_price: Option<BalanceOf<T>>,
 		config: &evm::Config,
 	) -> Result<CreateInfo, DispatchError> {
 		Self::inner_create(
@@ -232,6 +282,7 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 			init,
 			value,
 			gas_limit,
+			gas_price,
 			Some(assigned_address),
 			None,
 			"create-system-contract",
@@ -0,0 +1,151 @@
+//! Opt-in tracing of the native EVM runner.
+//!
+//! The tracer is only compiled with the `tracing` feature in std, otherwise
+//! the hooks are no-ops. A trace is recorded by running the execution inside
+//! `using`.
+
+use evm::{ExitReason, ExternalOpcode, Opcode, Stack};
+use primitives::evm::CallType;
+use sp_core::{H160, U256};
+
+#[cfg(all(feature = "std", feature = "tracing"))]
+pub use self::tracer::using;
+
+#[cfg(all(feature = "std", feature = "tracing"))]
+mod tracer {
+	use super::*;
+	use primitives::evm::{CallTrace, TraceResult, TraceStep, TracerKind};
+	use std::cell::RefCell;
+
+	struct Tracer {
+		kind: TracerKind,
+		/// The open frames, the last one is the current frame.
+		frames: Vec<CallTrace>,
+		calls: Vec<CallTrace>,
+		steps: Vec<TraceStep>,
+	}
+
+	thread_local! {
+		static TRACER: RefCell<Option<Tracer>> = RefCell::new(None);
+	}
+
+	/// Run `f` with the tracer of `kind`, returns the result of `f` and the
+	/// recorded trace.
+	pub fn using<R>(kind: TracerKind, f: impl FnOnce() -> R) -> (R, TraceResult) {
+		TRACER.with(|tracer| {
+			*tracer.borrow_mut() = Some(Tracer {
+				kind,
+				frames: Vec::new(),
+				calls: Vec::new(),
+				steps: Vec::new(),
+			})
+		});
+
+		let result = f();
+
+		let tracer = TRACER
+			.with(|tracer| tracer.borrow_mut().take())
+			.expect("tracer is set before execution; qed");
+		let mut calls = tracer.calls;
+		// close the frames that never exited, the execution was aborted
+		let mut frames = tracer.frames;
+		while let Some(frame) = frames.pop() {
+			match frames.last_mut() {
+				Some(parent) => parent.calls.push(frame),
+				None => calls.push(frame),
+			}
+		}
+
+		let trace = match tracer.kind {
+			TracerKind::CallTracer => TraceResult::Calls(calls),
+			TracerKind::OpcodeTracer => TraceResult::Steps(tracer.steps),
+		};
+		(result, trace)
+	}
+
+	fn with_tracer(f: impl FnOnce(&mut Tracer)) {
+		TRACER.with(|tracer| {
+			if let Some(tracer) = tracer.borrow_mut().as_mut() {
+				f(tracer)
+			}
+		})
+	}
+
+	pub fn enter(call_type: CallType, from: H160, to: H160, value: U256, input: &[u8], gas: usize, precompile: bool) {
+		with_tracer(|tracer| {
+			tracer.frames.push(CallTrace {
+				call_type,
+				from,
+				to,
+				value,
+				gas: gas as u64,
+				gas_used: 0,
+				input: input.to_vec(),
+				output: Vec::new(),
+				exit_reason: None,
+				precompile,
+				calls: Vec::new(),
+			})
+		})
+	}
+
+	pub fn exit(reason: &ExitReason, output: &[u8], gas_used: usize) {
+		with_tracer(|tracer| {
+			if let Some(mut frame) = tracer.frames.pop() {
+				frame.exit_reason = Some(reason.clone());
+				frame.output = output.to_vec();
+				frame.gas_used = gas_used as u64;
+				match tracer.frames.last_mut() {
+					Some(parent) => parent.calls.push(frame),
+					None => tracer.calls.push(frame),
+				}
+			}
+		})
+	}
+
+	pub fn step(opcode: Result<Opcode, ExternalOpcode>, stack: &Stack, gas: usize, gas_cost: usize) {
+		with_tracer(|tracer| {
+			if tracer.kind != TracerKind::OpcodeTracer {
+				return;
+			}
+			// the names of geth, e.g. `Push(1)` is `PUSH1`
+			let name = |debug: String| debug.to_uppercase().replace('(', "").replace(')', "");
+			let op = match opcode {
+				Ok(opcode) => name(format!("{:?}", opcode)),
+				Err(ExternalOpcode::Other(opcode)) => format!("opcode 0x{:02x}", opcode),
+				Err(opcode) => name(format!("{:?}", opcode)),
+			};
+			tracer.steps.push(TraceStep {
+				depth: tracer.frames.len() as u32,
+				op: op.into_bytes(),
+				gas: gas as u64,
+				gas_cost: gas_cost as u64,
+				stack: stack.data().clone(),
+			})
+		})
+	}
+}
+
+#[cfg(all(feature = "std", feature = "tracing"))]
+pub(crate) use self::tracer::{enter, exit, step};
+
+#[cfg(not(all(feature = "std", feature = "tracing")))]
+#[inline(always)]
+pub(crate) fn enter(
+	_call_type: CallType,
+	_from: H160,
+	_to: H160,
+	_value: U256,
+	_input: &[u8],
+	_gas: usize,
+	_precompile: bool,
+) {
+}
+
+#[cfg(not(all(feature = "std", feature = "tracing")))]
+#[inline(always)]
+pub(crate) fn exit(_reason: &ExitReason, _output: &[u8], _gas_used: usize) {}
+
+#[cfg(not(all(feature = "std", feature = "tracing")))]
+#[inline(always)]
+pub(crate) fn step(_opcode: Result<Opcode, ExternalOpcode>, _stack: &Stack, _gas: usize, _gas_cost: usize) {}
@@ -3,7 +3,7 @@
 use super::*;
 use mock::*;
 
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, storage::StorageDoubleMap, traits::OnInitialize};
 use sp_core::bytes::{from_hex, to_hex};
 use sp_runtime::{traits::BadOrigin, AccountId32};
 use std::str::FromStr;
@@ -14,6 +14,7 @@ fn fail_call_return_ok() {
 		let mut data = [0u8; 32];
 		data[0..4].copy_from_slice(b"evm:");
 		let signer: AccountId32 = AccountId32::from(data).into();
+		let _ = Balances::deposit_creating(&signer, INITIAL_BALANCE);
 
 		assert_ok!(EVM::call(
 			Origin::signed(signer.clone()),
@@ -86,6 +87,7 @@ fn should_create_and_call_contract() {
 			contract,
 			0,
 			1000000,
+			None,
 			<Test as Config>::config(),
 		).unwrap();
 		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
@@ -106,6 +108,7 @@ fn should_create_and_call_contract() {
 			multiply,
 			0,
 			1000000,
+			None,
 			<Test as Config>::config(),
 		).unwrap();
 		assert_eq!(
@@ -131,7 +134,8 @@ fn create_reverts_with_message() {
 	let contract = from_hex("0x6080604052348015600f57600080fd5b5060006083576040517f08c379a000000000000000000000000000000000000000000000000000000000815260040180806020018281038252600d8152602001807f6572726f72206d6573736167650000000000000000000000000000000000000081525060200191505060405180910390fd5b603e8060906000396000f3fe6080604052600080fdfea265627a7a723158204741083d83bf4e3ee8099dd0b3471c81061237c2e8eccfcb513dfa4c04634b5b64736f6c63430005110032").expect("invalid hex");
 	new_test_ext().execute_with(|| {
 		let result =
-			<Test as Config>::Runner::create(alice(), contract, 0, 12_000_000, <Test as Config>::config()).unwrap();
+			<Test as Config>::Runner::create(alice(), contract, 0, 12_000_000, None, <Test as Config>::config())
+				.unwrap();
 		assert_eq!(result.exit_reason, ExitReason::Revert(ExitRevert::Reverted));
 		assert!(String::from_utf8_lossy(&result.output).contains("error message"));
 	});
@@ -156,6 +160,7 @@ fn call_reverts_with_message() {
 			contract,
 			0,
 			1000000,
+			None,
 			<Test as Config>::config(),
 		).unwrap();
 
@@ -173,6 +178,7 @@ fn call_reverts_with_message() {
 			foo,
 			0,
 			1000000,
+			None,
 			<Test as Config>::config(),
 		).unwrap();
 
@@ -213,7 +219,8 @@ fn should_deploy_payable_contract() {
 		contract.append(&mut stored_value.clone());
 
 		let result =
-			<Test as Config>::Runner::create(alice(), contract, amount, 100000, <Test as Config>::config()).unwrap();
+			<Test as Config>::Runner::create(alice(), contract, amount, 100000, None, <Test as Config>::config())
+				.unwrap();
 		let contract_address = result.address;
 
 		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
@@ -230,6 +237,7 @@ fn should_deploy_payable_contract() {
 			from_hex("0x20965255").unwrap(),
 			amount,
 			100000,
+			None,
 			<Test as Config>::config(),
 		)
 		.unwrap();
@@ -272,7 +280,7 @@ fn should_transfer_from_contract() {
 	new_test_ext().execute_with(|| {
 		let amount = 1000u64;
 
-		let result = <Test as Config>::Runner::create(alice(), contract, 0, 10000000, <Test as Config>::config())
+		let result = <Test as Config>::Runner::create(alice(), contract, 0, 10000000, None, <Test as Config>::config())
 			.expect("create shouldn't fail");
 		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
 		let contract_address = result.address;
@@ -287,6 +295,7 @@ fn should_transfer_from_contract() {
 			via_transfer,
 			amount,
 			1000000,
+			None,
 			<Test as Config>::config(),
 		)
 		.expect("call shouldn't fail");
@@ -308,6 +317,7 @@ fn should_transfer_from_contract() {
 			via_send,
 			amount,
 			1000000,
+			None,
 			<Test as Config>::config(),
 		)
 		.expect("call shouldn't fail");
@@ -329,6 +339,7 @@ fn should_transfer_from_contract() {
 			via_call,
 			amount,
 			1000000,
+			None,
 			<Test as Config>::config(),
 		)
 		.expect("call shouldn't fail");
@@ -358,9 +369,15 @@ fn contract_should_deploy_contracts() {
 	// contract Contract {}
 	let contract = from_hex("0x608060405234801561001057600080fd5b5061016f806100206000396000f3fe608060405260043610610041576000357c0100000000000000000000000000000000000000000000000000000000900463ffffffff168063412a5a6d14610046575b600080fd5b61004e610050565b005b600061005a6100e2565b604051809103906000f080158015610076573d6000803e3d6000fd5b50905060008190806001815401808255809150509060018203906000526020600020016000909192909190916101000a81548173ffffffffffffffffffffffffffffffffffffffff021916908373ffffffffffffffffffffffffffffffffffffffff1602179055505050565b6040516052806100f28339019056fe6080604052348015600f57600080fd5b50603580601d6000396000f3fe6080604052600080fdfea165627a7a7230582092dc1966a8880ddf11e067f9dd56a632c11a78a4afd4a9f05924d427367958cc0029a165627a7a723058202b2cc7384e11c452cdbf39b68dada2d5e10a632cc0174a354b8b8c83237e28a40029").unwrap();
 	new_test_ext().execute_with(|| {
-		let result =
-			<Test as Config>::Runner::create(alice(), contract.clone(), 0, 1000000000, <Test as Config>::config())
-				.unwrap();
+		let result = <Test as Config>::Runner::create(
+			alice(),
+			contract.clone(),
+			0,
+			1000000000,
+			None,
+			<Test as Config>::config(),
+		)
+		.unwrap();
 		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
 		assert_eq!(
 			balance(alice()),
@@ -382,6 +399,7 @@ fn contract_should_deploy_contracts() {
 			create_contract,
 			amount,
 			1000000000,
+			None,
 			<Test as Config>::config(),
 		)
 		.unwrap();
@@ -418,9 +436,15 @@ fn contract_deploy_contracts_failed() {
 	// contract Contract {}
 	let contract = from_hex("0x608060405234801561001057600080fd5b5061016c806100206000396000f3fe608060405234801561001057600080fd5b506004361061002b5760003560e01c8063412a5a6d14610030575b600080fd5b61003861003a565b005b6000604051610048906100d0565b604051809103906000f080158015610064573d6000803e3d6000fd5b50905060008190806001815401808255809150509060018203906000526020600020016000909192909190916101000a81548173ffffffffffffffffffffffffffffffffffffffff021916908373ffffffffffffffffffffffffffffffffffffffff1602179055505050565b605b806100dd8339019056fe6080604052348015600f57600080fd5b50603e80601d6000396000f3fe6080604052600080fdfea265627a7a7231582094976cee5af14bf59c4bae67c79c12eb15de19bc18ad6038f3ee0898273c9c0564736f6c63430005110032a265627a7a72315820e19ae28dbf01eae11c526295a1ac533ea341c74d5724efe43171f6010fc98b3964736f6c63430005110032").unwrap();
 	new_test_ext().execute_with(|| {
-		let result =
-			<Test as Config>::Runner::create(alice(), contract.clone(), 0, 1000000000, <Test as Config>::config())
-				.unwrap();
+		let result = <Test as Config>::Runner::create(
+			alice(),
+			contract.clone(),
+			0,
+			1000000000,
+			None,
+			<Test as Config>::config(),
+		)
+		.unwrap();
 		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
 		assert_eq!(
 			balance(alice()),
@@ -441,6 +465,7 @@ fn contract_deploy_contracts_failed() {
 			create_contract,
 			0,
 			1000000000,
+			None,
 			<Test as Config>::config(),
 		)
 		.unwrap();
@@ -468,7 +493,8 @@ fn deploy_factory() {
 	let contract = from_hex("0x608060405234801561001057600080fd5b5060405161001d90610121565b604051809103906000f080158015610039573d6000803e3d6000fd5b506000806101000a81548173ffffffffffffffffffffffffffffffffffffffff021916908373ffffffffffffffffffffffffffffffffffffffff1602179055506000809054906101000a900473ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1663c29855786040518163ffffffff1660e01b815260040160206040518083038186803b1580156100e057600080fd5b505afa1580156100f4573d6000803e3d6000fd5b505050506040513d602081101561010a57600080fd5b81019080805190602001909291905050505061012d565b60a58061017983390190565b603e8061013b6000396000f3fe6080604052600080fdfea265627a7a7231582064177030ee644a03aaf8d65027df9e0331c8bc4b161de25bfb8aa3142848e0f864736f6c634300051100326080604052348015600f57600080fd5b5060878061001e6000396000f3fe6080604052348015600f57600080fd5b506004361060285760003560e01c8063c298557814602d575b600080fd5b60336049565b6040518082815260200191505060405180910390f35b6000607b90509056fea265627a7a7231582031e5a4abae00962cfe9875df1b5b0d3ce6624e220cb8c714a948794fcddb6b4f64736f6c63430005110032").unwrap();
 	new_test_ext().execute_with(|| {
 		let result =
-			<Test as Config>::Runner::create(alice(), contract, 0, 12_000_000, <Test as Config>::config()).unwrap();
+			<Test as Config>::Runner::create(alice(), contract, 0, 12_000_000, None, <Test as Config>::config())
+				.unwrap();
 		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
 		assert_eq!(result.used_gas.as_u64(), 95_203u64);
 		assert_eq!(
@@ -545,9 +571,15 @@ fn should_add_and_remove_storage_quota() {
 	// contract Contract {}
 	let contract = from_hex("0x608060405234801561001057600080fd5b5061016f806100206000396000f3fe608060405260043610610041576000357c0100000000000000000000000000000000000000000000000000000000900463ffffffff168063412a5a6d14610046575b600080fd5b61004e610050565b005b600061005a6100e2565b604051809103906000f080158015610076573d6000803e3d6000fd5b50905060008190806001815401808255809150509060018203906000526020600020016000909192909190916101000a81548173ffffffffffffffffffffffffffffffffffffffff021916908373ffffffffffffffffffffffffffffffffffffffff1602179055505050565b6040516052806100f28339019056fe6080604052348015600f57600080fd5b50603580601d6000396000f3fe6080604052600080fdfea165627a7a7230582092dc1966a8880ddf11e067f9dd56a632c11a78a4afd4a9f05924d427367958cc0029a165627a7a723058202b2cc7384e11c452cdbf39b68dada2d5e10a632cc0174a354b8b8c83237e28a40029").unwrap();
 	new_test_ext().execute_with(|| {
-		let result =
-			<Test as Config>::Runner::create(alice(), contract.clone(), 0, 1000000000, <Test as Config>::config())
-				.unwrap();
+		let result = <Test as Config>::Runner::create(
+			alice(),
+			contract.clone(),
+			0,
+			1000000000,
+			None,
+			<Test as Config>::config(),
+		)
+		.unwrap();
 		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
 		assert_eq!(
 			balance(alice()),
@@ -574,6 +606,7 @@ fn should_add_and_remove_storage_quota() {
 			create_contract,
 			amount,
 			1000000000,
+			None,
 			<Test as Config>::config(),
 		)
 		.unwrap();
@@ -666,7 +699,8 @@ fn should_request_transfer_maintainer() {
 	let contract = from_hex("0x608060405234801561001057600080fd5b5060405161001d90610121565b604051809103906000f080158015610039573d6000803e3d6000fd5b506000806101000a81548173ffffffffffffffffffffffffffffffffffffffff021916908373ffffffffffffffffffffffffffffffffffffffff1602179055506000809054906101000a900473ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1663c29855786040518163ffffffff1660e01b815260040160206040518083038186803b1580156100e057600080fd5b505afa1580156100f4573d6000803e3d6000fd5b505050506040513d602081101561010a57600080fd5b81019080805190602001909291905050505061012d565b60a58061017983390190565b603e8061013b6000396000f3fe6080604052600080fdfea265627a7a7231582064177030ee644a03aaf8d65027df9e0331c8bc4b161de25bfb8aa3142848e0f864736f6c634300051100326080604052348015600f57600080fd5b5060878061001e6000396000f3fe6080604052348015600f57600080fd5b506004361060285760003560e01c8063c298557814602d575b600080fd5b60336049565b6040518082815260200191505060405180910390f35b6000607b90509056fea265627a7a7231582031e5a4abae00962cfe9875df1b5b0d3ce6624e220cb8c714a948794fcddb6b4f64736f6c63430005110032").unwrap();
 	new_test_ext().execute_with(|| {
 		let result =
-			<Test as Config>::Runner::create(alice(), contract, 0, 12_000_000, <Test as Config>::config()).unwrap();
+			<Test as Config>::Runner::create(alice(), contract, 0, 12_000_000, None, <Test as Config>::config())
+				.unwrap();
 		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
 
 		assert_eq!(
@@ -731,7 +765,8 @@ fn should_cancel_transfer_maintainer() {
 	let contract = from_hex("0x608060405234801561001057600080fd5b5060405161001d90610121565b604051809103906000f080158015610039573d6000803e3d6000fd5b506000806101000a81548173ffffffffffffffffffffffffffffffffffffffff021916908373ffffffffffffffffffffffffffffffffffffffff1602179055506000809054906101000a900473ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1663c29855786040518163ffffffff1660e01b815260040160206040518083038186803b1580156100e057600080fd5b505afa1580156100f4573d6000803e3d6000fd5b505050506040513d602081101561010a57600080fd5b81019080805190602001909291905050505061012d565b60a58061017983390190565b603e8061013b6000396000f3fe6080604052600080fdfea265627a7a7231582064177030ee644a03aaf8d65027df9e0331c8bc4b161de25bfb8aa3142848e0f864736f6c634300051100326080604052348015600f57600080fd5b5060878061001e6000396000f3fe6080604052348015600f57600080fd5b506004361060285760003560e01c8063c298557814602d575b600080fd5b60336049565b6040518082815260200191505060405180910390f35b6000607b90509056fea265627a7a7231582031e5a4abae00962cfe9875df1b5b0d3ce6624e220cb8c714a948794fcddb6b4f64736f6c63430005110032").unwrap();
 	new_test_ext().execute_with(|| {
 		let result =
-			<Test as Config>::Runner::create(alice(), contract, 0, 12_000_000, <Test as Config>::config()).unwrap();
+			<Test as Config>::Runner::create(alice(), contract, 0, 12_000_000, None, <Test as Config>::config())
+				.unwrap();
 		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
 
 		assert_eq!(
@@ -788,7 +823,8 @@ fn should_confirm_transfer_maintainer() {
 	let contract = from_hex("0x608060405234801561001057600080fd5b5060405161001d90610121565b604051809103906000f080158015610039573d6000803e3d6000fd5b506000806101000a81548173ffffffffffffffffffffffffffffffffffffffff021916908373ffffffffffffffffffffffffffffffffffffffff1602179055506000809054906101000a900473ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1663c29855786040518163ffffffff1660e01b815260040160206040518083038186803b1580156100e057600080fd5b505afa1580156100f4573d6000803e3d6000fd5b505050506040513d602081101561010a57600080fd5b81019080805190602001909291905050505061012d565b60a58061017983390190565b603e8061013b6000396000f3fe6080604052600080fdfea265627a7a7231582064177030ee644a03aaf8d65027df9e0331c8bc4b161de25bfb8aa3142848e0f864736f6c634300051100326080604052348015600f57600080fd5b5060878061001e6000396000f3fe6080604052348015600f57600080fd5b506004361060285760003560e01c8063c298557814602d575b600080fd5b60336049565b6040518082815260200191505060405180910390f35b6000607b90509056fea265627a7a7231582031e5a4abae00962cfe9875df1b5b0d3ce6624e220cb8c714a948794fcddb6b4f64736f6c63430005110032").unwrap();
 	new_test_ext().execute_with(|| {
 		let result =
-			<Test as Config>::Runner::create(alice(), contract, 0, 12_000_000, <Test as Config>::config()).unwrap();
+			<Test as Config>::Runner::create(alice(), contract, 0, 12_000_000, None, <Test as Config>::config())
+				.unwrap();
 		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
 
 		assert_eq!(
@@ -858,7 +894,8 @@ fn should_reject_transfer_maintainer() {
 	let contract = from_hex("0x608060405234801561001057600080fd5b5060405161001d90610121565b604051809103906000f080158015610039573d6000803e3d6000fd5b506000806101000a81548173ffffffffffffffffffffffffffffffffffffffff021916908373ffffffffffffffffffffffffffffffffffffffff1602179055506000809054906101000a900473ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1663c29855786040518163ffffffff1660e01b815260040160206040518083038186803b1580156100e057600080fd5b505afa1580156100f4573d6000803e3d6000fd5b505050506040513d602081101561010a57600080fd5b81019080805190602001909291905050505061012d565b60a58061017983390190565b603e8061013b6000396000f3fe6080604052600080fdfea265627a7a7231582064177030ee644a03aaf8d65027df9e0331c8bc4b161de25bfb8aa3142848e0f864736f6c634300051100326080604052348015600f57600080fd5b5060878061001e6000396000f3fe6080604052348015600f57600080fd5b506004361060285760003560e01c8063c298557814602d575b600080fd5b60336049565b6040518082815260200191505060405180910390f35b6000607b90509056fea265627a7a7231582031e5a4abae00962cfe9875df1b5b0d3ce6624e220cb8c714a948794fcddb6b4f64736f6c63430005110032").unwrap();
 	new_test_ext().execute_with(|| {
 		let result =
-			<Test as Config>::Runner::create(alice(), contract, 0, 12_000_000, <Test as Config>::config()).unwrap();
+			<Test as Config>::Runner::create(alice(), contract, 0, 12_000_000, None, <Test as Config>::config())
+				.unwrap();
 		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
 
 		assert_eq!(
@@ -906,3 +943,1026 @@ fn should_reject_transfer_maintainer() {
 		);
 	});
 }
+
+#[test]
+fn ethereum_transaction_decode_and_recover_work() {
+	new_test_ext().execute_with(|| {
+		// the example transaction of EIP-155
+		let raw = from_hex("0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83").unwrap();
+		let transaction = EthereumTransaction::decode_rlp(&raw).unwrap();
+
+		assert_eq!(transaction.nonce, U256::from(9));
+		assert_eq!(transaction.gas_price, U256::from(20_000_000_000u64));
+		assert_eq!(transaction.gas_limit, U256::from(21000));
+		assert_eq!(
+			transaction.action,
+			TransactionAction::Call(H160::from_str("3535353535353535353535353535353535353535").unwrap())
+		);
+		assert_eq!(transaction.value, U256::from(1_000_000_000_000_000_000u128));
+		assert_eq!(transaction.input, Vec::<u8>::new());
+		assert_eq!(transaction.chain_id(), Some(1));
+		assert_eq!(
+			transaction.signing_hash(),
+			H256::from_str("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53").unwrap()
+		);
+		assert_eq!(
+			transaction.recover_signer(),
+			Some(H160::from_str("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap())
+		);
+		assert_eq!(transaction.encode_rlp(), raw);
+
+		assert!(EthereumTransaction::decode_rlp(&raw[..raw.len() - 1]).is_err());
+
+		let mut invalid_v = transaction;
+		invalid_v.v = 39;
+		assert_eq!(invalid_v.recover_signer(), None);
+	});
+}
+
+fn sign_transaction(secret: &secp256k1::SecretKey, transaction: EthereumTransaction) -> EthereumTransaction {
+	sign_transaction_with_chain_id(secret, Some(SystemChainId::get()), transaction)
+}
+
+fn sign_transaction_with_chain_id(
+	secret: &secp256k1::SecretKey,
+	chain_id: Option<u64>,
+	mut transaction: EthereumTransaction,
+) -> EthereumTransaction {
+	transaction.v = chain_id.map_or(27, |chain_id| chain_id * 2 + 35);
+	let message = secp256k1::Message::parse(&transaction.signing_hash().0);
+	let (signature, recovery_id) = secp256k1::sign(&message, secret);
+	let signature = signature.serialize();
+	transaction.r = H256::from_slice(&signature[0..32]);
+	transaction.s = H256::from_slice(&signature[32..64]);
+	transaction.v += recovery_id.serialize() as u64;
+	transaction
+}
+
+#[test]
+fn transact_work() {
+	new_test_ext().execute_with(|| {
+		let secret = secp256k1::SecretKey::parse(&[0x46; 32]).unwrap();
+		let public = secp256k1::PublicKey::from_secret_key(&secret);
+		let sender = H160::from_slice(&Keccak256::digest(&public.serialize()[1..65])[12..]);
+		let who = <Test as Config>::AddressMapping::to_account(&sender);
+
+		let transaction = sign_transaction(
+			&secret,
+			EthereumTransaction {
+				nonce: U256::zero(),
+				gas_price: U256::one(),
+				gas_limit: U256::from(100_000),
+				action: TransactionAction::Call(alice()),
+				value: U256::from(100),
+				input: vec![],
+				v: 0,
+				r: Default::default(),
+				s: Default::default(),
+			},
+		);
+		assert_eq!(transaction.recover_signer(), Some(sender));
+
+		let validate = |transaction: &EthereumTransaction| {
+			<EVM as frame_support::unsigned::ValidateUnsigned>::validate_unsigned(
+				TransactionSource::External,
+				&Call::transact(transaction.clone()),
+			)
+		};
+
+		// not enough balance for the value and the max fee
+		assert_eq!(validate(&transaction), InvalidTransaction::Payment.into());
+		let _ = Balances::deposit_creating(&who, 1_000_000);
+		assert!(validate(&transaction).is_ok());
+
+		let mut wrong_chain = transaction.clone();
+		wrong_chain.v += 2;
+		assert_eq!(validate(&wrong_chain), InvalidTransaction::BadProof.into());
+
+		// legacy transaction without chain id is rejected
+		let legacy = sign_transaction_with_chain_id(&secret, None, transaction.clone());
+		assert_eq!(legacy.chain_id(), None);
+		assert_eq!(legacy.recover_signer(), Some(sender));
+		assert_eq!(validate(&legacy), InvalidTransaction::BadProof.into());
+
+		// future transaction waits for the previous one
+		let mut future = transaction.clone();
+		future.nonce = U256::one();
+		let future = sign_transaction(&secret, future);
+		let valid = validate(&future).unwrap();
+		assert_eq!(valid.requires, validate(&transaction).unwrap().provides);
+		assert_eq!(
+			<EVM as frame_support::unsigned::ValidateUnsigned>::pre_dispatch(&Call::transact(future.clone())),
+			Err(InvalidTransaction::Future.into())
+		);
+
+		assert_noop!(
+			EVM::transact(Origin::signed(who.clone()), transaction.clone()),
+			BadOrigin
+		);
+		assert_ok!(EVM::transact(Origin::none(), transaction.clone()));
+
+		let executed_event = TestEvent::evm_mod(RawEvent::Executed(alice()));
+		assert!(System::events().iter().any(|record| record.event == executed_event));
+		assert_eq!(EVM::account_basic(&sender).nonce, U256::one());
+		assert_eq!(balance(alice()), INITIAL_BALANCE + 100);
+		// the fee of unused gas is refunded
+		let balance = Balances::free_balance(&who);
+		assert!(balance < 1_000_000 - 100 && balance > 1_000_000 - 100 - 100_000);
+
+		// replayed transaction is stale
+		assert_eq!(validate(&transaction), InvalidTransaction::Stale.into());
+	});
+}
+
+#[test]
+fn failed_transact_charges_fee_and_nonce() {
+	new_test_ext().execute_with(|| {
+		let secret = secp256k1::SecretKey::parse(&[0x46; 32]).unwrap();
+		let public = secp256k1::PublicKey::from_secret_key(&secret);
+		let sender = H160::from_slice(&Keccak256::digest(&public.serialize()[1..65])[12..]);
+		let who = <Test as Config>::AddressMapping::to_account(&sender);
+		let _ = Balances::deposit_creating(&who, 150_000);
+
+		// the sender can't afford the value after the max fee
+		let transaction = sign_transaction(
+			&secret,
+			EthereumTransaction {
+				nonce: U256::zero(),
+				gas_price: U256::one(),
+				gas_limit: U256::from(100_000),
+				action: TransactionAction::Call(alice()),
+				value: U256::from(100_000),
+				input: vec![],
+				v: 0,
+				r: Default::default(),
+				s: Default::default(),
+			},
+		);
+		assert_ok!(EVM::transact(Origin::none(), transaction.clone()));
+
+		let failed_event = TestEvent::evm_mod(RawEvent::TransactionFailed(
+			sender,
+			pallet_balances::Error::<Test>::InsufficientBalance.into(),
+		));
+		assert!(System::events().iter().any(|record| record.event == failed_event));
+		assert_eq!(EVM::account_basic(&sender).nonce, U256::one());
+		assert_eq!(Balances::free_balance(&who), 50_000);
+		assert_eq!(balance(alice()), INITIAL_BALANCE);
+
+		// the failed transaction can't be replayed
+		assert_eq!(
+			<EVM as frame_support::unsigned::ValidateUnsigned>::validate_unsigned(
+				TransactionSource::External,
+				&Call::transact(transaction),
+			),
+			InvalidTransaction::Stale.into()
+		);
+	});
+}
+
+#[test]
+fn killed_account_should_keep_nonce() {
+	// the `multiply` contract of `should_create_and_call_contract`
+	let contract = from_hex("0x608060405234801561001057600080fd5b5060b88061001f6000396000f3fe6080604052348015600f57600080fd5b506004361060285760003560e01c8063165c4a1614602d575b600080fd5b606060048036036040811015604157600080fd5b8101908080359060200190929190803590602001909291905050506076565b6040518082815260200191505060405180910390f35b600081830290509291505056fea265627a7a723158201f3db7301354b88b310868daf4395a6ab6cd42d16b1d8e68cdf4fdd9d34fffbf64736f6c63430005110032").unwrap();
+
+	new_test_ext().execute_with(|| {
+		let result =
+			<Test as Config>::Runner::create(alice(), contract, 0, 1000000, None, <Test as Config>::config()).unwrap();
+		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+		let nonce = EVM::account_basic(&alice()).nonce;
+
+		// the nonce of the external account stops its transactions being replayed
+		CallKillAccount::<Test>::on_killed_account(&<Test as Config>::AddressMapping::to_account(&alice()));
+		assert_eq!(EVM::account_basic(&alice()).nonce, nonce);
+
+		CallKillAccount::<Test>::on_killed_account(&<Test as Config>::AddressMapping::to_account(&result.address));
+		assert!(EVM::accounts(&result.address).is_none());
+	});
+}
+
+#[test]
+fn scheduled_call_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(EVM::reserve_scheduled_call(alice(), bob(), vec![], 0, 100_000), 0);
+		assert_eq!(
+			EVM::scheduled_calls(0),
+			Some(ScheduledCall {
+				source: alice(),
+				target: bob(),
+				input: vec![],
+				value: 0,
+				gas_limit: 100_000,
+				reserved_fee: 100_000,
+			})
+		);
+		assert_eq!(reserved_balance(alice()), 100_000);
+		let event = TestEvent::evm_mod(RawEvent::CallScheduled(alice(), 0));
+		assert!(System::events().iter().any(|record| record.event == event));
+
+		// only the contract can cancel its scheduled calls
+		assert_noop!(EVM::cancel_scheduled_call(bob(), 0), Error::<Test>::NoPermission);
+		assert_ok!(EVM::cancel_scheduled_call(alice(), 0));
+		assert_eq!(EVM::scheduled_calls(0), None);
+		assert_eq!(reserved_balance(alice()), 0);
+		assert_eq!(balance(alice()), INITIAL_BALANCE);
+		let event = TestEvent::evm_mod(RawEvent::ScheduledCallCanceled(alice(), 0));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert_noop!(
+			EVM::cancel_scheduled_call(alice(), 0),
+			Error::<Test>::ScheduledCallNotFound
+		);
+
+		assert_ok!(EVM::reserve_scheduled_call(bob(), alice(), vec![], 100, 100_000), 1);
+		assert_noop!(
+			EVM::scheduled_call(Origin::signed(Default::default()), 1, 100_000),
+			BadOrigin
+		);
+		// the weight must cover the gas limit of the scheduled call
+		assert_noop!(
+			EVM::scheduled_call(Origin::root(), 1, 99_999),
+			Error::<Test>::ScheduledCallGasLimitTooHigh
+		);
+		assert_ok!(EVM::scheduled_call(Origin::root(), 1, 100_000));
+
+		let event = TestEvent::evm_mod(RawEvent::Executed(alice()));
+		assert!(System::events().iter().any(|record| record.event == event));
+		let event = TestEvent::evm_mod(RawEvent::ScheduledCallDispatched(bob(), 1));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert_eq!(EVM::scheduled_calls(1), None);
+		assert_eq!(balance(alice()), INITIAL_BALANCE + 100);
+		// the fee of unused gas is refunded
+		assert_eq!(reserved_balance(bob()), 0);
+		let balance = balance(bob());
+		assert!(balance <= INITIAL_BALANCE - 100 && balance > INITIAL_BALANCE - 100 - 100_000);
+
+		// dispatched only once
+		assert_noop!(
+			EVM::scheduled_call(Origin::root(), 1, 100_000),
+			Error::<Test>::ScheduledCallNotFound
+		);
+	});
+}
+
+#[test]
+fn failed_scheduled_call_is_removed_and_refunded() {
+	new_test_ext().execute_with(|| {
+		// the value exceeds the balance of the contract
+		assert_ok!(EVM::reserve_scheduled_call(
+			alice(),
+			bob(),
+			vec![],
+			INITIAL_BALANCE,
+			100_000
+		));
+		assert_ok!(EVM::scheduled_call(Origin::root(), 0, 100_000));
+
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			TestEvent::evm_mod(RawEvent::TransactionFailed(source, _)) if source == alice()
+		)));
+		let event = TestEvent::evm_mod(RawEvent::ScheduledCallDispatched(alice(), 0));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert_eq!(EVM::scheduled_calls(0), None);
+		assert_eq!(reserved_balance(alice()), 0);
+		assert_eq!(balance(alice()), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn receipts_from_events_works() {
+	let log = Log {
+		address: bob(),
+		topics: vec![],
+		data: vec![1],
+	};
+	let events = vec![
+		(1, RawEvent::Log(log.clone())),
+		(1, RawEvent::TransactionExecuted(alice(), U256::from(21000))),
+		(1, RawEvent::Executed(bob())),
+		// executed by another module, not an EVM transaction
+		(
+			3,
+			RawEvent::ExecutedFailed(bob(), ExitReason::Revert(ExitRevert::Reverted), vec![]),
+		),
+	];
+
+	assert_eq!(
+		EVM::receipts_from_events(events),
+		vec![
+			ExecutionReceipt {
+				extrinsic_index: 1,
+				from: alice(),
+				to: Some(bob()),
+				contract_address: None,
+				status: true,
+				used_gas: U256::from(21000),
+				logs: vec![log],
+			},
+			ExecutionReceipt {
+				extrinsic_index: 3,
+				from: H160::default(),
+				to: Some(bob()),
+				contract_address: None,
+				status: false,
+				used_gas: U256::zero(),
+				logs: vec![],
+			},
+		]
+	);
+}
+
+#[test]
+fn signed_call_pays_gas_price() {
+	new_test_ext().execute_with(|| {
+		let alice_account_id = <Test as Config>::AddressMapping::to_account(&alice());
+		MockGasPriceMultiplier::set(FixedU128::saturating_from_integer(3));
+
+		let post_info = EVM::call(Origin::signed(alice_account_id), bob(), Vec::new(), 0, 1_000_000).unwrap();
+		// the weight fee is refunded by the transaction payment
+		assert_eq!(post_info.pays_fee, Pays::No);
+
+		let used_gas = System::events()
+			.iter()
+			.find_map(|record| match record.event {
+				TestEvent::evm_mod(RawEvent::TransactionExecuted(source, used_gas)) if source == alice() => {
+					Some(used_gas)
+				}
+				_ => None,
+			})
+			.unwrap();
+		assert_eq!(
+			post_info.actual_weight,
+			Some(GasToWeight::convert(used_gas.unique_saturated_into()))
+		);
+		assert_eq!(balance(alice()), INITIAL_BALANCE - 3 * used_gas.low_u64());
+	});
+}
+
+#[test]
+fn gas_price_works() {
+	// returns `GASPRICE`
+	let contract = from_hex("0x6009600c60003960096000f33a60005260206000f3").unwrap();
+	new_test_ext().execute_with(|| {
+		let address = <Test as Config>::Runner::create(alice(), contract, 0, 1000000, None, <Test as Config>::config())
+			.unwrap()
+			.address;
+		let gas_price_of = |gas_price: Option<u64>| {
+			let info = <Test as Config>::Runner::call(
+				alice(),
+				address,
+				vec![],
+				0,
+				1000000,
+				gas_price,
+				<Test as Config>::config(),
+			)
+			.unwrap();
+			U256::from_big_endian(&info.output)
+		};
+
+		assert_eq!(EVM::gas_price(), 1);
+		assert_eq!(gas_price_of(None), U256::one());
+		assert_eq!(gas_price_of(Some(5)), U256::from(5));
+
+		// the congestion raises the gas price
+		MockGasPriceMultiplier::set(FixedU128::saturating_from_integer(3));
+		assert_eq!(EVM::gas_price(), 3);
+		assert_eq!(gas_price_of(None), U256::from(3));
+
+		// but not below the min gas price
+		MockGasPriceMultiplier::set(FixedU128::saturating_from_rational(1, 2));
+		assert_eq!(EVM::gas_price(), 1);
+
+		MockGasPriceMultiplier::set(FixedU128::saturating_from_integer(3));
+		let secret = secp256k1::SecretKey::parse(&[0x46; 32]).unwrap();
+		let public = secp256k1::PublicKey::from_secret_key(&secret);
+		let sender = H160::from_slice(&Keccak256::digest(&public.serialize()[1..65])[12..]);
+		let who = <Test as Config>::AddressMapping::to_account(&sender);
+		let _ = Balances::deposit_creating(&who, 1_000_000);
+
+		let transaction_of = |gas_price: u64| {
+			sign_transaction(
+				&secret,
+				EthereumTransaction {
+					nonce: U256::zero(),
+					gas_price: U256::from(gas_price),
+					gas_limit: U256::from(1_000),
+					action: TransactionAction::Call(address),
+					value: U256::zero(),
+					input: vec![],
+					v: 0,
+					r: Default::default(),
+					s: Default::default(),
+				},
+			)
+		};
+		let validate = |transaction: &EthereumTransaction| {
+			<EVM as frame_support::unsigned::ValidateUnsigned>::validate_unsigned(
+				TransactionSource::External,
+				&Call::transact(transaction.clone()),
+			)
+		};
+
+		// pay less than the gas price
+		assert_eq!(validate(&transaction_of(2)), InvalidTransaction::Payment.into());
+		assert_noop!(
+			EVM::transact(Origin::none(), transaction_of(2)),
+			Error::<Test>::GasPriceTooLow
+		);
+
+		// the priority fee raises the priority
+		let priority = validate(&transaction_of(3)).unwrap().priority;
+		assert!(validate(&transaction_of(4)).unwrap().priority > priority);
+
+		// the fee is paid at the price of the transaction
+		assert_ok!(EVM::transact(Origin::none(), transaction_of(4)));
+		assert_eq!((1_000_000 - Balances::free_balance(&who)) % 4, 0);
+	});
+}
+
+/// Execute `code` at a contract whose storage 0 is `original`, returns the
+/// used gas and the refunded gas.
+fn storage_gas_of(metering: StorageGasMetering, code: &str, original: u64) -> (usize, isize) {
+	MockStorageMetering::set(metering);
+	new_test_ext().execute_with(|| {
+		let contract = H160::from_low_u64_be(0x1000);
+		if original != 0 {
+			AccountStorages::insert(contract, H256::zero(), H256::from_low_u64_be(original));
+		}
+
+		let vicinity = Vicinity {
+			gas_price: U256::one(),
+			origin: alice(),
+			creating: false,
+		};
+		let mut handler = crate::runner::handler::Handler::<Test>::new_with_precompile(
+			&vicinity,
+			1_000_000,
+			false,
+			<Test as Config>::config(),
+			<Test as Config>::Precompiles::execute,
+		);
+		let (reason, _) = handler.execute(alice(), contract, U256::zero(), from_hex(code).unwrap(), vec![]);
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+
+		(handler.gasometer.total_used_gas(), handler.gasometer.refunded_gas())
+	})
+}
+
+#[test]
+fn eip2200_storage_gas_metering_works() {
+	// https://eips.ethereum.org/EIPS/eip-2200#test-cases
+	let cases = [
+		("0x60006000556000600055", 0, 1612, 0),
+		("0x60006000556001600055", 0, 20812, 0),
+		("0x60016000556000600055", 0, 20812, 19200),
+		("0x60016000556002600055", 0, 20812, 0),
+		("0x60016000556001600055", 0, 20812, 0),
+		("0x60006000556000600055", 1, 5812, 15000),
+		("0x60006000556001600055", 1, 5812, 4200),
+		("0x60006000556002600055", 1, 5812, 0),
+		("0x60026000556000600055", 1, 5812, 15000),
+		("0x60026000556003600055", 1, 5812, 0),
+		("0x60026000556001600055", 1, 5812, 4200),
+		("0x60026000556002600055", 1, 5812, 0),
+		("0x60016000556000600055", 1, 5812, 15000),
+		("0x60016000556002600055", 1, 5812, 0),
+		("0x60016000556001600055", 1, 1612, 0),
+		("0x600160005560006000556001600055", 0, 40818, 19200),
+		("0x600060005560016000556000600055", 1, 10818, 19200),
+	];
+	for (code, original, used_gas, refund) in cases.iter() {
+		assert_eq!(
+			storage_gas_of(StorageGasMetering::Eip2200, code, *original),
+			(*used_gas, *refund),
+			"code: {}, original: {}",
+			code,
+			original
+		);
+	}
+}
+
+#[test]
+fn eip2929_storage_gas_metering_works() {
+	let cases = [
+		// cold SSTORE no-op, then warm
+		("0x60006000556000600055", 0, 2312, 0),
+		("0x60016000556000600055", 0, 22212, 19900),
+		("0x60006000556001600055", 1, 5112, 2800),
+		("0x600160005560006000556001600055", 0, 42218, 19900),
+		// cold SLOAD, then warm
+		("0x600054600054", 0, 2206, 0),
+	];
+	for (code, original, used_gas, refund) in cases.iter() {
+		assert_eq!(
+			storage_gas_of(StorageGasMetering::Eip2929, code, *original),
+			(*used_gas, *refund),
+			"code: {}, original: {}",
+			code,
+			original
+		);
+	}
+}
+
+#[test]
+fn acala_storage_gas_metering_has_no_refund() {
+	assert_eq!(
+		storage_gas_of(StorageGasMetering::Acala, "0x60016000556000600055", 0),
+		(25012, 0)
+	);
+	assert_eq!(
+		storage_gas_of(StorageGasMetering::Acala, "0x60006000556000600055", 1),
+		(10012, 0)
+	);
+}
+
+// PUSH1 1 PUSH1 0 SSTORE, and returns the code `STOP`
+const RENT_CONTRACT: &str = "0x6001600055600060005360016000f3";
+
+#[test]
+fn collect_rent_works() {
+	new_test_ext().execute_with(|| {
+		let result = <Test as Config>::Runner::create(
+			alice(),
+			from_hex(RENT_CONTRACT).unwrap(),
+			0,
+			1_000_000,
+			None,
+			<Test as Config>::config(),
+		)
+		.unwrap();
+		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+		let contract = result.address;
+		assert_eq!(
+			EVM::contract_rents(contract),
+			Some(RentInfo {
+				paid_until: 1,
+				overdue_since: None,
+				next_collection: 11
+			})
+		);
+
+		let bob_account_id = <Test as Config>::AddressMapping::to_account(&bob());
+		assert_noop!(
+			EVM::collect_rent(Origin::signed(bob_account_id.clone()), H160::default(), 0),
+			Error::<Test>::ContractNotFound
+		);
+
+		// one storage and one byte of code for 10 blocks
+		let alice_balance = balance(alice());
+		System::set_block_number(11);
+		assert_ok!(EVM::collect_rent(Origin::signed(bob_account_id), contract, 0));
+		assert_eq!(balance(alice()), alice_balance - 650);
+		let event = TestEvent::evm_mod(RawEvent::RentCollected(contract, 650));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert_eq!(
+			EVM::contract_rents(contract),
+			Some(RentInfo {
+				paid_until: 11,
+				overdue_since: None,
+				next_collection: 11
+			})
+		);
+	});
+}
+
+#[test]
+fn collect_rent_on_initialize_works() {
+	new_test_ext().execute_with(|| {
+		let contracts: Vec<H160> = (0..3)
+			.map(|_| {
+				<Test as Config>::Runner::create(
+					alice(),
+					from_hex(RENT_CONTRACT).unwrap(),
+					0,
+					1_000_000,
+					None,
+					<Test as Config>::config(),
+				)
+				.unwrap()
+				.address
+			})
+			.collect();
+		// at most `MaxRentCollections` contracts are collected in a block
+		assert_eq!(EVM::rent_collections(11), vec![contracts[0], contracts[1]]);
+		assert_eq!(EVM::rent_collections(12), vec![contracts[2]]);
+
+		let alice_balance = balance(alice());
+		System::set_block_number(11);
+		EVM::on_initialize(11);
+		assert_eq!(balance(alice()), alice_balance - 2 * 650);
+		let event = TestEvent::evm_mod(RawEvent::RentCollected(contracts[0], 650));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert!(EVM::rent_collections(11).is_empty());
+		assert_eq!(EVM::rent_collections(21), vec![contracts[0], contracts[1]]);
+		assert_eq!(
+			EVM::contract_rents(contracts[0]),
+			Some(RentInfo {
+				paid_until: 11,
+				overdue_since: None,
+				next_collection: 21
+			})
+		);
+
+		// the maintainer can't pay the rent
+		let alice_account_id = <Test as Config>::AddressMapping::to_account(&alice());
+		Balances::make_free_balance_be(&alice_account_id, 100);
+		System::set_block_number(12);
+		EVM::on_initialize(12);
+		let event = TestEvent::evm_mod(RawEvent::RentOverdue(contracts[2]));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert_eq!(
+			EVM::contract_rents(contracts[2]),
+			Some(RentInfo {
+				paid_until: 1,
+				overdue_since: Some(12),
+				next_collection: 22
+			})
+		);
+
+		// the outdated collections of the evicted contracts are skipped
+		ContractRents::<Test>::remove(contracts[0]);
+		System::set_block_number(21);
+		EVM::on_initialize(21);
+		assert_eq!(EVM::contract_rents(contracts[0]), None);
+		assert_eq!(EVM::rent_collections(31), vec![contracts[1]]);
+	});
+}
+
+#[test]
+fn evict_and_restore_contract_works() {
+	new_test_ext().execute_with(|| {
+		let contract = <Test as Config>::Runner::create(
+			alice(),
+			from_hex(RENT_CONTRACT).unwrap(),
+			0,
+			1_000_000,
+			None,
+			<Test as Config>::config(),
+		)
+		.unwrap()
+		.address;
+		let nonce = EVM::accounts(contract).unwrap().nonce;
+		let code = EVM::code_at_address(&contract);
+		let storage = vec![(H256::zero(), H256::from_low_u64_be(1))];
+		assert_eq!(code, vec![0x00]);
+		assert_eq!(EVM::account_storages(contract, H256::zero()), H256::from_low_u64_be(1));
+
+		let alice_account_id = <Test as Config>::AddressMapping::to_account(&alice());
+		let bob_account_id = <Test as Config>::AddressMapping::to_account(&bob());
+		Balances::make_free_balance_be(&alice_account_id, 100);
+
+		System::set_block_number(21);
+		assert_ok!(EVM::collect_rent(Origin::signed(bob_account_id.clone()), contract, 0));
+		let event = TestEvent::evm_mod(RawEvent::RentOverdue(contract));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert_eq!(
+			EVM::contract_rents(contract),
+			Some(RentInfo {
+				paid_until: 1,
+				overdue_since: Some(21),
+				next_collection: 11
+			})
+		);
+
+		// still in the grace period
+		System::set_block_number(30);
+		assert_ok!(EVM::collect_rent(Origin::signed(bob_account_id.clone()), contract, 0));
+		assert!(EVM::accounts(contract).is_some());
+
+		System::set_block_number(31);
+		// the eviction is bounded by the storage count
+		assert_noop!(
+			EVM::collect_rent(Origin::signed(bob_account_id.clone()), contract, 0),
+			Error::<Test>::StorageCountExceedsLimit
+		);
+		assert_ok!(EVM::collect_rent(Origin::signed(bob_account_id.clone()), contract, 1));
+		let event = TestEvent::evm_mod(RawEvent::ContractEvicted(contract, bob_account_id.clone()));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert!(EVM::accounts(contract).is_none());
+		assert_eq!(EVM::contract_rents(contract), None);
+		assert_eq!(EVM::code_at_address(&contract), Vec::<u8>::new());
+		assert_eq!(EVM::account_storages(contract, H256::zero()), H256::zero());
+		assert_eq!(reserved_balance(contract), 0);
+		// the evictor is rewarded with the existential deposit
+		assert_eq!(
+			balance(bob()),
+			INITIAL_BALANCE + <Test as Config>::ContractExistentialDeposit::get()
+		);
+		let tombstone = EVM::tombstones(contract).unwrap();
+		assert_eq!(tombstone.nonce, nonce);
+		assert_eq!(tombstone.maintainer, alice());
+		assert_eq!(tombstone.code_hash, code_hash(&code));
+		assert_eq!(tombstone.storage_hash, storage_hash(&storage));
+		assert_eq!(tombstone.unpaid_rent, 1950);
+
+		assert_noop!(
+			EVM::restore_contract(
+				Origin::signed(bob_account_id.clone()),
+				contract,
+				vec![0xff],
+				storage.clone()
+			),
+			Error::<Test>::InvalidRestoreCode
+		);
+		assert_noop!(
+			EVM::restore_contract(
+				Origin::signed(bob_account_id.clone()),
+				contract,
+				code.clone(),
+				vec![(H256::zero(), H256::from_low_u64_be(2))]
+			),
+			Error::<Test>::InvalidRestoreStorage
+		);
+		assert_noop!(
+			EVM::restore_contract(
+				Origin::signed(bob_account_id.clone()),
+				alice(),
+				code.clone(),
+				storage.clone()
+			),
+			Error::<Test>::TombstoneNotFound
+		);
+
+		assert_ok!(EVM::restore_contract(
+			Origin::signed(bob_account_id),
+			contract,
+			code.clone(),
+			storage
+		));
+		let event = TestEvent::evm_mod(RawEvent::ContractRestored(contract));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert!(EVM::tombstones(contract).is_none());
+		assert_eq!(EVM::code_at_address(&contract), code);
+		assert_eq!(EVM::account_storages(contract, H256::zero()), H256::from_low_u64_be(1));
+		assert_eq!(EVM::accounts(contract).unwrap().nonce, nonce);
+		assert_eq!(
+			reserved_balance(contract),
+			<Test as Config>::ContractExistentialDeposit::get()
+		);
+		assert_eq!(balance(bob()), INITIAL_BALANCE - 1950);
+		assert_eq!(
+			EVM::contract_rents(contract),
+			Some(RentInfo {
+				paid_until: 31,
+				overdue_since: None,
+				next_collection: 41
+			})
+		);
+	});
+}
+
+#[test]
+fn set_code_works() {
+	new_test_ext().execute_with(|| {
+		let contract = <Test as Config>::Runner::create(
+			alice(),
+			from_hex(RENT_CONTRACT).unwrap(),
+			0,
+			1_000_000,
+			None,
+			<Test as Config>::config(),
+		)
+		.unwrap()
+		.address;
+		let alice_account_id = <Test as Config>::AddressMapping::to_account(&alice());
+		let bob_account_id = <Test as Config>::AddressMapping::to_account(&bob());
+		let stop_hash = code_hash(&[0x00]);
+		let invalid_hash = code_hash(&[0xfe]);
+		// shared with the genesis code of alice
+		assert_eq!(EVM::code_infos(stop_hash).map(|code_info| code_info.ref_count), Some(2));
+
+		assert_noop!(
+			EVM::set_code(Origin::signed(alice_account_id.clone()), H160::default(), vec![0xfe]),
+			Error::<Test>::ContractNotFound
+		);
+		assert_noop!(
+			EVM::set_code(Origin::signed(bob_account_id), contract, vec![0xfe]),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			EVM::set_code(
+				Origin::signed(alice_account_id.clone()),
+				contract,
+				vec![0x00; MAX_CODE_SIZE as usize + 1]
+			),
+			Error::<Test>::ContractExceedsMaxCodeSize
+		);
+
+		assert_ok!(EVM::set_code(
+			Origin::signed(alice_account_id.clone()),
+			contract,
+			vec![0xfe]
+		));
+		let event = TestEvent::evm_mod(RawEvent::ContractSetCode(contract, invalid_hash));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert_eq!(EVM::code_at_address(&contract), vec![0xfe]);
+		assert_eq!(EVM::account_storages(contract, H256::zero()), H256::from_low_u64_be(1));
+		assert_eq!(EVM::code_infos(stop_hash).map(|code_info| code_info.ref_count), Some(1));
+		assert_eq!(
+			EVM::code_infos(invalid_hash).map(|code_info| code_info.ref_count),
+			Some(1)
+		);
+
+		// the unused code is removed
+		assert_ok!(EVM::set_code(Origin::signed(alice_account_id), contract, vec![0x00]));
+		assert_eq!(EVM::code_at_address(&contract), vec![0x00]);
+		assert_eq!(EVM::code_infos(stop_hash).map(|code_info| code_info.ref_count), Some(2));
+		assert!(EVM::code_infos(invalid_hash).is_none());
+		assert!(EVM::codes(invalid_hash).is_empty());
+	});
+}
+
+#[test]
+fn set_code_with_delay_works() {
+	new_test_ext().execute_with(|| {
+		MockCodeUpgradeDelay::set(10);
+		let contract = <Test as Config>::Runner::create(
+			alice(),
+			from_hex(RENT_CONTRACT).unwrap(),
+			0,
+			1_000_000,
+			None,
+			<Test as Config>::config(),
+		)
+		.unwrap()
+		.address;
+		let alice_account_id = <Test as Config>::AddressMapping::to_account(&alice());
+		let bob_account_id = <Test as Config>::AddressMapping::to_account(&bob());
+
+		assert_noop!(
+			EVM::apply_code(Origin::signed(bob_account_id.clone()), contract),
+			Error::<Test>::CodeUpgradeNotFound
+		);
+
+		// the deposit of the code is reserved, and released when replaced
+		let reserved = Balances::reserved_balance(&alice_account_id);
+		assert_ok!(EVM::set_code(
+			Origin::signed(alice_account_id.clone()),
+			contract,
+			vec![0xfe, 0xfe]
+		));
+		assert_eq!(Balances::reserved_balance(&alice_account_id), reserved + 2 * 10);
+		assert_ok!(EVM::set_code(
+			Origin::signed(alice_account_id.clone()),
+			contract,
+			vec![0xfe]
+		));
+		assert_eq!(Balances::reserved_balance(&alice_account_id), reserved + 10);
+		let event = TestEvent::evm_mod(RawEvent::ScheduledSetCode(contract, code_hash(&[0xfe]), 11));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert_eq!(EVM::code_at_address(&contract), vec![0x00]);
+
+		System::set_block_number(10);
+		assert_noop!(
+			EVM::apply_code(Origin::signed(bob_account_id.clone()), contract),
+			Error::<Test>::CodeUpgradeNotDue
+		);
+
+		System::set_block_number(11);
+		assert_ok!(EVM::apply_code(Origin::signed(bob_account_id.clone()), contract));
+		assert_eq!(EVM::code_at_address(&contract), vec![0xfe]);
+		assert!(EVM::pending_code_upgrades(contract).is_none());
+		assert_eq!(Balances::reserved_balance(&alice_account_id), reserved);
+		assert_noop!(
+			EVM::apply_code(Origin::signed(bob_account_id), contract),
+			Error::<Test>::CodeUpgradeNotFound
+		);
+	});
+}
+
+#[test]
+fn deployment_permission_works() {
+	new_test_ext().execute_with(|| {
+		let governance = Origin::signed(NetworkContractAccount::get());
+		let alice_account_id = <Test as Config>::AddressMapping::to_account(&alice());
+		let bob_account_id = <Test as Config>::AddressMapping::to_account(&bob());
+		let init = from_hex(RENT_CONTRACT).unwrap();
+
+		assert_noop!(
+			EVM::set_open_deployment(Origin::signed(alice_account_id.clone()), false),
+			BadOrigin
+		);
+		assert_ok!(EVM::set_open_deployment(governance.clone(), false));
+		let event = TestEvent::evm_mod(RawEvent::OpenDeploymentSet(false));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert!(!EVM::can_deploy(&alice()));
+		assert_noop!(
+			EVM::create(Origin::signed(alice_account_id.clone()), init.clone(), 0, 1_000_000),
+			Error::<Test>::NoDeploymentPermission
+		);
+
+		// deployers
+		assert_noop!(
+			EVM::add_deployer(Origin::signed(alice_account_id.clone()), alice()),
+			BadOrigin
+		);
+		assert_ok!(EVM::add_deployer(governance.clone(), alice()));
+		let event = TestEvent::evm_mod(RawEvent::DeployerAdded(alice()));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert_ok!(EVM::create(
+			Origin::signed(alice_account_id.clone()),
+			init.clone(),
+			0,
+			1_000_000
+		));
+		assert_ok!(EVM::remove_deployer(governance.clone(), alice()));
+		assert!(!EVM::can_deploy(&alice()));
+
+		// developers
+		assert_noop!(
+			EVM::disable_developer_mode(Origin::signed(bob_account_id.clone())),
+			Error::<Test>::DeveloperModeNotEnabled
+		);
+		assert_ok!(EVM::enable_developer_mode(Origin::signed(bob_account_id.clone())));
+		let event = TestEvent::evm_mod(RawEvent::DeveloperModeEnabled(bob_account_id.clone()));
+		assert!(System::events().iter().any(|record| record.event == event));
+		assert_eq!(reserved_balance(bob()), DeveloperDeposit::get());
+		assert_noop!(
+			EVM::enable_developer_mode(Origin::signed(bob_account_id.clone())),
+			Error::<Test>::DeveloperModeAlreadyEnabled
+		);
+		assert_ok!(EVM::create(
+			Origin::signed(bob_account_id.clone()),
+			init.clone(),
+			0,
+			1_000_000
+		));
+
+		assert_ok!(EVM::disable_developer_mode(Origin::signed(bob_account_id.clone())));
+		assert_eq!(reserved_balance(bob()), 0);
+		assert_noop!(
+			EVM::create(Origin::signed(bob_account_id), init.clone(), 0, 1_000_000),
+			Error::<Test>::NoDeploymentPermission
+		);
+
+		assert_ok!(EVM::set_open_deployment(governance, true));
+		assert_ok!(EVM::create(Origin::signed(alice_account_id), init, 0, 1_000_000));
+	});
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn tracing_records_calls_and_steps() {
+	use primitives::evm::{CallType, TraceResult, TracerKind};
+	use runner::tracing;
+
+	// the factory of `contract_should_deploy_contracts`
+	let contract = from_hex("0x608060405234801561001057600080fd5b5061016f806100206000396000f3fe608060405260043610610041576000357c0100000000000000000000000000000000000000000000000000000000900463ffffffff168063412a5a6d14610046575b600080fd5b61004e610050565b005b600061005a6100e2565b604051809103906000f080158015610076573d6000803e3d6000fd5b50905060008190806001815401808255809150509060018203906000526020600020016000909192909190916101000a81548173ffffffffffffffffffffffffffffffffffffffff021916908373ffffffffffffffffffffffffffffffffffffffff1602179055505050565b6040516052806100f28339019056fe6080604052348015600f57600080fd5b50603580601d6000396000f3fe6080604052600080fdfea165627a7a7230582092dc1966a8880ddf11e067f9dd56a632c11a78a4afd4a9f05924d427367958cc0029a165627a7a723058202b2cc7384e11c452cdbf39b68dada2d5e10a632cc0174a354b8b8c83237e28a40029").unwrap();
+	new_test_ext().execute_with(|| {
+		let factory =
+			<Test as Config>::Runner::create(alice(), contract, 0, 1000000000, None, <Test as Config>::config())
+				.unwrap()
+				.address;
+
+		// Factory.createContract
+		let create_contract = from_hex("0x412a5a6d").unwrap();
+		let (result, trace) = tracing::using(TracerKind::CallTracer, || {
+			<Test as Config>::Runner::call(
+				alice(),
+				factory,
+				create_contract.clone(),
+				0,
+				1000000000,
+				None,
+				<Test as Config>::config(),
+			)
+		});
+		assert_eq!(result.unwrap().exit_reason, ExitReason::Succeed(ExitSucceed::Stopped));
+
+		let calls = match trace {
+			TraceResult::Calls(calls) => calls,
+			_ => panic!("call tracer returns calls"),
+		};
+		assert_eq!(calls.len(), 1);
+		assert_eq!(calls[0].call_type, CallType::Call);
+		assert_eq!(calls[0].from, alice());
+		assert_eq!(calls[0].to, factory);
+		assert_eq!(calls[0].input, create_contract);
+		assert_eq!(calls[0].exit_reason, Some(ExitReason::Succeed(ExitSucceed::Stopped)));
+		assert_eq!(calls[0].calls.len(), 1);
+		assert_eq!(calls[0].calls[0].call_type, CallType::Create);
+		assert_eq!(calls[0].calls[0].from, factory);
+		assert!(calls[0].calls[0].gas_used > 0);
+
+		let (_, trace) = tracing::using(TracerKind::OpcodeTracer, || {
+			<Test as Config>::Runner::call(
+				alice(),
+				factory,
+				create_contract,
+				0,
+				1000000000,
+				None,
+				<Test as Config>::config(),
+			)
+		});
+		let steps = match trace {
+			TraceResult::Steps(steps) => steps,
+			_ => panic!("opcode tracer returns steps"),
+		};
+		assert_eq!(steps[0].depth, 1);
+		assert_eq!(steps[0].op, b"PUSH1".to_vec());
+		assert!(steps.iter().any(|step| step.depth == 2));
+		assert!(steps.iter().any(|step| step.op == b"CREATE".to_vec()));
+	});
+}
@@ -0,0 +1,131 @@
+//! Signed Ethereum legacy and EIP-155 transactions.
+
+use codec::{Decode, Encode};
+use frame_support::RuntimeDebug;
+use rlp::{DecoderError, Rlp, RlpStream};
+use sha3::{Digest, Keccak256};
+use sp_core::{H160, H256, U256};
+use sp_std::vec::Vec;
+
+/// The target of a transaction.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum TransactionAction {
+	/// Call the contract or transfer to the account.
+	Call(H160),
+	/// Create a contract.
+	Create,
+}
+
+/// A signed Ethereum transaction, RLP encoded as
+/// `[nonce, gas_price, gas_limit, to, value, input, v, r, s]`.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct EthereumTransaction {
+	pub nonce: U256,
+	pub gas_price: U256,
+	pub gas_limit: U256,
+	pub action: TransactionAction,
+	pub value: U256,
+	pub input: Vec<u8>,
+	/// `27 + recovery_id` for legacy transactions,
+	/// `chain_id * 2 + 35 + recovery_id` for EIP-155 transactions.
+	pub v: u64,
+	pub r: H256,
+	pub s: H256,
+}
+
+impl EthereumTransaction {
+	/// Decode the RLP encoded transaction.
+	pub fn decode_rlp(raw: &[u8]) -> Result<Self, DecoderError> {
+		let rlp = Rlp::new(raw);
+		if rlp.item_count()? != 9 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+
+		let to = rlp.at(3)?;
+		let action = if to.is_empty() {
+			TransactionAction::Create
+		} else {
+			TransactionAction::Call(to.as_val()?)
+		};
+		let to_h256 = |value: U256| {
+			let mut bytes = [0u8; 32];
+			value.to_big_endian(&mut bytes);
+			H256::from(bytes)
+		};
+
+		Ok(Self {
+			nonce: rlp.val_at(0)?,
+			gas_price: rlp.val_at(1)?,
+			gas_limit: rlp.val_at(2)?,
+			action,
+			value: rlp.val_at(4)?,
+			input: rlp.val_at(5)?,
+			v: rlp.val_at(6)?,
+			r: to_h256(rlp.val_at(7)?),
+			s: to_h256(rlp.val_at(8)?),
+		})
+	}
+
+	/// The RLP encoded transaction.
+	pub fn encode_rlp(&self) -> Vec<u8> {
+		let mut stream = RlpStream::new_list(9);
+		self.append_unsigned(&mut stream);
+		stream.append(&self.v);
+		stream.append(&U256::from_big_endian(&self.r[..]));
+		stream.append(&U256::from_big_endian(&self.s[..]));
+		stream.out()
+	}
+
+	/// The chain id of EIP-155 transaction, `None` for legacy transaction.
+	pub fn chain_id(&self) -> Option<u64> {
+		if self.v >= 35 {
+			Some((self.v - 35) / 2)
+		} else {
+			None
+		}
+	}
+
+	/// The hash signed by the sender.
+	pub fn signing_hash(&self) -> H256 {
+		let chain_id = self.chain_id();
+		let mut stream = RlpStream::new_list(if chain_id.is_some() { 9 } else { 6 });
+		self.append_unsigned(&mut stream);
+		if let Some(chain_id) = chain_id {
+			stream.append(&chain_id);
+			stream.append(&0u8);
+			stream.append(&0u8);
+		}
+		H256::from_slice(Keccak256::digest(&stream.out()).as_slice())
+	}
+
+	/// Recover the address of the sender, `None` if the signature is invalid.
+	pub fn recover_signer(&self) -> Option<H160> {
+		let recovery_id = match self.chain_id() {
+			Some(chain_id) => self.v.checked_sub(chain_id.checked_mul(2)?.checked_add(35)?)?,
+			None => self.v.checked_sub(27)?,
+		};
+		if recovery_id > 1 {
+			return None;
+		}
+
+		let mut signature = [0u8; 65];
+		signature[0..32].copy_from_slice(&self.r[..]);
+		signature[32..64].copy_from_slice(&self.s[..]);
+		signature[64] = recovery_id as u8;
+
+		let public = sp_io::crypto::secp256k1_ecdsa_recover(&signature, &self.signing_hash().0).ok()?;
+		Some(H160::from_slice(&Keccak256::digest(&public[..])[12..]))
+	}
+
+	fn append_unsigned(&self, stream: &mut RlpStream) {
+		stream.append(&self.nonce);
+		stream.append(&self.gas_price);
+		stream.append(&self.gas_limit);
+		match self.action {
+			TransactionAction::Call(to) => stream.append(&to),
+			TransactionAction::Create => stream.append_empty_data(),
+		};
+		stream.append(&self.value);
+		stream.append(&self.input);
+	}
+}
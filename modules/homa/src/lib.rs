@@ -1,21 +1,20 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::{Decode, Encode};
 use frame_support::{decl_module, transactional};
 use frame_system::{self as system, ensure_signed};
 use primitives::{Balance, EraIndex};
-use sp_runtime::RuntimeDebug;
-use support::HomaProtocol;
-
-#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
-pub enum RedeemStrategy {
-	Immediately,
-	Target(EraIndex),
-	WaitForUnbonding,
-}
+use support::{HomaProtocol, OnLiquidRedeem};
+
+pub use primitives::homa::RedeemStrategy;
+
+mod mock;
+mod tests;
 
 pub trait Config: system::Config {
 	type Homa: HomaProtocol<Self::AccountId, Balance, EraIndex>;
+
+	/// Hooks to release the locked liquid currency for redeem.
+	type OnLiquidRedeem: OnLiquidRedeem<Self::AccountId, Balance>;
 }
 
 decl_module! {
@@ -31,17 +30,19 @@ decl_module! {
 		#[transactional]
 		pub fn redeem(origin, #[compact] amount: Balance, strategy: RedeemStrategy) {
 			let who = ensure_signed(origin)?;
-			match strategy {
+			T::OnLiquidRedeem::before_liquid_redeem(&who, amount);
+			let redeemed = match strategy {
 				RedeemStrategy::Immediately => {
-					T::Homa::redeem_by_free_unbonded(&who, amount)?;
+					T::Homa::redeem_by_free_unbonded(&who, amount)?
 				},
 				RedeemStrategy::Target(target_era) => {
-					T::Homa::redeem_by_claim_unbonding(&who, amount, target_era)?;
+					T::Homa::redeem_by_claim_unbonding(&who, amount, target_era)?
 				},
 				RedeemStrategy::WaitForUnbonding => {
-					T::Homa::redeem_by_unbond(&who, amount)?;
+					T::Homa::redeem_by_unbond(&who, amount)?
 				},
-			}
+			};
+			T::OnLiquidRedeem::on_liquid_redeem(&who, redeemed);
 		}
 
 		#[weight = 10_000]
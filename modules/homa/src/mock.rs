@@ -0,0 +1,136 @@
+//! Mocks for the homa module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{impl_outer_origin, parameter_types};
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchError, Perbill};
+use std::cell::RefCell;
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 0;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Runtime;
+
+impl_outer_origin! {
+	pub enum Origin for Runtime {}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Config for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+}
+
+/// The mock Homa protocol: redeeming immediately burns half of the amount,
+/// redeeming by claim unbonding burns nothing, and target era 0 is invalid.
+pub struct MockHoma;
+impl HomaProtocol<AccountId, Balance, EraIndex> for MockHoma {
+	type Balance = Balance;
+
+	fn mint(_who: &AccountId, amount: Balance) -> sp_std::result::Result<Balance, DispatchError> {
+		Ok(amount)
+	}
+
+	fn redeem_by_unbond(_who: &AccountId, amount: Balance) -> sp_std::result::Result<Balance, DispatchError> {
+		Ok(amount)
+	}
+
+	fn redeem_by_free_unbonded(_who: &AccountId, amount: Balance) -> sp_std::result::Result<Balance, DispatchError> {
+		Ok(amount / 2)
+	}
+
+	fn redeem_by_claim_unbonding(
+		_who: &AccountId,
+		_amount: Balance,
+		target_era: EraIndex,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		if target_era == 0 {
+			return Err(DispatchError::Other("invalid era"));
+		}
+		Ok(0)
+	}
+
+	fn withdraw_redemption(_who: &AccountId) -> sp_std::result::Result<Balance, DispatchError> {
+		Ok(0)
+	}
+}
+
+thread_local! {
+	static LIQUID_REDEEM_CALLS: RefCell<Vec<(bool, AccountId, Balance)>> = RefCell::new(vec![]);
+}
+
+/// Records the calls of the hooks, `true` for `before_liquid_redeem`.
+pub struct MockOnLiquidRedeem;
+impl MockOnLiquidRedeem {
+	pub fn calls() -> Vec<(bool, AccountId, Balance)> {
+		LIQUID_REDEEM_CALLS.with(|v| v.borrow().clone())
+	}
+}
+impl OnLiquidRedeem<AccountId, Balance> for MockOnLiquidRedeem {
+	fn before_liquid_redeem(who: &AccountId, amount: Balance) {
+		LIQUID_REDEEM_CALLS.with(|v| v.borrow_mut().push((true, *who, amount)));
+	}
+
+	fn on_liquid_redeem(who: &AccountId, redeemed: Balance) {
+		LIQUID_REDEEM_CALLS.with(|v| v.borrow_mut().push((false, *who, redeemed)));
+	}
+}
+
+impl Config for Runtime {
+	type Homa = MockHoma;
+	type OnLiquidRedeem = MockOnLiquidRedeem;
+}
+pub type HomaModule = Module<Runtime>;
+
+pub struct ExtBuilder;
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		ExtBuilder
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		LIQUID_REDEEM_CALLS.with(|v| v.borrow_mut().clear());
+
+		frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap()
+			.into()
+	}
+}
@@ -0,0 +1,49 @@
+//! Unit tests for the homa module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{ExtBuilder, HomaModule, MockOnLiquidRedeem, Origin, ALICE};
+use sp_runtime::DispatchError;
+
+#[test]
+fn redeem_calls_hooks_with_redeemed_amount() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(HomaModule::redeem(
+			Origin::signed(ALICE),
+			1000,
+			RedeemStrategy::Immediately
+		));
+		assert_eq!(
+			MockOnLiquidRedeem::calls(),
+			vec![(true, ALICE, 1000), (false, ALICE, 500)]
+		);
+	});
+}
+
+#[test]
+fn redeem_nothing_calls_hooks_with_zero() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(HomaModule::redeem(
+			Origin::signed(ALICE),
+			1000,
+			RedeemStrategy::Target(1)
+		));
+		assert_eq!(
+			MockOnLiquidRedeem::calls(),
+			vec![(true, ALICE, 1000), (false, ALICE, 0)]
+		);
+	});
+}
+
+#[test]
+fn redeem_failed_does_not_call_on_liquid_redeem() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			HomaModule::redeem(Origin::signed(ALICE), 1000, RedeemStrategy::Target(0)),
+			DispatchError::Other("invalid era"),
+		);
+		assert_eq!(MockOnLiquidRedeem::calls(), vec![(true, ALICE, 1000)]);
+	});
+}
@@ -0,0 +1,18 @@
+//! # Homa Simulation
+//!
+//! ## Overview
+//!
+//! Test support for Homa protocol. It builds a mandala-like runtime with
+//! staking pool, the polkadot bridge simulator and homa, and runs multi-era
+//! scenarios declared as actions at eras, e.g. mints, redeems, slashes and
+//! reward rates of sub accounts. The state of the protocol is recorded after
+//! every action and era, and checked against invariants such as the exchange
+//! rate never decreasing absent slashes.
+
+pub mod runtime;
+pub mod scenario;
+
+mod tests;
+
+pub use runtime::*;
+pub use scenario::*;
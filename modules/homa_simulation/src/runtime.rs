@@ -0,0 +1,232 @@
+//! The mandala-like runtime for simulations.
+
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types};
+use frame_system::EnsureRoot;
+use orml_traits::parameter_type_with_key;
+use primitives::{Amount, Balance, CurrencyId, EraIndex, TokenSymbol};
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, FixedPointNumber, ModuleId, Perbill};
+use support::{ExchangeRate, NomineesProvider, Rate, Ratio};
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+pub type PolkadotAccountId = u128;
+
+pub const ALICE: AccountId = 0;
+pub const BOB: AccountId = 1;
+pub const CHARLIE: AccountId = 2;
+pub const ACA: CurrencyId = CurrencyId::Token(TokenSymbol::ACA);
+pub const DOT: CurrencyId = CurrencyId::Token(TokenSymbol::DOT);
+pub const LDOT: CurrencyId = CurrencyId::Token(TokenSymbol::LDOT);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Runtime;
+
+mod staking_pool {
+	pub use ::staking_pool::*;
+}
+
+impl_outer_origin! {
+	pub enum Origin for Runtime {}
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Runtime {
+		frame_system<T>,
+		staking_pool<T>,
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Config for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+}
+pub type System = frame_system::Module<Runtime>;
+
+parameter_type_with_key! {
+	pub ExistentialDeposits: |currency_id: CurrencyId| -> Balance {
+		Default::default()
+	};
+}
+
+impl orml_tokens::Config for Runtime {
+	type Event = ();
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type WeightInfo = ();
+	type ExistentialDeposits = ExistentialDeposits;
+	type OnDust = ();
+}
+pub type Tokens = orml_tokens::Module<Runtime>;
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+}
+
+impl pallet_balances::Config for Runtime {
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type MaxLocks = ();
+	type WeightInfo = ();
+}
+type PalletBalances = pallet_balances::Module<Runtime>;
+
+parameter_types! {
+	pub const GetNativeCurrencyId: CurrencyId = ACA;
+	pub const GetStakingCurrencyId: CurrencyId = DOT;
+	pub const GetLiquidCurrencyId: CurrencyId = LDOT;
+}
+
+pub type NativeCurrency = orml_currencies::BasicCurrencyAdapter<Runtime, PalletBalances, Amount, BlockNumber>;
+
+impl orml_currencies::Config for Runtime {
+	type Event = ();
+	type MultiCurrency = Tokens;
+	type NativeCurrency = NativeCurrency;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type WeightInfo = ();
+}
+pub type Currencies = orml_currencies::Module<Runtime>;
+
+parameter_types! {
+	pub const PolkadotBondingDuration: EraIndex = 7;
+	pub const EraLength: BlockNumber = 10;
+}
+
+impl polkadot_bridge::Config for Runtime {
+	type DOTCurrency = orml_currencies::Currency<Runtime, GetStakingCurrencyId>;
+	type OnNewEra = StakingPool;
+	type BondingDuration = PolkadotBondingDuration;
+	type EraLength = EraLength;
+	type PolkadotAccountId = PolkadotAccountId;
+}
+pub type PolkadotBridge = polkadot_bridge::Module<Runtime>;
+
+pub struct SimulationNominees;
+impl NomineesProvider<PolkadotAccountId> for SimulationNominees {
+	fn nominees() -> Vec<PolkadotAccountId> {
+		vec![1, 2, 3]
+	}
+}
+
+parameter_types! {
+	pub DefaultExchangeRate: ExchangeRate = ExchangeRate::saturating_from_rational(10, 100);	// 1 : 10
+	pub const StakingPoolModuleId: ModuleId = ModuleId(*b"aca/stkp");
+	pub PoolAccountIndexes: Vec<u32> = vec![1, 2, 3, 4];
+	pub const EraRewardsHistoryDepth: EraIndex = 28;
+}
+
+impl staking_pool::Config for Runtime {
+	type Event = TestEvent;
+	type StakingCurrencyId = GetStakingCurrencyId;
+	type LiquidCurrencyId = GetLiquidCurrencyId;
+	type DefaultExchangeRate = DefaultExchangeRate;
+	type ModuleId = StakingPoolModuleId;
+	type PoolAccountIndexes = PoolAccountIndexes;
+	type UpdateOrigin = EnsureRoot<AccountId>;
+	type FeeModel = runtime_common::CurveFeeModel;
+	type Nominees = SimulationNominees;
+	type Bridge = PolkadotBridge;
+	type Currency = Currencies;
+	type EraRewardsHistoryDepth = EraRewardsHistoryDepth;
+}
+pub type StakingPool = staking_pool::Module<Runtime>;
+
+impl homa::Config for Runtime {
+	type Homa = StakingPool;
+	type OnLiquidRedeem = ();
+}
+pub type Homa = homa::Module<Runtime>;
+
+/// The staking pool params of mandala genesis.
+pub fn mandala_staking_pool_params() -> staking_pool::Params {
+	staking_pool::Params {
+		target_max_free_unbonded_ratio: Ratio::saturating_from_rational(10, 100),
+		target_min_free_unbonded_ratio: Ratio::saturating_from_rational(5, 100),
+		target_unbonding_to_free_ratio: Ratio::saturating_from_rational(2, 100),
+		unbonding_to_free_adjustment: Rate::saturating_from_rational(1, 1000),
+		base_fee_rate: Rate::saturating_from_rational(2, 100),
+	}
+}
+
+pub struct ExtBuilder {
+	endowed_accounts: Vec<(AccountId, CurrencyId, Balance)>,
+	staking_pool_params: staking_pool::Params,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			endowed_accounts: vec![],
+			staking_pool_params: mandala_staking_pool_params(),
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn balances(mut self, endowed_accounts: Vec<(AccountId, CurrencyId, Balance)>) -> Self {
+		self.endowed_accounts = endowed_accounts;
+		self
+	}
+
+	pub fn staking_pool_params(mut self, params: staking_pool::Params) -> Self {
+		self.staking_pool_params = params;
+		self
+	}
+
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap();
+
+		orml_tokens::GenesisConfig::<Runtime> {
+			endowed_accounts: self.endowed_accounts,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		staking_pool::GenesisConfig {
+			staking_pool_params: self.staking_pool_params,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		let mut ext: sp_io::TestExternalities = t.into();
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
@@ -0,0 +1,303 @@
+//! Declarative multi-era scenarios and their invariants.
+
+use crate::runtime::{
+	AccountId, ExtBuilder, Homa, Origin, PolkadotBridge, PoolAccountIndexes, StakingPool, System, Tokens, DOT, LDOT,
+};
+use frame_support::traits::{Get, OnFinalize};
+use homa::RedeemStrategy;
+use orml_traits::MultiCurrency;
+use primitives::{Balance, CurrencyId, EraIndex};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	DispatchResult, FixedPointNumber,
+};
+use std::collections::BTreeMap;
+use support::{ExchangeRate, PolkadotBridgeState, Rate};
+
+/// An operation applied to the simulation at the start of an era.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+	/// Mint liquid currency by `amount` staking currency.
+	Mint(AccountId, Balance),
+	/// Redeem `amount` liquid currency by `strategy`.
+	Redeem(AccountId, Balance, RedeemStrategy),
+	/// Withdraw the redeemed staking currency which is unbonded.
+	WithdrawRedemption(AccountId),
+	/// Set the reward rate per era of the sub account in relay chain.
+	SetRewardRate(u32, Rate),
+	/// Slash `amount` bonded of the sub account in relay chain.
+	Slash(u32, Balance),
+}
+
+impl Action {
+	fn apply(&self) -> DispatchResult {
+		match self.clone() {
+			Action::Mint(who, amount) => Homa::mint(Origin::signed(who), amount),
+			Action::Redeem(who, amount, strategy) => Homa::redeem(Origin::signed(who), amount, strategy),
+			Action::WithdrawRedemption(who) => Homa::withdraw_redemption(Origin::signed(who)),
+			Action::SetRewardRate(account_index, rate) => {
+				PolkadotBridge::set_mock_reward_rate(Origin::root(), account_index, rate)
+			}
+			Action::Slash(account_index, amount) => {
+				PolkadotBridge::simulate_slash_sub_account(Origin::root(), account_index, amount)
+			}
+		}
+	}
+}
+
+/// The state of Homa protocol observed during the simulation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+	pub era: EraIndex,
+	pub block_number: u64,
+	pub exchange_rate: ExchangeRate,
+	pub liquid_issuance: Balance,
+	pub total_communal_balance: Balance,
+	pub total_bonded: Balance,
+	pub free_unbonded: Balance,
+	pub unbonding_to_free: Balance,
+	pub total_claimed_unbonded: Balance,
+	/// The balance of all pool sub accounts in relay chain.
+	pub bridge_balance: Balance,
+	/// Whether any sub account was slashed in this era, or in the last era for
+	/// the snapshot at the start of an era when staking pool syncs the slash.
+	pub slashed: bool,
+}
+
+impl Snapshot {
+	fn take(slashed: bool) -> Self {
+		Snapshot {
+			era: StakingPool::current_era(),
+			block_number: System::block_number(),
+			exchange_rate: StakingPool::liquid_exchange_rate(),
+			liquid_issuance: Tokens::total_issuance(LDOT),
+			total_communal_balance: StakingPool::get_total_communal_balance(),
+			total_bonded: StakingPool::total_bonded(),
+			free_unbonded: StakingPool::free_unbonded(),
+			unbonding_to_free: StakingPool::unbonding_to_free(),
+			total_claimed_unbonded: StakingPool::total_claimed_unbonded(),
+			bridge_balance: PoolAccountIndexes::get().into_iter().fold(
+				Zero::zero(),
+				|total: Balance, account_index| {
+					total.saturating_add(<PolkadotBridge as PolkadotBridgeState<_, _>>::balance(account_index))
+				},
+			),
+			slashed,
+		}
+	}
+}
+
+/// An invariant checked between every two consecutive snapshots, returns
+/// the reason if it is violated.
+pub type Invariant = Box<dyn Fn(&Snapshot, &Snapshot) -> Result<(), String>>;
+
+/// The exchange rate of liquid currency never decreases unless sub accounts
+/// are slashed. The last digit of the fixed point is rounding noise.
+pub fn exchange_rate_never_decreases_without_slash() -> Invariant {
+	Box::new(|previous, current| {
+		let tolerance = ExchangeRate::from_inner(1);
+		if current.slashed || current.exchange_rate.saturating_add(tolerance) >= previous.exchange_rate {
+			Ok(())
+		} else {
+			Err(format!(
+				"exchange rate decreased from {:?} to {:?} without slash",
+				previous.exchange_rate, current.exchange_rate
+			))
+		}
+	})
+}
+
+/// The staking currency belonging to the staking pool in relay chain is never
+/// less than what the pool accounts for.
+pub fn bridge_covers_total_bonded() -> Invariant {
+	Box::new(|_, current| {
+		if current.slashed || current.bridge_balance >= current.total_bonded {
+			Ok(())
+		} else {
+			Err(format!(
+				"bridge balance {:?} is less than total bonded {:?}",
+				current.bridge_balance, current.total_bonded
+			))
+		}
+	})
+}
+
+/// The result of an applied action.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActionRecord {
+	pub era: EraIndex,
+	pub action: Action,
+	pub result: DispatchResult,
+}
+
+/// The result of a finished simulation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+	/// Taken at the start, after every action and at every new era.
+	pub snapshots: Vec<Snapshot>,
+	pub actions: Vec<ActionRecord>,
+	/// Free balances of the accounts at the end, (account, currency, free).
+	pub balances: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Report {
+	/// The snapshots taken at the start of every era.
+	pub fn era_snapshots(&self) -> Vec<&Snapshot> {
+		let mut eras = BTreeMap::new();
+		for snapshot in self.snapshots.iter() {
+			eras.entry(snapshot.era).or_insert(snapshot);
+		}
+		eras.into_iter().map(|(_, snapshot)| snapshot).collect()
+	}
+
+	pub fn last(&self) -> &Snapshot {
+		self.snapshots
+			.last()
+			.expect("the initial snapshot is always taken; qed")
+	}
+
+	pub fn free_balance(&self, who: AccountId, currency_id: CurrencyId) -> Balance {
+		self.balances
+			.iter()
+			.find(|(account, currency, _)| *account == who && *currency == currency_id)
+			.map(|(_, _, free)| *free)
+			.unwrap_or_default()
+	}
+
+	/// Panics if any action failed.
+	pub fn assert_all_actions_ok(&self) {
+		for record in self.actions.iter() {
+			assert!(
+				record.result.is_ok(),
+				"{:?} failed at era {:?}: {:?}",
+				record.action,
+				record.era,
+				record.result
+			);
+		}
+	}
+}
+
+/// A multi-era scenario of Homa protocol, e.g.
+///
+/// ```ignore
+/// let report = Scenario::new()
+/// 	.endow(ALICE, 1000)
+/// 	.at_era(0, Action::Mint(ALICE, 1000))
+/// 	.at_era(1, Action::SetRewardRate(1, Rate::saturating_from_rational(1, 100)))
+/// 	.run_until_era(10);
+/// ```
+///
+/// Actions of an era are applied in order at the first block of the era,
+/// then the blocks are finalized one by one until the next era. Invariants
+/// are checked after every snapshot and the simulation panics on violation.
+pub struct Scenario {
+	endowed_accounts: Vec<(AccountId, CurrencyId, Balance)>,
+	actions: BTreeMap<EraIndex, Vec<Action>>,
+	invariants: Vec<(&'static str, Invariant)>,
+}
+
+impl Default for Scenario {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Scenario {
+	/// A scenario with the default invariants.
+	pub fn new() -> Self {
+		Scenario {
+			endowed_accounts: vec![],
+			actions: BTreeMap::new(),
+			invariants: vec![
+				(
+					"exchange_rate_never_decreases_without_slash",
+					exchange_rate_never_decreases_without_slash(),
+				),
+				("bridge_covers_total_bonded", bridge_covers_total_bonded()),
+			],
+		}
+	}
+
+	/// Endow `who` with `amount` staking currency.
+	pub fn endow(mut self, who: AccountId, amount: Balance) -> Self {
+		self.endowed_accounts.push((who, DOT, amount));
+		self
+	}
+
+	pub fn at_era(mut self, era: EraIndex, action: Action) -> Self {
+		self.actions.entry(era).or_insert_with(Vec::new).push(action);
+		self
+	}
+
+	pub fn invariant(mut self, name: &'static str, invariant: Invariant) -> Self {
+		self.invariants.push((name, invariant));
+		self
+	}
+
+	/// Run the scenario from era 0 until `era` starts.
+	pub fn run_until_era(self, era: EraIndex) -> Report {
+		let accounts = self.endowed_accounts.iter().map(|(who, _, _)| *who).collect::<Vec<_>>();
+		let mut ext = ExtBuilder::default().balances(self.endowed_accounts.clone()).build();
+
+		ext.execute_with(|| {
+			let mut report = Report::default();
+			self.record(&mut report, Snapshot::take(false));
+
+			loop {
+				let current_era = StakingPool::current_era();
+				// the slash is synced to staking pool at the next era
+				let mut slashed = false;
+				for action in self.actions.get(&current_era).cloned().unwrap_or_default() {
+					let result = action.apply();
+					slashed = slashed || (matches!(action, Action::Slash(_, _)) && result.is_ok());
+					report.actions.push(ActionRecord {
+						era: current_era,
+						action,
+						result,
+					});
+					self.record(&mut report, Snapshot::take(slashed));
+				}
+
+				if current_era >= era {
+					break;
+				}
+
+				Self::run_to_next_era();
+				self.record(&mut report, Snapshot::take(slashed));
+			}
+
+			for who in accounts {
+				for currency_id in [DOT, LDOT].iter() {
+					report
+						.balances
+						.push((who, *currency_id, Tokens::free_balance(*currency_id, &who)));
+				}
+			}
+			report
+		})
+	}
+
+	fn run_to_next_era() {
+		let era = StakingPool::current_era();
+		while StakingPool::current_era() == era {
+			let now = System::block_number();
+			PolkadotBridge::on_finalize(now);
+			System::set_block_number(now + 1);
+		}
+	}
+
+	fn record(&self, report: &mut Report, snapshot: Snapshot) {
+		if let Some(previous) = report.snapshots.last() {
+			for (name, invariant) in self.invariants.iter() {
+				if let Err(reason) = invariant(previous, &snapshot) {
+					panic!(
+						"invariant {} violated at era {:?} block {:?}: {}",
+						name, snapshot.era, snapshot.block_number, reason
+					);
+				}
+			}
+		}
+		report.snapshots.push(snapshot);
+	}
+}
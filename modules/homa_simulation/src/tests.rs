@@ -0,0 +1,106 @@
+//! Unit tests for homa simulation.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::traits::Get;
+use homa::RedeemStrategy;
+use primitives::Balance;
+use sp_runtime::FixedPointNumber;
+use support::Rate;
+
+const UNIT: Balance = 1_000_000_000_000;
+
+fn reward_rate_of_all_sub_accounts(scenario: Scenario, era: u32, percent: u128) -> Scenario {
+	PoolAccountIndexes::get()
+		.into_iter()
+		.fold(scenario, |scenario, account_index| {
+			scenario.at_era(
+				era,
+				Action::SetRewardRate(account_index, Rate::saturating_from_rational(percent, 100)),
+			)
+		})
+}
+
+#[test]
+fn rewards_increase_exchange_rate() {
+	let scenario = Scenario::new()
+		.endow(ALICE, 1000 * UNIT)
+		.endow(BOB, 1000 * UNIT)
+		.at_era(0, Action::Mint(ALICE, 1000 * UNIT))
+		.at_era(2, Action::Mint(BOB, 500 * UNIT));
+	let report = reward_rate_of_all_sub_accounts(scenario, 1, 1).run_until_era(6);
+	report.assert_all_actions_ok();
+
+	let era_snapshots = report.era_snapshots();
+	assert_eq!(era_snapshots.len(), 7);
+	assert_eq!(era_snapshots[0].exchange_rate, DefaultExchangeRate::get());
+	assert!(era_snapshots[1].total_bonded > 0);
+	assert!(era_snapshots[6].exchange_rate > era_snapshots[2].exchange_rate);
+	assert_eq!(report.free_balance(ALICE, LDOT), 10000 * UNIT);
+	assert!(report.free_balance(BOB, LDOT) < 5000 * UNIT);
+}
+
+#[test]
+fn redeem_strategies_work() {
+	let scenario = Scenario::new()
+		.endow(ALICE, 1000 * UNIT)
+		.at_era(0, Action::Mint(ALICE, 1000 * UNIT))
+		.at_era(2, Action::Redeem(ALICE, 1000 * UNIT, RedeemStrategy::WaitForUnbonding))
+		.at_era(2, Action::Redeem(ALICE, 100 * UNIT, RedeemStrategy::Immediately))
+		.at_era(10, Action::WithdrawRedemption(ALICE));
+	let report = reward_rate_of_all_sub_accounts(scenario, 0, 1).run_until_era(10);
+	report.assert_all_actions_ok();
+
+	assert_eq!(report.free_balance(ALICE, LDOT), 8900 * UNIT);
+	assert!(report.free_balance(ALICE, DOT) > 100 * UNIT);
+	assert_eq!(report.last().total_claimed_unbonded, 0);
+}
+
+#[test]
+fn failed_actions_are_recorded() {
+	let report = Scenario::new()
+		.endow(ALICE, 1000 * UNIT)
+		.at_era(0, Action::Mint(ALICE, 2000 * UNIT))
+		.at_era(1, Action::Mint(ALICE, 1000 * UNIT))
+		.run_until_era(1);
+
+	assert_eq!(report.actions.len(), 2);
+	assert!(report.actions[0].result.is_err());
+	assert_eq!(report.actions[1].era, 1);
+	assert!(report.actions[1].result.is_ok());
+}
+
+#[test]
+fn slash_can_decrease_exchange_rate() {
+	let scenario = Scenario::new()
+		.endow(ALICE, 1000 * UNIT)
+		.at_era(0, Action::Mint(ALICE, 1000 * UNIT))
+		.at_era(3, Action::Slash(1, 100 * UNIT));
+	let report = reward_rate_of_all_sub_accounts(scenario, 0, 1).run_until_era(5);
+	report.assert_all_actions_ok();
+
+	let era_snapshots = report.era_snapshots();
+	assert!(era_snapshots[4].slashed);
+	assert!(era_snapshots[4].exchange_rate < era_snapshots[3].exchange_rate);
+	assert!(!era_snapshots[5].slashed);
+}
+
+#[test]
+#[should_panic(expected = "invariant liquid_issuance_never_increases violated at era 0")]
+fn invariant_violation_panics() {
+	Scenario::new()
+		.endow(ALICE, 1000 * UNIT)
+		.at_era(0, Action::Mint(ALICE, 1000 * UNIT))
+		.invariant(
+			"liquid_issuance_never_increases",
+			Box::new(|previous, current| {
+				if current.liquid_issuance > previous.liquid_issuance {
+					Err("liquid issuance increased".to_string())
+				} else {
+					Ok(())
+				}
+			}),
+		)
+		.run_until_era(1);
+}
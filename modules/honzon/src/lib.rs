@@ -14,9 +14,9 @@ use frame_support::{
 	decl_error, decl_event, decl_module, decl_storage, ensure, traits::Get, transactional, weights::Weight,
 };
 use frame_system::{self as system, ensure_signed};
-use primitives::{Amount, CurrencyId};
+use primitives::{Amount, Balance, CurrencyId};
 use sp_runtime::{traits::Zero, DispatchResult};
-use support::EmergencyShutdown;
+use support::{EmergencyShutdown, HonzonManager, PriceProvider, Ratio};
 
 mod default_weight;
 mod mock;
@@ -98,12 +98,7 @@ decl_module! {
 			debit_adjustment: Amount,
 		) {
 			let who = ensure_signed(origin)?;
-
-			// not allowed to adjust the debit after system shutdown
-			if !debit_adjustment.is_zero() {
-				ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
-			}
-			<cdp_engine::Module<T>>::adjust_position(&who, currency_id, collateral_adjustment, debit_adjustment)?;
+			Self::do_adjust_loan(&who, currency_id, collateral_adjustment, debit_adjustment)?;
 		}
 
 		/// Transfer the whole CDP of `from` under `currency_id` to caller's CDP under the same `currency_id`,
@@ -127,9 +122,7 @@ decl_module! {
 			from: T::AccountId,
 		) {
 			let to = ensure_signed(origin)?;
-			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
-			Self::check_authorization(&from, &to, currency_id)?;
-			<loans::Module<T>>::transfer_loan(&from, &to, currency_id)?;
+			Self::do_transfer_loan_from(currency_id, &from, &to)?;
 		}
 
 		/// Authorize `to` to manipulate the loan under `currency_id`
@@ -152,8 +145,7 @@ decl_module! {
 			to: T::AccountId,
 		) {
 			let from = ensure_signed(origin)?;
-			<Authorization<T>>::insert(&from, (currency_id, &to), true);
-			Self::deposit_event(RawEvent::Authorization(from, to, currency_id));
+			Self::do_authorize(&from, currency_id, &to);
 		}
 
 		/// Cancel the authorization for `to` under `currency_id`
@@ -176,8 +168,7 @@ decl_module! {
 			to: T::AccountId,
 		) {
 			let from = ensure_signed(origin)?;
-			<Authorization<T>>::remove(&from, (currency_id, &to));
-			Self::deposit_event(RawEvent::UnAuthorization(from, to, currency_id));
+			Self::do_unauthorize(&from, currency_id, &to);
 		}
 
 		/// Cancel all authorization of caller
@@ -200,6 +191,35 @@ decl_module! {
 }
 
 impl<T: Config> Module<T> {
+	fn do_adjust_loan(
+		who: &T::AccountId,
+		currency_id: CurrencyId,
+		collateral_adjustment: Amount,
+		debit_adjustment: Amount,
+	) -> DispatchResult {
+		// not allowed to adjust the debit after system shutdown
+		if !debit_adjustment.is_zero() {
+			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+		}
+		<cdp_engine::Module<T>>::adjust_position(who, currency_id, collateral_adjustment, debit_adjustment)
+	}
+
+	fn do_transfer_loan_from(currency_id: CurrencyId, from: &T::AccountId, to: &T::AccountId) -> DispatchResult {
+		ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+		Self::check_authorization(from, to, currency_id)?;
+		<loans::Module<T>>::transfer_loan(from, to, currency_id)
+	}
+
+	fn do_authorize(from: &T::AccountId, currency_id: CurrencyId, to: &T::AccountId) {
+		<Authorization<T>>::insert(from, (currency_id, to), true);
+		Self::deposit_event(RawEvent::Authorization(from.clone(), to.clone(), currency_id));
+	}
+
+	fn do_unauthorize(from: &T::AccountId, currency_id: CurrencyId, to: &T::AccountId) {
+		<Authorization<T>>::remove(from, (currency_id, to));
+		Self::deposit_event(RawEvent::UnAuthorization(from.clone(), to.clone(), currency_id));
+	}
+
 	/// Check if `from` has the authorization of `to` under `currency_id`
 	fn check_authorization(from: &T::AccountId, to: &T::AccountId, currency_id: CurrencyId) -> DispatchResult {
 		ensure!(
@@ -209,3 +229,49 @@ impl<T: Config> Module<T> {
 		Ok(())
 	}
 }
+
+impl<T: Config> HonzonManager<T::AccountId, CurrencyId, Amount, Balance> for Module<T> {
+	#[transactional]
+	fn adjust_loan(
+		who: &T::AccountId,
+		currency_id: CurrencyId,
+		collateral_adjustment: Amount,
+		debit_adjustment: Amount,
+	) -> DispatchResult {
+		Self::do_adjust_loan(who, currency_id, collateral_adjustment, debit_adjustment)
+	}
+
+	#[transactional]
+	fn transfer_loan_from(currency_id: CurrencyId, from: &T::AccountId, to: &T::AccountId) -> DispatchResult {
+		Self::do_transfer_loan_from(currency_id, from, to)
+	}
+
+	fn authorize(from: &T::AccountId, currency_id: CurrencyId, to: &T::AccountId) -> DispatchResult {
+		Self::do_authorize(from, currency_id, to);
+		Ok(())
+	}
+
+	fn unauthorize(from: &T::AccountId, currency_id: CurrencyId, to: &T::AccountId) -> DispatchResult {
+		Self::do_unauthorize(from, currency_id, to);
+		Ok(())
+	}
+
+	fn get_position(who: &T::AccountId, currency_id: CurrencyId) -> (Balance, Balance) {
+		let loans::Position { collateral, debit } = <loans::Module<T>>::positions(currency_id, who);
+		(collateral, debit)
+	}
+
+	fn get_collateral_ratio(who: &T::AccountId, currency_id: CurrencyId) -> Option<Ratio> {
+		let (collateral, debit) = Self::get_position(who, currency_id);
+		let price = <T as cdp_engine::Config>::PriceSource::get_relative_price(
+			currency_id,
+			<T as cdp_engine::Config>::GetStableCurrencyId::get(),
+		)?;
+		Some(<cdp_engine::Module<T>>::calculate_collateral_ratio(
+			currency_id,
+			collateral,
+			debit,
+			price,
+		))
+	}
+}
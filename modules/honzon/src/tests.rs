@@ -130,3 +130,45 @@ fn on_emergency_shutdown_should_work() {
 		);
 	});
 }
+
+#[test]
+fn honzon_manager_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_eq!(HonzonModule::get_position(&ALICE, BTC), (0, 0));
+
+		assert_ok!(<HonzonModule as HonzonManager<_, _, _, _>>::adjust_loan(
+			&ALICE, BTC, 100, 50
+		));
+		assert_eq!(HonzonModule::get_position(&ALICE, BTC), (100, 50));
+		assert_eq!(
+			HonzonModule::get_collateral_ratio(&ALICE, BTC),
+			Some(Ratio::saturating_from_rational(2, 1))
+		);
+
+		assert_noop!(
+			<HonzonModule as HonzonManager<_, _, _, _>>::transfer_loan_from(BTC, &ALICE, &BOB),
+			Error::<Runtime>::NoAuthorization,
+		);
+		assert_ok!(<HonzonModule as HonzonManager<_, _, _, _>>::authorize(
+			&ALICE, BTC, &BOB
+		));
+		assert_ok!(<HonzonModule as HonzonManager<_, _, _, _>>::transfer_loan_from(
+			BTC, &ALICE, &BOB
+		));
+		assert_eq!(HonzonModule::get_position(&BOB, BTC), (100, 50));
+
+		assert_ok!(<HonzonModule as HonzonManager<_, _, _, _>>::unauthorize(
+			&ALICE, BTC, &BOB
+		));
+		assert!(!HonzonModule::authorization(&ALICE, (BTC, &BOB)));
+	});
+}
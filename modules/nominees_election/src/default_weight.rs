@@ -0,0 +1,20 @@
+//! Weights for nominees_election
+//!
+//! Estimated from the storage accesses, to be replaced by running the
+//! benchmarks of `nominees_election`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(clippy::unnecessary_cast)]
+
+use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
+
+impl crate::WeightInfo for () {
+	fn rebalance_by_phragmen(v: u32) -> Weight {
+		(34_127_000 as Weight)
+			.saturating_add((27_518_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+}
@@ -4,21 +4,29 @@ use codec::{Decode, Encode};
 use frame_support::{
 	decl_error, decl_module, decl_storage, ensure,
 	traits::{Get, LockIdentifier},
-	transactional, IterableStorageMap, Parameter,
+	transactional,
+	weights::{DispatchClass, Weight},
+	IterableStorageMap, Parameter,
 };
 use frame_system::{self as system, ensure_signed};
 use orml_traits::{BasicCurrency, BasicLockableCurrency};
 use primitives::{Balance, EraIndex};
 use sp_runtime::{
-	traits::{MaybeDisplay, MaybeSerializeDeserialize, Member, Zero},
-	RuntimeDebug,
+	helpers_128bit::multiply_by_rational,
+	traits::{MaybeDisplay, MaybeSerializeDeserialize, Member, Saturating, Zero},
+	FixedPointNumber, RuntimeDebug,
 };
-use sp_std::{fmt::Debug, prelude::*};
-use support::{NomineesProvider, OnNewEra};
+use sp_std::{collections::btree_map::BTreeMap, fmt::Debug, prelude::*};
+use support::{ExchangeRateProvider, NomineesProvider, OnLiquidRedeem, OnNewEra};
 
+mod default_weight;
 mod mock;
 mod tests;
 
+pub trait WeightInfo {
+	fn rebalance_by_phragmen(v: u32) -> Weight;
+}
+
 const NOMINEES_ELECTION_ID: LockIdentifier = *b"nomelect";
 
 /// Just a Balance/BlockNumber tuple to encode when a chunk of funds will be
@@ -96,13 +104,54 @@ impl BondingLedger {
 	}
 }
 
+/// The way to select `Nominees` from the nominations of voters.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug)]
+pub enum ElectionStrategy {
+	/// Pick the candidates with the most `Votes`, recalculated whenever
+	/// nominees are queried.
+	TopVotes,
+	/// Run sequential Phragmén over the voters' `Nominations` weighted by
+	/// their active bonded, recalculated at era boundaries.
+	SequentialPhragmen,
+}
+
+impl Default for ElectionStrategy {
+	fn default() -> Self {
+		ElectionStrategy::TopVotes
+	}
+}
+
 pub trait Config: system::Config {
+	/// The currency to bond for voting, it's the liquid currency (LDOT) of
+	/// Homa protocol, and the bonded is locked.
 	type Currency: BasicLockableCurrency<Self::AccountId, Moment = Self::BlockNumber, Balance = Balance>;
 	type PolkadotAccountId: Parameter + Member + MaybeSerializeDeserialize + Debug + MaybeDisplay + Ord + Default;
+
+	/// The minimum vote weight (in staking currency) of a bonded ledger.
 	type MinBondThreshold: Get<Balance>;
 	type BondingDuration: Get<EraIndex>;
 	type NominateesCount: Get<usize>;
 	type MaxUnlockingChunks: Get<usize>;
+
+	/// The strategy to elect nominees.
+	type Strategy: Get<ElectionStrategy>;
+
+	/// The maximum number of nominators, which are the voters of a sequential
+	/// Phragmén election, to bound its weight.
+	type MaxElectionVoters: Get<u32>;
+
+	/// The exchange rate from the bonded currency to staking currency, used to
+	/// convert the bonded into vote weight.
+	type LiquidStakingExchangeRateProvider: ExchangeRateProvider;
+
+	/// Whether to vote with the liquid currency (LDOT) of Homa protocol: the
+	/// vote weight is converted by `LiquidStakingExchangeRateProvider`, and
+	/// the bonded is released when it's redeemed through Homa. Otherwise the
+	/// bonded votes 1:1 and can't be redeemed before unbonded.
+	type LiquidVoting: Get<bool>;
+
+	/// Weight information for the election in this module.
+	type WeightInfo: WeightInfo;
 }
 
 decl_error! {
@@ -113,6 +162,7 @@ decl_error! {
 		TooManyChunks,
 		NoBonded,
 		NoUnlockChunk,
+		TooManyNominators,
 	}
 }
 
@@ -123,6 +173,7 @@ decl_storage! {
 		pub Votes get(fn votes): map hasher(twox_64_concat) T::PolkadotAccountId => Balance;
 		pub Nominees get(fn nominees): Vec<T::PolkadotAccountId>;
 		pub CurrentEra get(fn current_era): EraIndex;
+		pub NominatorCount get(fn nominator_count): u32;
 	}
 }
 
@@ -133,6 +184,17 @@ decl_module! {
 		const MinBondThreshold: Balance = T::MinBondThreshold::get();
 		const NominateesCount: u32 = T::NominateesCount::get() as u32;
 		const MaxUnlockingChunks: u32 = T::MaxUnlockingChunks::get() as u32;
+		const MaxElectionVoters: u32 = T::MaxElectionVoters::get();
+
+		fn on_runtime_upgrade() -> Weight {
+			// count the nominators before `NominatorCount` was introduced
+			if NominatorCount::exists() {
+				return T::DbWeight::get().reads(1);
+			}
+			let count = <Nominations<T>>::iter().count() as u32;
+			NominatorCount::put(count);
+			T::DbWeight::get().reads_writes((count + 1).into(), 1)
+		}
 
 		#[weight = 10_000]
 		#[transactional]
@@ -145,7 +207,10 @@ decl_module! {
 				let extra = extra.min(amount);
 				let old_active = ledger.active;
 				ledger.active += extra;
-				ensure!(ledger.active >= T::MinBondThreshold::get(), Error::<T>::BelowMinBondThreshold);
+				ensure!(
+					Self::vote_weight(ledger.active) >= T::MinBondThreshold::get(),
+					Error::<T>::BelowMinBondThreshold,
+				);
 				ledger.total += extra;
 				let old_nominations = Self::nominations(&who);
 
@@ -172,7 +237,7 @@ decl_module! {
 				ledger.active -= amount;
 
 				ensure!(
-					ledger.active.is_zero() || ledger.active >= T::MinBondThreshold::get(),
+					ledger.active.is_zero() || Self::vote_weight(ledger.active) >= T::MinBondThreshold::get(),
 					Error::<T>::BelowMinBondThreshold,
 				);
 
@@ -237,6 +302,12 @@ decl_module! {
 			targets.sort();
 			targets.dedup();
 
+			if !<Nominations<T>>::contains_key(&who) {
+				let count = Self::nominator_count();
+				ensure!(count < T::MaxElectionVoters::get(), Error::<T>::TooManyNominators);
+				NominatorCount::put(count + 1);
+			}
+
 			let old_nominations = Self::nominations(&who);
 			let old_active = Self::ledger(&who).active;
 
@@ -253,12 +324,21 @@ decl_module! {
 			let old_active = Self::ledger(&who).active;
 
 			Self::update_votes(old_active, &old_nominations, Zero::zero(), &[]);
-			<Nominations<T>>::remove(&who);
+			Self::remove_nominations(&who);
 		}
 	}
 }
 
 impl<T: Config> Module<T> {
+	/// Convert the bonded liquid currency into vote weight in staking currency.
+	pub fn vote_weight(bonded: Balance) -> Balance {
+		if T::LiquidVoting::get() {
+			T::LiquidStakingExchangeRateProvider::get_exchange_rate().saturating_mul_int(bonded)
+		} else {
+			bonded
+		}
+	}
+
 	fn update_ledger(who: &T::AccountId, ledger: &BondingLedger) {
 		T::Currency::set_lock(NOMINEES_ELECTION_ID, who, ledger.total);
 		<Ledger<T>>::insert(who, ledger);
@@ -267,7 +347,14 @@ impl<T: Config> Module<T> {
 	fn remove_ledger(who: &T::AccountId) {
 		T::Currency::remove_lock(NOMINEES_ELECTION_ID, who);
 		<Ledger<T>>::remove(who);
-		<Nominations<T>>::remove(who);
+		Self::remove_nominations(who);
+	}
+
+	fn remove_nominations(who: &T::AccountId) {
+		if <Nominations<T>>::contains_key(who) {
+			<Nominations<T>>::remove(who);
+			NominatorCount::mutate(|count| *count = count.saturating_sub(1));
+		}
 	}
 
 	fn update_votes(
@@ -302,11 +389,93 @@ impl<T: Config> Module<T> {
 
 		<Nominees<T>>::put(new_nominees);
 	}
+
+	/// Elect `NominateesCount` nominees by sequential Phragmén, the voters are
+	/// the nominators, at most `MaxElectionVoters`, and the approval stake of
+	/// a voter is the vote weight of its active bonded. Returns the weight
+	/// consumed.
+	pub fn rebalance_by_phragmen() -> Weight {
+		// `nominate` keeps the nominators within `MaxElectionVoters`, the bound
+		// only takes effect for the nominators counted by the runtime upgrade.
+		let mut voters_count: u32 = 0;
+		let mut voters = <Nominations<T>>::iter()
+			.take(T::MaxElectionVoters::get() as usize)
+			.inspect(|_| voters_count += 1)
+			.map(|(who, targets)| (Self::vote_weight(Self::ledger(&who).active), targets))
+			.filter(|(active, targets)| !active.is_zero() && !targets.is_empty())
+			.collect::<Vec<(Balance, Vec<T::PolkadotAccountId>)>>();
+
+		voters.sort_by(|a, b| b.0.cmp(&a.0));
+
+		let new_nominees = Self::seq_phragmen(T::NominateesCount::get(), voters);
+		<Nominees<T>>::put(new_nominees);
+
+		T::WeightInfo::rebalance_by_phragmen(voters_count)
+	}
+
+	/// Sequential Phragmén method, returns the elected candidates in the
+	/// order of being elected.
+	///
+	/// All scores and loads are rationals with the implicit denominator
+	/// `u128::max_value()`, which keeps precision without fixed point types.
+	fn seq_phragmen(to_elect: usize, voters: Vec<(Balance, Vec<T::PolkadotAccountId>)>) -> Vec<T::PolkadotAccountId> {
+		const DEN: u128 = u128::max_value();
+
+		// candidate => (approval_stake, elected)
+		let mut candidates: BTreeMap<T::PolkadotAccountId, (Balance, bool)> = BTreeMap::new();
+		for (budget, targets) in voters.iter() {
+			for target in targets {
+				let approval_stake = &mut candidates.entry(target.clone()).or_insert((Zero::zero(), false)).0;
+				*approval_stake = approval_stake.saturating_add(*budget);
+			}
+		}
+
+		let mut loads: Vec<u128> = vec![Zero::zero(); voters.len()];
+		let mut winners: Vec<T::PolkadotAccountId> = vec![];
+
+		while winners.len() < to_elect {
+			let mut scores: BTreeMap<T::PolkadotAccountId, u128> = candidates
+				.iter()
+				.filter(|(_, (approval_stake, elected))| !elected && !approval_stake.is_zero())
+				.map(|(candidate, (approval_stake, _))| (candidate.clone(), DEN / approval_stake))
+				.collect();
+
+			for ((budget, targets), load) in voters.iter().zip(loads.iter()) {
+				for target in targets {
+					if let (Some(score), Some((approval_stake, _))) = (scores.get_mut(target), candidates.get(target)) {
+						let temp = multiply_by_rational(*load, *budget, *approval_stake).unwrap_or_else(|_| DEN);
+						*score = score.saturating_add(temp);
+					}
+				}
+			}
+
+			// the candidate with minimum score wins, ties are broken by the order of
+			// candidate.
+			let winner = match scores.into_iter().min_by(|a, b| a.1.cmp(&b.1)) {
+				Some(winner) => winner,
+				None => break,
+			};
+
+			for ((_, targets), load) in voters.iter().zip(loads.iter_mut()) {
+				if targets.contains(&winner.0) {
+					*load = winner.1;
+				}
+			}
+			if let Some((_, elected)) = candidates.get_mut(&winner.0) {
+				*elected = true;
+			}
+			winners.push(winner.0);
+		}
+
+		winners
+	}
 }
 
 impl<T: Config> NomineesProvider<T::PolkadotAccountId> for Module<T> {
 	fn nominees() -> Vec<T::PolkadotAccountId> {
-		Self::rebalance();
+		if T::Strategy::get() == ElectionStrategy::TopVotes {
+			Self::rebalance();
+		}
 		<Nominees<T>>::get()
 	}
 }
@@ -314,6 +483,67 @@ impl<T: Config> NomineesProvider<T::PolkadotAccountId> for Module<T> {
 impl<T: Config> OnNewEra<EraIndex> for Module<T> {
 	fn on_new_era(era: EraIndex) {
 		CurrentEra::put(era);
-		Self::rebalance();
+		match T::Strategy::get() {
+			ElectionStrategy::TopVotes => Self::rebalance(),
+			ElectionStrategy::SequentialPhragmen => {
+				let weight = Self::rebalance_by_phragmen();
+				<system::Module<T>>::register_extra_weight_unchecked(weight, DispatchClass::Mandatory);
+			}
+		}
+	}
+}
+
+impl<T: Config> OnLiquidRedeem<T::AccountId, Balance> for Module<T> {
+	/// Lift the lock of `who` for the redeem, it's restored by
+	/// `on_liquid_redeem`.
+	fn before_liquid_redeem(who: &T::AccountId, _amount: Balance) {
+		if T::LiquidVoting::get() {
+			T::Currency::remove_lock(NOMINEES_ELECTION_ID, who);
+		}
+	}
+
+	/// Release the bonded which is burned by the redeem, the unlocking chunks
+	/// are released first and then the active bonded, and the votes are
+	/// reduced accordingly. The lock of the remaining bonded is restored.
+	fn on_liquid_redeem(who: &T::AccountId, _redeemed: Balance) {
+		if !T::LiquidVoting::get() {
+			return;
+		}
+
+		let mut ledger = Self::ledger(who);
+		if ledger.total.is_zero() {
+			return;
+		}
+		let mut to_release = ledger.total.saturating_sub(T::Currency::free_balance(who));
+
+		let old_active = ledger.active;
+		ledger.total -= to_release;
+		while let Some(last) = ledger.unlocking.last_mut() {
+			if to_release.is_zero() {
+				break;
+			}
+			let released = last.value.min(to_release);
+			last.value -= released;
+			to_release -= released;
+			if last.value.is_zero() {
+				ledger.unlocking.pop();
+			}
+		}
+		ledger.active = ledger.active.saturating_sub(to_release);
+
+		// release all the remaining active if it's below the threshold.
+		if !ledger.active.is_zero() && Self::vote_weight(ledger.active) < T::MinBondThreshold::get() {
+			ledger.total = ledger.total.saturating_sub(ledger.active);
+			ledger.active = Zero::zero();
+		}
+
+		let old_nominations = Self::nominations(who);
+		Self::update_votes(old_active, &old_nominations, ledger.active, &old_nominations);
+
+		if ledger.unlocking.is_empty() && ledger.active.is_zero() {
+			Self::remove_ledger(who);
+		} else {
+			Self::update_ledger(who, &ledger);
+		}
 	}
 }
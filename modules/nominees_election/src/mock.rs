@@ -7,13 +7,20 @@ use frame_support::{impl_outer_origin, parameter_types};
 use orml_traits::parameter_type_with_key;
 use primitives::{Amount, CurrencyId, TokenSymbol};
 use sp_core::H256;
-use sp_runtime::{testing::Header, traits::IdentityLookup, Perbill};
+use sp_runtime::{
+	testing::Header,
+	traits::{IdentityLookup, One},
+	Perbill,
+};
+use sp_std::cell::RefCell;
+use support::ExchangeRate;
 
 pub type AccountId = u128;
 pub type BlockNumber = u64;
 
 pub const ALICE: AccountId = 0;
 pub const BOB: AccountId = 1;
+pub const CHARLIE: AccountId = 2;
 pub const ACA: CurrencyId = CurrencyId::Token(TokenSymbol::ACA);
 pub const LDOT: CurrencyId = CurrencyId::Token(TokenSymbol::LDOT);
 
@@ -113,6 +120,49 @@ parameter_types! {
 	pub const BondingDuration: EraIndex = 4;
 	pub const NominateesCount: usize = 5;
 	pub const MaxUnlockingChunks: usize = 3;
+	pub const MaxElectionVoters: u32 = 2;
+}
+
+thread_local! {
+	static STRATEGY: RefCell<ElectionStrategy> = RefCell::new(ElectionStrategy::TopVotes);
+	static EXCHANGE_RATE: RefCell<ExchangeRate> = RefCell::new(ExchangeRate::one());
+	static LIQUID_VOTING: RefCell<bool> = RefCell::new(true);
+}
+
+pub struct MockStrategy;
+impl MockStrategy {
+	pub fn set(strategy: ElectionStrategy) {
+		STRATEGY.with(|v| *v.borrow_mut() = strategy);
+	}
+}
+impl Get<ElectionStrategy> for MockStrategy {
+	fn get() -> ElectionStrategy {
+		STRATEGY.with(|v| *v.borrow())
+	}
+}
+
+pub struct MockLiquidVoting;
+impl MockLiquidVoting {
+	pub fn set(enabled: bool) {
+		LIQUID_VOTING.with(|v| *v.borrow_mut() = enabled);
+	}
+}
+impl Get<bool> for MockLiquidVoting {
+	fn get() -> bool {
+		LIQUID_VOTING.with(|v| *v.borrow())
+	}
+}
+
+pub struct MockLiquidStakingExchangeProvider;
+impl MockLiquidStakingExchangeProvider {
+	pub fn set(rate: ExchangeRate) {
+		EXCHANGE_RATE.with(|v| *v.borrow_mut() = rate);
+	}
+}
+impl ExchangeRateProvider for MockLiquidStakingExchangeProvider {
+	fn get_exchange_rate() -> ExchangeRate {
+		EXCHANGE_RATE.with(|v| *v.borrow())
+	}
 }
 
 impl Config for Runtime {
@@ -122,6 +172,11 @@ impl Config for Runtime {
 	type BondingDuration = BondingDuration;
 	type NominateesCount = NominateesCount;
 	type MaxUnlockingChunks = MaxUnlockingChunks;
+	type Strategy = MockStrategy;
+	type MaxElectionVoters = MaxElectionVoters;
+	type LiquidStakingExchangeRateProvider = MockLiquidStakingExchangeProvider;
+	type LiquidVoting = MockLiquidVoting;
+	type WeightInfo = ();
 }
 pub type NomineesElectionModule = Module<Runtime>;
 
@@ -132,13 +187,17 @@ pub struct ExtBuilder {
 impl Default for ExtBuilder {
 	fn default() -> Self {
 		Self {
-			endowed_accounts: vec![(ALICE, LDOT, 1000), (BOB, LDOT, 1000)],
+			endowed_accounts: vec![(ALICE, LDOT, 1000), (BOB, LDOT, 1000), (CHARLIE, LDOT, 1000)],
 		}
 	}
 }
 
 impl ExtBuilder {
 	pub fn build(self) -> sp_io::TestExternalities {
+		MockStrategy::set(ElectionStrategy::TopVotes);
+		MockLiquidStakingExchangeProvider::set(ExchangeRate::one());
+		MockLiquidVoting::set(true);
+
 		let mut t = frame_system::GenesisConfig::default()
 			.build_storage::<Runtime>()
 			.unwrap();
@@ -3,8 +3,12 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok};
-use mock::{ExtBuilder, LDOTCurrency, NomineesElectionModule, Origin, Runtime, ALICE, BOB};
+use frame_support::{assert_noop, assert_ok, traits::OnRuntimeUpgrade};
+use mock::{
+	ExtBuilder, LDOTCurrency, MockLiquidStakingExchangeProvider, MockLiquidVoting, MockStrategy,
+	NomineesElectionModule, Origin, Runtime, ALICE, BOB, CHARLIE,
+};
+use support::ExchangeRate;
 
 #[test]
 fn bond_below_min_bond_threshold() {
@@ -17,6 +21,23 @@ fn bond_below_min_bond_threshold() {
 	});
 }
 
+#[test]
+fn bond_below_min_bond_threshold_by_vote_weight() {
+	ExtBuilder::default().build().execute_with(|| {
+		MockLiquidStakingExchangeProvider::set(ExchangeRate::saturating_from_rational(1, 10));
+		assert_noop!(
+			NomineesElectionModule::bond(Origin::signed(ALICE), 49),
+			Error::<Runtime>::BelowMinBondThreshold,
+		);
+		assert_ok!(NomineesElectionModule::bond(Origin::signed(ALICE), 50));
+		assert_eq!(NomineesElectionModule::ledger(&ALICE).active, 50);
+		assert_eq!(NomineesElectionModule::vote_weight(50), 5);
+
+		MockLiquidVoting::set(false);
+		assert_eq!(NomineesElectionModule::vote_weight(50), 50);
+	});
+}
+
 #[test]
 fn bond_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -216,6 +237,80 @@ fn rebalance_work() {
 	});
 }
 
+#[test]
+fn rebalance_by_phragmen_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(NomineesElectionModule::bond(Origin::signed(ALICE), 1000));
+		assert_ok!(NomineesElectionModule::nominate(
+			Origin::signed(ALICE),
+			vec![1, 2, 3, 4, 5]
+		));
+		assert_ok!(NomineesElectionModule::bond(Origin::signed(BOB), 600));
+		assert_ok!(NomineesElectionModule::nominate(Origin::signed(BOB), vec![6]));
+		assert_ok!(NomineesElectionModule::bond(Origin::signed(CHARLIE), 10));
+
+		// the nominators are bounded by MaxElectionVoters
+		assert_eq!(NomineesElectionModule::nominator_count(), 2);
+		assert_noop!(
+			NomineesElectionModule::nominate(Origin::signed(CHARLIE), vec![7]),
+			Error::<Runtime>::TooManyNominators,
+		);
+		// nominating again doesn't take another seat
+		assert_ok!(NomineesElectionModule::nominate(Origin::signed(BOB), vec![6]));
+		assert_eq!(NomineesElectionModule::nominator_count(), 2);
+
+		NomineesElectionModule::rebalance();
+		assert_eq!(NomineesElectionModule::nominees(), vec![1, 2, 3, 4, 5]);
+
+		// BOB's nominee is elected after ALICE's first nominee
+		NomineesElectionModule::rebalance_by_phragmen();
+		assert_eq!(NomineesElectionModule::nominees(), vec![1, 6, 2, 3, 4]);
+
+		// the seat of a nominator is released by chill
+		assert_ok!(NomineesElectionModule::chill(Origin::signed(BOB)));
+		assert_ok!(NomineesElectionModule::chill(Origin::signed(BOB)));
+		assert_eq!(NomineesElectionModule::nominator_count(), 1);
+		assert_ok!(NomineesElectionModule::nominate(Origin::signed(CHARLIE), vec![7]));
+		assert_eq!(NomineesElectionModule::nominator_count(), 2);
+	});
+}
+
+#[test]
+fn count_nominators_on_runtime_upgrade() {
+	ExtBuilder::default().build().execute_with(|| {
+		<Nominations<Runtime>>::insert(ALICE, vec![1]);
+		<Nominations<Runtime>>::insert(BOB, vec![2]);
+		assert!(!NominatorCount::exists());
+
+		NomineesElectionModule::on_runtime_upgrade();
+		assert_eq!(NomineesElectionModule::nominator_count(), 2);
+
+		<Nominations<Runtime>>::insert(CHARLIE, vec![3]);
+		NomineesElectionModule::on_runtime_upgrade();
+		assert_eq!(NomineesElectionModule::nominator_count(), 2);
+	});
+}
+
+#[test]
+fn elect_at_new_era_by_strategy() {
+	ExtBuilder::default().build().execute_with(|| {
+		MockStrategy::set(ElectionStrategy::SequentialPhragmen);
+		assert_ok!(NomineesElectionModule::bond(Origin::signed(ALICE), 500));
+		assert_ok!(NomineesElectionModule::nominate(Origin::signed(ALICE), vec![1, 2]));
+
+		// nominees is not recalculated until new era
+		assert_eq!(<Module<Runtime> as NomineesProvider<_>>::nominees(), vec![]);
+		NomineesElectionModule::on_new_era(1);
+		assert_eq!(NomineesElectionModule::current_era(), 1);
+		assert_eq!(<Module<Runtime> as NomineesProvider<_>>::nominees(), vec![1, 2]);
+
+		assert_ok!(NomineesElectionModule::chill(Origin::signed(ALICE)));
+		assert_eq!(<Module<Runtime> as NomineesProvider<_>>::nominees(), vec![1, 2]);
+		NomineesElectionModule::on_new_era(2);
+		assert_eq!(<Module<Runtime> as NomineesProvider<_>>::nominees(), vec![]);
+	});
+}
+
 #[test]
 fn update_votes_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -236,3 +331,67 @@ fn update_votes_work() {
 		assert_eq!(NomineesElectionModule::votes(4), 10);
 	});
 }
+
+#[test]
+fn on_liquid_redeem_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(NomineesElectionModule::bond(Origin::signed(ALICE), 800));
+		assert_ok!(NomineesElectionModule::nominate(Origin::signed(ALICE), vec![1, 2]));
+		assert_ok!(NomineesElectionModule::unbond(Origin::signed(ALICE), 100));
+		assert_eq!(NomineesElectionModule::ledger(&ALICE).total, 800);
+		assert_eq!(NomineesElectionModule::ledger(&ALICE).active, 700);
+		assert_eq!(NomineesElectionModule::votes(1), 700);
+
+		// the bonded is locked
+		assert!(LDOTCurrency::withdraw(&ALICE, 201).is_err());
+
+		// the unlocked is enough to redeem
+		NomineesElectionModule::before_liquid_redeem(&ALICE, 200);
+		assert_ok!(LDOTCurrency::withdraw(&ALICE, 200));
+		NomineesElectionModule::on_liquid_redeem(&ALICE, 200);
+		assert_eq!(NomineesElectionModule::ledger(&ALICE).total, 800);
+		assert!(LDOTCurrency::withdraw(&ALICE, 1).is_err());
+
+		// nothing is burned, the lock is restored
+		NomineesElectionModule::before_liquid_redeem(&ALICE, 500);
+		NomineesElectionModule::on_liquid_redeem(&ALICE, 0);
+		assert_eq!(NomineesElectionModule::ledger(&ALICE).total, 800);
+		assert_eq!(NomineesElectionModule::votes(1), 700);
+		assert!(LDOTCurrency::withdraw(&ALICE, 1).is_err());
+
+		// release the unlocking chunk first, then active
+		NomineesElectionModule::before_liquid_redeem(&ALICE, 300);
+		assert_ok!(LDOTCurrency::withdraw(&ALICE, 300));
+		NomineesElectionModule::on_liquid_redeem(&ALICE, 300);
+		assert_eq!(NomineesElectionModule::ledger(&ALICE).total, 500);
+		assert_eq!(NomineesElectionModule::ledger(&ALICE).active, 500);
+		assert_eq!(NomineesElectionModule::ledger(&ALICE).unlocking, vec![]);
+		assert_eq!(NomineesElectionModule::votes(1), 500);
+		assert_eq!(NomineesElectionModule::votes(2), 500);
+		assert!(LDOTCurrency::withdraw(&ALICE, 1).is_err());
+
+		// remove the ledger when the remaining active is below threshold
+		NomineesElectionModule::before_liquid_redeem(&ALICE, 498);
+		assert_ok!(LDOTCurrency::withdraw(&ALICE, 498));
+		NomineesElectionModule::on_liquid_redeem(&ALICE, 498);
+		assert_eq!(NomineesElectionModule::ledger(&ALICE), Default::default());
+		assert_eq!(NomineesElectionModule::nominations(&ALICE), vec![]);
+		assert_eq!(NomineesElectionModule::votes(1), 0);
+		assert_ok!(LDOTCurrency::withdraw(&ALICE, 2));
+	});
+}
+
+#[test]
+fn on_liquid_redeem_does_nothing_without_liquid_voting() {
+	ExtBuilder::default().build().execute_with(|| {
+		MockLiquidVoting::set(false);
+		assert_ok!(NomineesElectionModule::bond(Origin::signed(ALICE), 800));
+		assert_ok!(NomineesElectionModule::nominate(Origin::signed(ALICE), vec![1]));
+
+		NomineesElectionModule::before_liquid_redeem(&ALICE, 500);
+		assert!(LDOTCurrency::withdraw(&ALICE, 500).is_err());
+		NomineesElectionModule::on_liquid_redeem(&ALICE, 0);
+		assert_eq!(NomineesElectionModule::ledger(&ALICE).total, 800);
+		assert_eq!(NomineesElectionModule::votes(1), 800);
+	});
+}
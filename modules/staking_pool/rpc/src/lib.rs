@@ -3,26 +3,41 @@
 use codec::Codec;
 use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
 use jsonrpc_derive::rpc;
-use module_staking_pool_rpc_runtime_api::BalanceInfo;
+use module_staking_pool_rpc_runtime_api::{BalanceInfo, RedeemQuote, RedeemStrategy, StakingPoolInfo};
 use module_support::ExchangeRate;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
 use sp_runtime::{
 	generic::BlockId,
 	traits::{Block as BlockT, MaybeDisplay, MaybeFromStr},
 };
-use std::sync::Arc;
+use std::{
+	convert::{TryFrom, TryInto},
+	sync::Arc,
+};
 
 pub use self::gen_client::Client as StakingPoolClient;
 pub use module_staking_pool_rpc_runtime_api::StakingPoolApi as StakingPoolRuntimeApi;
 
 #[rpc]
-pub trait StakingPoolApi<BlockHash, AccountId, ResponseType> {
+pub trait StakingPoolApi<BlockHash, AccountId, ResponseType, Balance> {
 	#[rpc(name = "stakingPool_getAvailableUnbonded")]
 	fn get_available_unbonded(&self, account: AccountId, at: Option<BlockHash>) -> Result<ResponseType>;
 
 	#[rpc(name = "stakingPool_getLiquidStakingExchangeRate")]
 	fn get_liquid_staking_exchange_rate(&self, at: Option<BlockHash>) -> Result<ExchangeRate>;
+
+	#[rpc(name = "stakingPool_getStakingPoolInfo")]
+	fn get_staking_pool_info(&self, at: Option<BlockHash>) -> Result<StakingPoolInfo<Balance>>;
+
+	#[rpc(name = "stakingPool_getRedeemQuote")]
+	fn get_redeem_quote(
+		&self,
+		amount: NumberOrHex,
+		strategy: RedeemStrategy,
+		at: Option<BlockHash>,
+	) -> Result<Option<RedeemQuote<Balance>>>;
 }
 
 /// A struct that implements the [`StakingPoolApi`].
@@ -43,24 +58,26 @@ impl<C, B> StakingPool<C, B> {
 
 pub enum Error {
 	RuntimeError,
+	InvalidParams,
 }
 
 impl From<Error> for i64 {
 	fn from(e: Error) -> i64 {
 		match e {
 			Error::RuntimeError => 1,
+			Error::InvalidParams => 2,
 		}
 	}
 }
 
-impl<C, Block, AccountId, Balance> StakingPoolApi<<Block as BlockT>::Hash, AccountId, BalanceInfo<Balance>>
+impl<C, Block, AccountId, Balance> StakingPoolApi<<Block as BlockT>::Hash, AccountId, BalanceInfo<Balance>, Balance>
 	for StakingPool<C, Block>
 where
 	Block: BlockT,
 	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
 	C::Api: StakingPoolRuntimeApi<Block, AccountId, Balance>,
 	AccountId: Codec,
-	Balance: Codec + MaybeDisplay + MaybeFromStr,
+	Balance: Codec + MaybeDisplay + MaybeFromStr + TryFrom<u128>,
 {
 	fn get_available_unbonded(
 		&self,
@@ -93,4 +110,46 @@ where
 			data: Some(format!("{:?}", e).into()),
 		})
 	}
+
+	fn get_staking_pool_info(&self, at: Option<<Block as BlockT>::Hash>) -> Result<StakingPoolInfo<Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or(
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash,
+		));
+
+		api.get_staking_pool_info(&at).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to get staking pool info.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+
+	fn get_redeem_quote(
+		&self,
+		amount: NumberOrHex,
+		strategy: RedeemStrategy,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<RedeemQuote<Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or(
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash,
+		));
+
+		let amount: Balance = TryInto::<u128>::try_into(amount.into_u256())
+			.ok()
+			.and_then(|v| Balance::try_from(v).ok())
+			.ok_or_else(|| RpcError {
+				code: ErrorCode::ServerError(Error::InvalidParams.into()),
+				message: format!("Invalid parameter amount: {:?}", amount),
+				data: None,
+			})?;
+
+		api.get_redeem_quote(&at, amount, strategy).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to get redeem quote.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
 }
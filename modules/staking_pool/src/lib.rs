@@ -1,21 +1,18 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::{Decode, Encode};
 use frame_support::{
 	decl_error, decl_event, decl_module, decl_storage, ensure,
 	traits::{EnsureOrigin, Get},
 	transactional,
 	weights::DispatchClass,
-	IterableStorageDoubleMap,
+	IterableStorageDoubleMap, IterableStorageMap,
 };
 use frame_system::{self as system};
 use orml_traits::{Change, MultiCurrency};
 use primitives::{Balance, CurrencyId, EraIndex};
-#[cfg(feature = "std")]
-use serde::{Deserialize, Serialize};
 use sp_runtime::{
 	traits::{AccountIdConversion, CheckedDiv, One, Saturating, Zero},
-	DispatchError, DispatchResult, FixedPointNumber, ModuleId, RuntimeDebug,
+	DispatchError, DispatchResult, FixedPointNumber, ModuleId,
 };
 use sp_std::prelude::*;
 use support::{
@@ -27,15 +24,7 @@ mod mock;
 mod tests;
 
 /// The params related to rebalance per era
-#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, Default)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-pub struct Params {
-	pub target_max_free_unbonded_ratio: Ratio,
-	pub target_min_free_unbonded_ratio: Ratio,
-	pub target_unbonding_to_free_ratio: Ratio,
-	pub unbonding_to_free_adjustment: Rate,
-	pub base_fee_rate: Rate,
-}
+pub use primitives::homa::StakingPoolParams as Params;
 
 pub trait FeeModel<Balance> {
 	fn get_fee(
@@ -87,6 +76,10 @@ pub trait Config: system::Config {
 
 	/// The currency for managing assets related to Homa protocol.
 	type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+	/// The number of recent eras whose staking rewards are kept to estimate
+	/// the APR.
+	type EraRewardsHistoryDepth: Get<EraIndex>;
 }
 
 decl_event!(
@@ -132,6 +125,8 @@ decl_storage! {
 		pub FreeUnbonded get(fn free_unbonded): Balance;
 
 		pub StakingPoolParams get(fn staking_pool_params) config(): Params;
+
+		pub EraRewards get(fn era_rewards): map hasher(twox_64_concat) EraIndex => (Balance, Balance); // (bonded_before_payout, reward)
 	}
 }
 
@@ -155,6 +150,9 @@ decl_module! {
 		/// The sub account indexs of parachain to vault assets of Homa protocol in Polkadot.
 		const PoolAccountIndexes: Vec<u32> = T::PoolAccountIndexes::get();
 
+		/// The number of recent eras whose staking rewards are kept to estimate the APR.
+		const EraRewardsHistoryDepth: EraIndex = T::EraRewardsHistoryDepth::get();
+
 		#[weight = (10_000, DispatchClass::Operational)]
 		#[transactional]
 		pub fn set_staking_pool_params(
@@ -430,6 +428,188 @@ impl<T: Config> Module<T> {
 			})
 	}
 
+	/// Record the staking reward of the bridge at `era`, and prune the record
+	/// which is out of `EraRewardsHistoryDepth`.
+	fn record_era_reward(era: EraIndex, bonded: Balance, reward: Balance) {
+		EraRewards::insert(era, (bonded, reward));
+		if let Some(expired_era) = era.checked_sub(T::EraRewardsHistoryDepth::get()) {
+			EraRewards::remove(expired_era);
+		}
+	}
+
+	/// Estimate the APR of staking by the average reward rate of recorded
+	/// eras, `eras_per_year` is used to annualize the per era rate.
+	pub fn get_estimated_apr(eras_per_year: EraIndex) -> Rate {
+		let (total_bonded, total_reward) = EraRewards::iter().fold(
+			(Balance::zero(), Balance::zero()),
+			|(total_bonded, total_reward), (_, (bonded, reward))| {
+				(total_bonded.saturating_add(bonded), total_reward.saturating_add(reward))
+			},
+		);
+
+		Rate::checked_from_rational(total_reward, total_bonded)
+			.unwrap_or_default()
+			.saturating_mul(Rate::saturating_from_integer(eras_per_year))
+	}
+
+	/// Calculate the result of redeeming `amount` liquid currency by waiting
+	/// for unbonding, without changing any state.
+	/// Returns `Some((liquid_amount_to_redeem, staking_amount_to_unbond, fee))`,
+	/// or `None` if nothing can be redeemed now. The fee is always zero.
+	pub fn quote_redeem_by_unbond(
+		amount: Balance,
+	) -> sp_std::result::Result<Option<(Balance, Balance, Balance)>, DispatchError> {
+		let mut liquid_amount_to_redeem = amount;
+		let liquid_exchange_rate = Self::liquid_exchange_rate();
+		let mut staking_amount_to_unbond = liquid_exchange_rate
+			.checked_mul_int(liquid_amount_to_redeem)
+			.ok_or(Error::<T>::Overflow)?;
+		let communal_bonded_staking_amount = Self::get_communal_bonded();
+
+		if staking_amount_to_unbond.is_zero() || communal_bonded_staking_amount.is_zero() {
+			return Ok(None);
+		}
+
+		// communal_bonded_staking_amount is not enough, re-calculate
+		if staking_amount_to_unbond > communal_bonded_staking_amount {
+			liquid_amount_to_redeem = liquid_exchange_rate
+				.reciprocal()
+				.unwrap_or_default()
+				.saturating_mul_int(communal_bonded_staking_amount);
+			staking_amount_to_unbond = communal_bonded_staking_amount;
+		}
+
+		Ok(Some((liquid_amount_to_redeem, staking_amount_to_unbond, Zero::zero())))
+	}
+
+	/// Calculate the result of redeeming `amount` liquid currency from the free
+	/// unbonded pool, without changing any state.
+	/// Returns `Some((liquid_amount_to_redeem, staking_amount_retrieved,
+	/// fee_in_staking))`, or `None` if nothing can be redeemed now.
+	pub fn quote_redeem_by_free_unbonded(
+		amount: Balance,
+	) -> sp_std::result::Result<Option<(Balance, Balance, Balance)>, DispatchError> {
+		let mut redeem_liquid_amount = amount;
+		let liquid_exchange_rate = Self::liquid_exchange_rate();
+		let mut demand_staking_amount = liquid_exchange_rate
+			.checked_mul_int(redeem_liquid_amount)
+			.ok_or(Error::<T>::Overflow)?;
+
+		let staking_pool_params = Self::staking_pool_params();
+		let available_free_unbonded = Self::free_unbonded().saturating_sub(
+			staking_pool_params
+				.target_min_free_unbonded_ratio
+				.saturating_mul_int(Self::get_total_communal_balance()),
+		);
+
+		if demand_staking_amount.is_zero() || available_free_unbonded.is_zero() {
+			return Ok(None);
+		}
+
+		// if available_free_unbonded is not enough, need re-calculate
+		if demand_staking_amount > available_free_unbonded {
+			let ratio = Ratio::checked_from_rational(available_free_unbonded, demand_staking_amount)
+				.ok_or(Error::<T>::Overflow)?;
+			redeem_liquid_amount = ratio.saturating_mul_int(redeem_liquid_amount);
+			demand_staking_amount = available_free_unbonded;
+		}
+
+		let current_free_unbonded_ratio = Self::get_free_unbonded_ratio();
+		let remain_available_percent = current_free_unbonded_ratio
+			.saturating_sub(staking_pool_params.target_min_free_unbonded_ratio)
+			.checked_div(
+				&sp_std::cmp::max(
+					staking_pool_params.target_max_free_unbonded_ratio,
+					current_free_unbonded_ratio,
+				)
+				.saturating_sub(staking_pool_params.target_min_free_unbonded_ratio),
+			)
+			.ok_or(Error::<T>::InvalidConfig)?;
+		let fee_in_staking = T::FeeModel::get_fee(
+			remain_available_percent,
+			available_free_unbonded,
+			demand_staking_amount,
+			staking_pool_params.base_fee_rate,
+		)
+		.ok_or(Error::<T>::GetFeeFailed)?;
+
+		let retrieved_staking_amount = demand_staking_amount.saturating_sub(fee_in_staking);
+
+		Ok(Some((redeem_liquid_amount, retrieved_staking_amount, fee_in_staking)))
+	}
+
+	/// Calculate the result of redeeming `amount` liquid currency by claiming
+	/// the unbonding which will be unbonded at `target_era`, without changing
+	/// any state.
+	/// Returns `Some((liquid_amount_to_redeem, staking_amount_claimed,
+	/// fee_in_staking))`, or `None` if nothing can be redeemed now.
+	pub fn quote_redeem_by_claim_unbonding(
+		amount: Balance,
+		target_era: EraIndex,
+	) -> sp_std::result::Result<Option<(Balance, Balance, Balance)>, DispatchError> {
+		let current_era = Self::current_era();
+		let bonding_duration = <<T as Config>::Bridge as PolkadotBridgeType<_, _>>::BondingDuration::get();
+		ensure!(
+			target_era > current_era && target_era <= current_era + bonding_duration,
+			Error::<T>::InvalidEra,
+		);
+
+		let mut redeem_liquid_amount = amount;
+		let liquid_exchange_rate = Self::liquid_exchange_rate();
+		let mut demand_staking_amount = liquid_exchange_rate
+			.checked_mul_int(redeem_liquid_amount)
+			.ok_or(Error::<T>::Overflow)?;
+		let (unbonding, claimed_unbonding, initial_claimed_unbonding) = Self::unbonding(target_era);
+		let staking_pool_params = Self::staking_pool_params();
+
+		let initial_unclaimed = unbonding.saturating_sub(initial_claimed_unbonding);
+		let unclaimed = unbonding.saturating_sub(claimed_unbonding);
+
+		let available_unclaimed_unbonding = unclaimed.saturating_sub(
+			staking_pool_params
+				.target_min_free_unbonded_ratio
+				.saturating_mul_int(initial_unclaimed),
+		);
+
+		if demand_staking_amount.is_zero() || available_unclaimed_unbonding.is_zero() {
+			return Ok(None);
+		}
+
+		// if available_unclaimed_unbonding is not enough, need re-calculate
+		if demand_staking_amount > available_unclaimed_unbonding {
+			let ratio = Ratio::checked_from_rational(available_unclaimed_unbonding, demand_staking_amount)
+				.ok_or(Error::<T>::Overflow)?;
+			redeem_liquid_amount = ratio.saturating_mul_int(redeem_liquid_amount);
+			demand_staking_amount = available_unclaimed_unbonding;
+		}
+
+		let current_unclaimed_ratio =
+			Ratio::checked_from_rational(unclaimed, initial_unclaimed).ok_or(Error::<T>::Overflow)?;
+
+		let remain_available_percent = current_unclaimed_ratio
+			.saturating_sub(staking_pool_params.target_min_free_unbonded_ratio)
+			.checked_div(
+				&sp_std::cmp::max(
+					staking_pool_params.target_max_free_unbonded_ratio,
+					current_unclaimed_ratio,
+				)
+				.saturating_sub(staking_pool_params.target_min_free_unbonded_ratio),
+			)
+			.unwrap_or_default();
+
+		let fee_in_staking = T::FeeModel::get_fee(
+			remain_available_percent,
+			available_unclaimed_unbonding,
+			demand_staking_amount,
+			staking_pool_params.base_fee_rate,
+		)
+		.ok_or(Error::<T>::GetFeeFailed)?;
+
+		let claimed_staking_amount = demand_staking_amount.saturating_sub(fee_in_staking);
+
+		Ok(Some((redeem_liquid_amount, claimed_staking_amount, fee_in_staking)))
+	}
+
 	pub fn bond_to_bridge(amount: Balance) -> DispatchResult {
 		if amount.is_zero() {
 			return Ok(());
@@ -470,14 +650,19 @@ impl<T: Config> Module<T> {
 		// #1: bridge withdraw unbonded and withdraw payout
 		Self::withdraw_unbonded();
 
-		// TODO: record the balances of bridge before and after do payout_nominator,
-		// and oncommision to homa treasury according to RewardFeeRatio
+		// TODO: oncommision to homa treasury according to RewardFeeRatio
+		let bonded_before_payout = Self::staking_ledger().active;
 		Self::payout_nominator();
 
 		// #2: update staking pool by bridge ledger
 		// TODO: adjust the amount of this era unbond by the slash situation in last era
 		let bridge_ledger = Self::staking_ledger();
 		TotalBonded::put(bridge_ledger.active);
+		Self::record_era_reward(
+			era,
+			bonded_before_payout,
+			bridge_ledger.active.saturating_sub(bonded_before_payout),
+		);
 
 		// #3: withdraw available from bridge ledger and update unbonded at this era
 		let bridge_available = Self::balance().saturating_sub(bridge_ledger.total);
@@ -568,24 +753,11 @@ impl<T: Config> HomaProtocol<T::AccountId, Balance, EraIndex> for Module<T> {
 
 	/// Ensure atomic.
 	#[transactional]
-	fn redeem_by_unbond(who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
-		let mut liquid_amount_to_redeem = amount;
-		let liquid_exchange_rate = Self::liquid_exchange_rate();
-		let mut staking_amount_to_unbond = liquid_exchange_rate
-			.checked_mul_int(liquid_amount_to_redeem)
-			.ok_or(Error::<T>::Overflow)?;
-		let communal_bonded_staking_amount = Self::get_communal_bonded();
-
-		if !staking_amount_to_unbond.is_zero() && !communal_bonded_staking_amount.is_zero() {
-			// communal_bonded_staking_amount is not enough, re-calculate
-			if staking_amount_to_unbond > communal_bonded_staking_amount {
-				liquid_amount_to_redeem = liquid_exchange_rate
-					.reciprocal()
-					.unwrap_or_default()
-					.saturating_mul_int(communal_bonded_staking_amount);
-				staking_amount_to_unbond = communal_bonded_staking_amount;
-			}
-
+	fn redeem_by_unbond(
+		who: &T::AccountId,
+		amount: Self::Balance,
+	) -> sp_std::result::Result<Self::Balance, DispatchError> {
+		if let Some((liquid_amount_to_redeem, staking_amount_to_unbond, _)) = Self::quote_redeem_by_unbond(amount)? {
 			// burn liquid currency
 			T::Currency::withdraw(T::LiquidCurrencyId::get(), who, liquid_amount_to_redeem)
 				.map_err(|_| Error::<T>::LiquidCurrencyNotEnough)?;
@@ -612,57 +784,21 @@ impl<T: Config> HomaProtocol<T::AccountId, Balance, EraIndex> for Module<T> {
 				liquid_amount_to_redeem,
 				staking_amount_to_unbond,
 			));
+			return Ok(liquid_amount_to_redeem);
 		}
 
-		Ok(())
+		Ok(Zero::zero())
 	}
 
 	/// Ensure atomic.
 	#[transactional]
-	fn redeem_by_free_unbonded(who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
-		let mut redeem_liquid_amount = amount;
-		let liquid_exchange_rate = Self::liquid_exchange_rate();
-		let mut demand_staking_amount = liquid_exchange_rate
-			.checked_mul_int(redeem_liquid_amount)
-			.ok_or(Error::<T>::Overflow)?;
-
-		let staking_pool_params = Self::staking_pool_params();
-		let available_free_unbonded = Self::free_unbonded().saturating_sub(
-			staking_pool_params
-				.target_min_free_unbonded_ratio
-				.saturating_mul_int(Self::get_total_communal_balance()),
-		);
-
-		if !demand_staking_amount.is_zero() && !available_free_unbonded.is_zero() {
-			// if available_free_unbonded is not enough, need re-calculate
-			if demand_staking_amount > available_free_unbonded {
-				let ratio = Ratio::checked_from_rational(available_free_unbonded, demand_staking_amount)
-					.expect("demand_staking_amount is not zero; qed");
-				redeem_liquid_amount = ratio.saturating_mul_int(redeem_liquid_amount);
-				demand_staking_amount = available_free_unbonded;
-			}
-
-			let current_free_unbonded_ratio = Self::get_free_unbonded_ratio();
-			let remain_available_percent = current_free_unbonded_ratio
-				.saturating_sub(staking_pool_params.target_min_free_unbonded_ratio)
-				.checked_div(
-					&sp_std::cmp::max(
-						staking_pool_params.target_max_free_unbonded_ratio,
-						current_free_unbonded_ratio,
-					)
-					.saturating_sub(staking_pool_params.target_min_free_unbonded_ratio),
-				)
-				.expect("shouldn't panic expect the fee config is incorrect; qed");
-			let fee_in_staking = T::FeeModel::get_fee(
-				remain_available_percent,
-				available_free_unbonded,
-				demand_staking_amount,
-				staking_pool_params.base_fee_rate,
-			)
-			.ok_or(Error::<T>::GetFeeFailed)?;
-
-			let retrieved_staking_amount = demand_staking_amount.saturating_sub(fee_in_staking);
-
+	fn redeem_by_free_unbonded(
+		who: &T::AccountId,
+		amount: Self::Balance,
+	) -> sp_std::result::Result<Self::Balance, DispatchError> {
+		if let Some((redeem_liquid_amount, retrieved_staking_amount, fee_in_staking)) =
+			Self::quote_redeem_by_free_unbonded(amount)?
+		{
 			T::Currency::withdraw(T::LiquidCurrencyId::get(), who, redeem_liquid_amount)
 				.map_err(|_| Error::<T>::LiquidCurrencyNotEnough)?;
 			T::Currency::transfer(
@@ -682,71 +818,22 @@ impl<T: Config> HomaProtocol<T::AccountId, Balance, EraIndex> for Module<T> {
 				retrieved_staking_amount,
 				fee_in_staking,
 			));
+			return Ok(redeem_liquid_amount);
 		}
 
-		Ok(())
+		Ok(Zero::zero())
 	}
 
 	/// Ensure atomic.
 	#[transactional]
-	fn redeem_by_claim_unbonding(who: &T::AccountId, amount: Self::Balance, target_era: EraIndex) -> DispatchResult {
-		let current_era = Self::current_era();
-		let bonding_duration = <<T as Config>::Bridge as PolkadotBridgeType<_, _>>::BondingDuration::get();
-		ensure!(
-			target_era > current_era && target_era <= current_era + bonding_duration,
-			Error::<T>::InvalidEra,
-		);
-
-		let mut redeem_liquid_amount = amount;
-		let liquid_exchange_rate = Self::liquid_exchange_rate();
-		let mut demand_staking_amount = liquid_exchange_rate
-			.checked_mul_int(redeem_liquid_amount)
-			.ok_or(Error::<T>::Overflow)?;
-		let (unbonding, claimed_unbonding, initial_claimed_unbonding) = Self::unbonding(target_era);
-		let staking_pool_params = Self::staking_pool_params();
-
-		let initial_unclaimed = unbonding.saturating_sub(initial_claimed_unbonding);
-		let unclaimed = unbonding.saturating_sub(claimed_unbonding);
-
-		let available_unclaimed_unbonding = unclaimed.saturating_sub(
-			staking_pool_params
-				.target_min_free_unbonded_ratio
-				.saturating_mul_int(initial_unclaimed),
-		);
-
-		if !demand_staking_amount.is_zero() && !available_unclaimed_unbonding.is_zero() {
-			// if available_unclaimed_unbonding is not enough, need re-calculate
-			if demand_staking_amount > available_unclaimed_unbonding {
-				let ratio = Ratio::checked_from_rational(available_unclaimed_unbonding, demand_staking_amount)
-					.expect("staking_amount_to_claim is not zero; qed");
-				redeem_liquid_amount = ratio.saturating_mul_int(redeem_liquid_amount);
-				demand_staking_amount = available_unclaimed_unbonding;
-			}
-
-			let current_unclaimed_ratio = Ratio::checked_from_rational(unclaimed, initial_unclaimed)
-				.expect("if available_unclaimed_unbonding is not zero, initial_unclaimed must not be zero; qed");
-
-			let remain_available_percent = current_unclaimed_ratio
-				.saturating_sub(staking_pool_params.target_min_free_unbonded_ratio)
-				.checked_div(
-					&sp_std::cmp::max(
-						staking_pool_params.target_max_free_unbonded_ratio,
-						current_unclaimed_ratio,
-					)
-					.saturating_sub(staking_pool_params.target_min_free_unbonded_ratio),
-				)
-				.unwrap_or_default();
-
-			let fee_in_staking = T::FeeModel::get_fee(
-				remain_available_percent,
-				available_unclaimed_unbonding,
-				demand_staking_amount,
-				staking_pool_params.base_fee_rate,
-			)
-			.ok_or(Error::<T>::GetFeeFailed)?;
-
-			let claimed_staking_amount = demand_staking_amount.saturating_sub(fee_in_staking);
-
+	fn redeem_by_claim_unbonding(
+		who: &T::AccountId,
+		amount: Self::Balance,
+		target_era: EraIndex,
+	) -> sp_std::result::Result<Self::Balance, DispatchError> {
+		if let Some((redeem_liquid_amount, claimed_staking_amount, fee_in_staking)) =
+			Self::quote_redeem_by_claim_unbonding(amount, target_era)?
+		{
 			T::Currency::withdraw(T::LiquidCurrencyId::get(), who, redeem_liquid_amount)
 				.map_err(|_| Error::<T>::LiquidCurrencyNotEnough)?;
 
@@ -767,9 +854,10 @@ impl<T: Config> HomaProtocol<T::AccountId, Balance, EraIndex> for Module<T> {
 				claimed_staking_amount,
 				fee_in_staking,
 			));
+			return Ok(redeem_liquid_amount);
 		}
 
-		Ok(())
+		Ok(Zero::zero())
 	}
 
 	/// Ensure atomic.
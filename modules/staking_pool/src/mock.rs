@@ -351,6 +351,7 @@ parameter_types! {
 	pub DefaultExchangeRate: ExchangeRate = ExchangeRate::saturating_from_rational(10, 100);	// 1 : 10
 	pub const StakingPoolModuleId: ModuleId = ModuleId(*b"aca/stkp");
 	pub PoolAccountIndexes: Vec<u32> = vec![1, 2, 3, 4];
+	pub const EraRewardsHistoryDepth: EraIndex = 3;
 }
 
 ord_parameter_types! {
@@ -369,6 +370,7 @@ impl Config for Runtime {
 	type Nominees = MockNomineesProvider;
 	type Bridge = MockBridge;
 	type Currency = CurrenciesModule;
+	type EraRewardsHistoryDepth = EraRewardsHistoryDepth;
 }
 pub type StakingPoolModule = Module<Runtime>;
 
@@ -732,6 +732,64 @@ fn get_available_unbonded_work() {
 	});
 }
 
+#[test]
+fn get_estimated_apr_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(StakingPoolModule::get_estimated_apr(365), Rate::zero());
+
+		StakingPoolModule::record_era_reward(1, 1000, 10);
+		StakingPoolModule::record_era_reward(2, 2000, 10);
+		assert_eq!(StakingPoolModule::era_rewards(1), (1000, 10));
+		assert_eq!(StakingPoolModule::era_rewards(2), (2000, 10));
+		assert_eq!(
+			StakingPoolModule::get_estimated_apr(300),
+			Rate::saturating_from_rational(2, 1)
+		);
+
+		// records out of history depth are pruned
+		StakingPoolModule::record_era_reward(4, 1000, 0);
+		assert_eq!(StakingPoolModule::era_rewards(1), (0, 0));
+		assert_eq!(
+			StakingPoolModule::get_estimated_apr(300),
+			Rate::saturating_from_rational(1, 1)
+		);
+	});
+}
+
+#[test]
+fn quote_redeem_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(StakingPoolModule::quote_redeem_by_unbond(1000), Ok(None));
+		assert_eq!(StakingPoolModule::quote_redeem_by_free_unbonded(1000), Ok(None));
+
+		assert_eq!(StakingPoolModule::mint(&BOB, 1000), Ok(10000));
+		assert_ok!(StakingPoolModule::bond_to_bridge(500));
+
+		assert_eq!(
+			StakingPoolModule::quote_redeem_by_unbond(1000),
+			Ok(Some((1000, 100, 0)))
+		);
+		assert_eq!(
+			StakingPoolModule::quote_redeem_by_unbond(10000),
+			Ok(Some((5000, 500, 0)))
+		);
+		assert_eq!(
+			StakingPoolModule::quote_redeem_by_free_unbonded(1000),
+			Ok(Some((1000, 80, 20)))
+		);
+		assert_eq!(
+			StakingPoolModule::quote_redeem_by_claim_unbonding(1000, BondingDuration::get() + 1),
+			Err(Error::<Runtime>::InvalidEra.into())
+		);
+		assert_eq!(StakingPoolModule::quote_redeem_by_claim_unbonding(1000, 4), Ok(None));
+
+		// quote does not change any state
+		assert_eq!(StakingPoolModule::free_unbonded(), 500);
+		assert_eq!(StakingPoolModule::next_era_unbond(), (0, 0));
+		assert_eq!(CurrenciesModule::total_issuance(LDOT), 10000);
+	});
+}
+
 #[test]
 fn bond_to_bridge_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -891,7 +949,7 @@ fn redeem_by_unbond_work() {
 			0
 		);
 
-		assert_ok!(StakingPoolModule::redeem_by_unbond(&BOB, 9000));
+		assert_eq!(StakingPoolModule::redeem_by_unbond(&BOB, 9000), Ok(4000));
 		let redeem_by_unbond_event_2 = TestEvent::staking_pool(RawEvent::RedeemByUnbond(BOB, 4000, 400));
 		assert!(System::events()
 			.iter()
@@ -953,7 +1011,7 @@ fn redeem_by_free_unbonded_work() {
 		assert_eq!(CurrenciesModule::free_balance(LDOT, &BOB), 9000);
 
 		// when overflow available
-		assert_ok!(StakingPoolModule::redeem_by_free_unbonded(&BOB, 9000));
+		assert_eq!(StakingPoolModule::redeem_by_free_unbonded(&BOB, 9000), Ok(3662));
 		let redeem_by_free_unbonded_event_2 =
 			TestEvent::staking_pool(RawEvent::RedeemByFreeUnbonded(BOB, 3662, 300, 74));
 		assert!(System::events()
@@ -1014,7 +1072,7 @@ fn redeem_by_claim_unbonding_work() {
 		assert_eq!(StakingPoolModule::claimed_unbond(&BOB, 4), 0);
 
 		// when overflow available
-		assert_ok!(StakingPoolModule::redeem_by_claim_unbonding(&BOB, 10000, 4));
+		assert_eq!(StakingPoolModule::redeem_by_claim_unbonding(&BOB, 10000, 4), Ok(3910));
 		let redeem_by_claimed_unbonding_event_2 =
 			TestEvent::staking_pool(RawEvent::RedeemByClaimUnbonding(BOB, 4, 3910, 316, 79));
 		assert!(System::events()
@@ -14,6 +14,18 @@ pub trait NomineesProvider<AccountId> {
 	fn nominees() -> Vec<AccountId>;
 }
 
+/// Hooks around `who` redeeming liquid currency, so modules which locked the
+/// liquid currency can release it.
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+pub trait OnLiquidRedeem<AccountId, Balance> {
+	/// Called before `who` redeems up to `amount`, the locks may be lifted
+	/// temporarily for the redeem.
+	fn before_liquid_redeem(who: &AccountId, amount: Balance);
+	/// Called after `who` redeemed, `redeemed` is the amount actually burned.
+	/// The locks must be restored for the remaining.
+	fn on_liquid_redeem(who: &AccountId, redeemed: Balance);
+}
+
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
 pub struct PolkadotUnlockChunk<Balance, EraIndex> {
 	pub value: Balance,
@@ -71,8 +83,15 @@ pub trait HomaProtocol<AccountId, Balance, EraIndex> {
 	type Balance: Decode + Encode + Debug + Eq + PartialEq + Clone + HasCompact;
 
 	fn mint(who: &AccountId, amount: Balance) -> sp_std::result::Result<Balance, DispatchError>;
-	fn redeem_by_unbond(who: &AccountId, amount: Balance) -> DispatchResult;
-	fn redeem_by_free_unbonded(who: &AccountId, amount: Balance) -> DispatchResult;
-	fn redeem_by_claim_unbonding(who: &AccountId, amount: Balance, target_era: EraIndex) -> DispatchResult;
+	/// Redeem by unbond, returns the liquid amount burned.
+	fn redeem_by_unbond(who: &AccountId, amount: Balance) -> sp_std::result::Result<Balance, DispatchError>;
+	/// Redeem by free unbonded, returns the liquid amount burned.
+	fn redeem_by_free_unbonded(who: &AccountId, amount: Balance) -> sp_std::result::Result<Balance, DispatchError>;
+	/// Redeem by claim unbonding, returns the liquid amount burned.
+	fn redeem_by_claim_unbonding(
+		who: &AccountId,
+		amount: Balance,
+		target_era: EraIndex,
+	) -> sp_std::result::Result<Balance, DispatchError>;
 	fn withdraw_redemption(who: &AccountId) -> sp_std::result::Result<Balance, DispatchError>;
 }
@@ -1,6 +1,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode, FullCodec, HasCompact};
+use frame_support::weights::Weight;
 use primitives::evm::{CallInfo, Config};
 use sp_core::H160;
 use sp_runtime::{
@@ -15,8 +16,8 @@ use sp_std::{
 
 pub mod homa;
 pub use homa::{
-	HomaProtocol, NomineesProvider, OnCommission, OnNewEra, PolkadotBridge, PolkadotBridgeCall, PolkadotBridgeState,
-	PolkadotBridgeType, PolkadotStakingLedger, PolkadotUnlockChunk,
+	HomaProtocol, NomineesProvider, OnCommission, OnLiquidRedeem, OnNewEra, PolkadotBridge, PolkadotBridgeCall,
+	PolkadotBridgeState, PolkadotBridgeType, PolkadotStakingLedger, PolkadotUnlockChunk,
 };
 
 pub type Price = FixedU128;
@@ -249,6 +250,29 @@ impl<AccountId, CurrencyId, Balance> DEXIncentives<AccountId, CurrencyId, Balanc
 	}
 }
 
+/// The Honzon protocol of the users, used by the Honzon precompile.
+pub trait HonzonManager<AccountId, CurrencyId, Amount, Balance> {
+	/// Adjust the loan of `who` under `currency_id`.
+	fn adjust_loan(
+		who: &AccountId,
+		currency_id: CurrencyId,
+		collateral_adjustment: Amount,
+		debit_adjustment: Amount,
+	) -> DispatchResult;
+	/// Transfer the whole loan of `from` under `currency_id` to `to`, `to` must
+	/// have the authorization of `from`.
+	fn transfer_loan_from(currency_id: CurrencyId, from: &AccountId, to: &AccountId) -> DispatchResult;
+	/// Authorize `to` to manipulate the loan of `from` under `currency_id`.
+	fn authorize(from: &AccountId, currency_id: CurrencyId, to: &AccountId) -> DispatchResult;
+	/// Cancel the authorization of `to` under `currency_id`.
+	fn unauthorize(from: &AccountId, currency_id: CurrencyId, to: &AccountId) -> DispatchResult;
+	/// The collateral and debit of the loan of `who` under `currency_id`.
+	fn get_position(who: &AccountId, currency_id: CurrencyId) -> (Balance, Balance);
+	/// The current collateral ratio of the loan of `who` under `currency_id`,
+	/// `None` if there's no price.
+	fn get_collateral_ratio(who: &AccountId, currency_id: CurrencyId) -> Option<Ratio>;
+}
+
 /// Return true if the call of EVM precompile contract is allowed.
 pub trait PrecompileCallerFilter {
 	fn is_allowed(caller: H160) -> bool;
@@ -265,15 +289,59 @@ pub trait EVM {
 		gas_limit: u32,
 		config: Option<Config>,
 	) -> Result<CallInfo, sp_runtime::DispatchError>;
+	/// The current price of one gas.
+	fn gas_price() -> Self::Balance;
+}
+
+/// The allowances of the ERC20 mirror contracts of the native tokens, which
+/// are kept natively.
+pub trait Erc20Allowances<CurrencyId, Balance> {
+	/// The amount which `spender` is allowed to transfer from `owner`.
+	fn allowance(currency_id: CurrencyId, owner: H160, spender: H160) -> Balance;
+	/// Set the amount which `spender` is allowed to transfer from `owner`.
+	fn set_allowance(currency_id: CurrencyId, owner: H160, spender: H160, amount: Balance);
+}
+
+/// Check the ERC20 balances of EVM addresses.
+pub trait Erc20BalanceChecker {
+	/// Return true if `address` holds the tokens of any ERC20 currency.
+	fn has_erc20_balance(address: H160) -> bool;
+	/// The maximum weight of `has_erc20_balance`.
+	fn has_erc20_balance_weight() -> Weight;
+}
+
+impl Erc20BalanceChecker for () {
+	fn has_erc20_balance(_address: H160) -> bool {
+		false
+	}
+
+	fn has_erc20_balance_weight() -> Weight {
+		0
+	}
 }
 
 /// An abstraction of EVMBridge
 pub trait EVMBridge<InvokeContext, Balance> {
+	/// Execute ERC20.name() to read token name from ERC20 contract
+	fn name(context: InvokeContext) -> Result<Vec<u8>, DispatchError>;
+	/// Execute ERC20.symbol() to read token symbol from ERC20 contract
+	fn symbol(context: InvokeContext) -> Result<Vec<u8>, DispatchError>;
+	/// Execute ERC20.decimals() to read token decimals from ERC20 contract
+	fn decimals(context: InvokeContext) -> Result<u8, DispatchError>;
 	/// Execute ERC20.totalSupply() to read total supply from ERC20 contract
 	fn total_supply(context: InvokeContext) -> Result<Balance, DispatchError>;
 	/// Execute ERC20.balanceOf(address) to read balance of address from ERC20
 	/// contract
 	fn balance_of(context: InvokeContext, address: H160) -> Result<Balance, DispatchError>;
+	/// Execute ERC20.allowance(address, address) to read the allowance of
+	/// `spender` approved by `owner` from ERC20 contract
+	fn allowance(context: InvokeContext, owner: H160, spender: H160) -> Result<Balance, DispatchError>;
 	/// Execute ERC20.transfer(address, uint256) to transfer value to `to`
 	fn transfer(context: InvokeContext, to: H160, value: Balance) -> DispatchResult;
+	/// Execute ERC20.approve(address, uint256) to approve `spender` to
+	/// transfer value from the source
+	fn approve(context: InvokeContext, spender: H160, value: Balance) -> DispatchResult;
+	/// Execute ERC20.transferFrom(address, address, uint256) to transfer value
+	/// from `from` to `to` with the allowance of the source
+	fn transfer_from(context: InvokeContext, from: H160, to: H160, value: Balance) -> DispatchResult;
 }
@@ -2,7 +2,7 @@ use codec::{Decode, Encode};
 use evm::ExitReason;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
-use sp_core::{H160, U256};
+use sp_core::{H160, H256, U256};
 use sp_runtime::RuntimeDebug;
 use sp_std::vec::Vec;
 
@@ -38,8 +38,95 @@ pub struct CallInfo {
 	pub used_gas: U256,
 }
 
+/// The receipt of an EVM transaction, built from the EVM events of the
+/// extrinsic.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ExecutionReceipt {
+	/// The index of the extrinsic in the block.
+	pub extrinsic_index: u32,
+	/// The sender, zero if the execution isn't an EVM transaction.
+	pub from: H160,
+	/// The called contract, `None` for contract creation.
+	pub to: Option<H160>,
+	/// The created contract, `None` for contract call.
+	pub contract_address: Option<H160>,
+	/// Whether the execution succeeded.
+	pub status: bool,
+	/// The gas used, zero if the execution isn't an EVM transaction.
+	pub used_gas: U256,
+	pub logs: Vec<Log>,
+}
+
+/// The kind of tracer used to trace an EVM execution.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum TracerKind {
+	/// Record the tree of calls and creates.
+	CallTracer,
+	/// Record every executed opcode.
+	OpcodeTracer,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum CallType {
+	Call,
+	StaticCall,
+	DelegateCall,
+	Create,
+}
+
+/// A call or create frame of an EVM execution.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct CallTrace {
+	pub call_type: CallType,
+	pub from: H160,
+	/// The called or created contract.
+	pub to: H160,
+	pub value: U256,
+	/// The gas available to the frame.
+	pub gas: u64,
+	pub gas_used: u64,
+	pub input: Vec<u8>,
+	pub output: Vec<u8>,
+	/// `None` if the frame didn't exit, e.g. the execution was aborted.
+	pub exit_reason: Option<ExitReason>,
+	/// Whether the callee is a precompile.
+	pub precompile: bool,
+	/// The nested frames.
+	pub calls: Vec<CallTrace>,
+}
+
+/// An executed opcode.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct TraceStep {
+	/// The depth of the frame, starts from 1.
+	pub depth: u32,
+	/// The name of the opcode.
+	pub op: Vec<u8>,
+	/// The gas left before the opcode.
+	pub gas: u64,
+	pub gas_cost: u64,
+	pub stack: Vec<H256>,
+}
+
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum TraceResult {
+	/// The top level frames of the `CallTracer`.
+	Calls(Vec<CallTrace>),
+	/// The steps of the `OpcodeTracer`.
+	Steps(Vec<TraceStep>),
+}
+
 /// A mapping between `AccountId` and `H160`.
 pub trait AddressMapping<AccountId> {
 	fn to_account(evm: &H160) -> AccountId;
 	fn to_evm_address(account: &AccountId) -> Option<H160>;
+	/// Returns the EVM address of `account`, maps it to a default EVM address
+	/// if it has none.
+	fn get_or_create_evm_address(account: &AccountId) -> H160;
 }
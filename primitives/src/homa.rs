@@ -0,0 +1,26 @@
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::{FixedU128, RuntimeDebug};
+
+use crate::EraIndex;
+
+/// The way to redeem the liquid currency of Homa protocol.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum RedeemStrategy {
+	Immediately,
+	Target(EraIndex),
+	WaitForUnbonding,
+}
+
+/// The params of the staking pool related to rebalance per era
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct StakingPoolParams {
+	pub target_max_free_unbonded_ratio: FixedU128,
+	pub target_min_free_unbonded_ratio: FixedU128,
+	pub target_unbonding_to_free_ratio: FixedU128,
+	pub unbonding_to_free_adjustment: FixedU128,
+	pub base_fee_rate: FixedU128,
+}
@@ -2,8 +2,9 @@
 #![allow(clippy::unnecessary_cast)]
 
 pub mod evm;
+pub mod homa;
 
-use codec::{Decode, Encode};
+use codec::{Decode, Encode, EncodeLike, Error as CodecError, Input, Output};
 use sp_runtime::{
 	generic,
 	traits::{BlakeTwo256, IdentifyAccount, Verify},
@@ -61,6 +62,9 @@ pub type AuctionId = u32;
 /// Share type
 pub type Share = u128;
 
+/// EVM address type.
+pub type EvmAddress = sp_core::H160;
+
 /// Header type.
 pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
 
@@ -102,9 +106,66 @@ impl TryFrom<u8> for TokenSymbol {
 
 #[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, PartialOrd, Ord)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum DexShare {
+	Token(TokenSymbol),
+	ERC20(EvmAddress),
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum CurrencyId {
 	Token(TokenSymbol),
-	DEXShare(TokenSymbol, TokenSymbol),
+	DEXShare(DexShare, DexShare),
+	ERC20(EvmAddress),
+}
+
+/// The DEX shares of two tokens keep the encoding of
+/// `DEXShare(TokenSymbol, TokenSymbol)` at index 1, so the stored currency ids
+/// don't need migration. The DEX shares with ERC20 are encoded at index 3.
+impl Encode for CurrencyId {
+	fn encode_to<T: Output>(&self, dest: &mut T) {
+		match self {
+			CurrencyId::Token(token) => {
+				dest.push_byte(0);
+				token.encode_to(dest);
+			}
+			CurrencyId::DEXShare(DexShare::Token(token_0), DexShare::Token(token_1)) => {
+				dest.push_byte(1);
+				token_0.encode_to(dest);
+				token_1.encode_to(dest);
+			}
+			CurrencyId::ERC20(address) => {
+				dest.push_byte(2);
+				address.encode_to(dest);
+			}
+			CurrencyId::DEXShare(dex_share_0, dex_share_1) => {
+				dest.push_byte(3);
+				dex_share_0.encode_to(dest);
+				dex_share_1.encode_to(dest);
+			}
+		}
+	}
+}
+
+impl EncodeLike for CurrencyId {}
+
+impl Decode for CurrencyId {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+		match input.read_byte()? {
+			0 => Ok(CurrencyId::Token(TokenSymbol::decode(input)?)),
+			1 => Ok(CurrencyId::DEXShare(
+				DexShare::Token(TokenSymbol::decode(input)?),
+				DexShare::Token(TokenSymbol::decode(input)?),
+			)),
+			2 => Ok(CurrencyId::ERC20(EvmAddress::decode(input)?)),
+			3 => match (DexShare::decode(input)?, DexShare::decode(input)?) {
+				// the DEX shares of two tokens are only encoded at index 1
+				(DexShare::Token(_), DexShare::Token(_)) => Err("Invalid DEX share of CurrencyId".into()),
+				(dex_share_0, dex_share_1) => Ok(CurrencyId::DEXShare(dex_share_0, dex_share_1)),
+			},
+			_ => Err("Invalid variant of CurrencyId".into()),
+		}
+	}
 }
 
 impl CurrencyId {
@@ -116,21 +177,41 @@ impl CurrencyId {
 		matches!(self, CurrencyId::DEXShare(_, _))
 	}
 
+	pub fn is_erc20_currency_id(&self) -> bool {
+		matches!(self, CurrencyId::ERC20(_))
+	}
+
 	pub fn split_dex_share_currency_id(&self) -> Option<(Self, Self)> {
 		match self {
-			CurrencyId::DEXShare(token_symbol_0, token_symbol_1) => {
-				Some((CurrencyId::Token(*token_symbol_0), CurrencyId::Token(*token_symbol_1)))
-			}
+			CurrencyId::DEXShare(dex_share_0, dex_share_1) => Some(((*dex_share_0).into(), (*dex_share_1).into())),
 			_ => None,
 		}
 	}
 
 	pub fn join_dex_share_currency_id(currency_id_0: Self, currency_id_1: Self) -> Option<Self> {
-		match (currency_id_0, currency_id_1) {
-			(CurrencyId::Token(token_symbol_0), CurrencyId::Token(token_symbol_1)) => {
-				Some(CurrencyId::DEXShare(token_symbol_0, token_symbol_1))
-			}
-			_ => None,
+		let dex_share_0 = currency_id_0.try_into().ok()?;
+		let dex_share_1 = currency_id_1.try_into().ok()?;
+		Some(CurrencyId::DEXShare(dex_share_0, dex_share_1))
+	}
+}
+
+impl Into<CurrencyId> for DexShare {
+	fn into(self) -> CurrencyId {
+		match self {
+			DexShare::Token(token) => CurrencyId::Token(token),
+			DexShare::ERC20(address) => CurrencyId::ERC20(address),
+		}
+	}
+}
+
+impl TryFrom<CurrencyId> for DexShare {
+	type Error = ();
+
+	fn try_from(currency_id: CurrencyId) -> Result<Self, Self::Error> {
+		match currency_id {
+			CurrencyId::Token(token) => Ok(DexShare::Token(token)),
+			CurrencyId::ERC20(address) => Ok(DexShare::ERC20(address)),
+			CurrencyId::DEXShare(_, _) => Err(()),
 		}
 	}
 }
@@ -141,6 +222,14 @@ impl TryFrom<[u8; 32]> for CurrencyId {
 	type Error = ();
 
 	fn try_from(v: [u8; 32]) -> Result<Self, Self::Error> {
+		// ERC20
+		if v[29] == 2 {
+			if !v.starts_with(&[0u8; 9][..]) || v[30] != 0 || v[31] != 0 {
+				return Err(());
+			}
+			return Ok(CurrencyId::ERC20(EvmAddress::from_slice(&v[9..29])));
+		}
+
 		if !v.starts_with(&[0u8; 29][..]) {
 			return Err(());
 		}
@@ -154,7 +243,7 @@ impl TryFrom<[u8; 32]> for CurrencyId {
 		if v[29] == 1 {
 			let left = v[30].try_into()?;
 			let right = v[31].try_into()?;
-			return Ok(CurrencyId::DEXShare(left, right));
+			return Ok(CurrencyId::DEXShare(DexShare::Token(left), DexShare::Token(right)));
 		}
 
 		Err(())
@@ -163,20 +252,29 @@ impl TryFrom<[u8; 32]> for CurrencyId {
 
 /// Note the pre-deployed ERC20 contracts depend on `CurrencyId` implementation,
 /// and need to be updated if any change.
-impl Into<[u8; 32]> for CurrencyId {
-	fn into(self) -> [u8; 32] {
+///
+/// The DEX share of ERC20 tokens can't be encoded into 32 bytes.
+impl TryFrom<CurrencyId> for [u8; 32] {
+	type Error = ();
+
+	fn try_from(currency_id: CurrencyId) -> Result<Self, Self::Error> {
 		let mut bytes = [0u8; 32];
-		match self {
+		match currency_id {
 			CurrencyId::Token(token) => {
 				bytes[30] = token as u8;
 			}
-			CurrencyId::DEXShare(left, right) => {
+			CurrencyId::DEXShare(DexShare::Token(left), DexShare::Token(right)) => {
 				bytes[29] = 1;
 				bytes[30] = left as u8;
 				bytes[31] = right as u8;
 			}
+			CurrencyId::DEXShare(_, _) => return Err(()),
+			CurrencyId::ERC20(address) => {
+				bytes[9..29].copy_from_slice(address.as_bytes());
+				bytes[29] = 2;
+			}
 		}
-		bytes
+		Ok(bytes)
 	}
 }
 
@@ -218,8 +316,11 @@ impl TradingPair {
 		}
 	}
 
+	/// The trading pair of tokens, both the native tokens and the ERC20 tokens.
 	pub fn from_token_currency_ids(currency_id_0: CurrencyId, currency_id_1: CurrencyId) -> Option<Self> {
-		match currency_id_0.is_token_currency_id() && currency_id_1.is_token_currency_id() {
+		let is_token =
+			|currency_id: &CurrencyId| currency_id.is_token_currency_id() || currency_id.is_erc20_currency_id();
+		match is_token(&currency_id_0) && is_token(&currency_id_1) {
 			true if currency_id_0 > currency_id_1 => Some(TradingPair(currency_id_1, currency_id_0)),
 			true if currency_id_0 < currency_id_1 => Some(TradingPair(currency_id_0, currency_id_1)),
 			_ => None,
@@ -234,7 +335,34 @@ impl TradingPair {
 /// The start address for pre-compiles.
 pub const PRECOMPILE_ADDRESS_START: u64 = 1024;
 
+/// The start address for the ERC20 mirror contracts of the native tokens,
+/// the 256 addresses are reserved for the token indexes.
+///
+/// The mirrors are precompiles between the other precompiles and
+/// `PREDEPLOY_ADDRESS_START`, rather than pre-deployed contracts from
+/// `PREDEPLOY_ADDRESS_START`. The pre-deployed range is assigned to the
+/// contracts of the genesis config in order, so the mirrors wouldn't have
+/// deterministic addresses there.
+pub const ERC20_MIRROR_ADDRESS_START: u64 = 1536;
+
 /// The start address for pre-deployed smart contracts.
 pub const PREDEPLOY_ADDRESS_START: u64 = 2048;
 
+/// The address of the ERC20 mirror contract of the native token `symbol`,
+/// `ERC20_MIRROR_ADDRESS_START` plus the token index.
+pub fn erc20_mirror_address(symbol: TokenSymbol) -> EvmAddress {
+	EvmAddress::from_low_u64_be(ERC20_MIRROR_ADDRESS_START + symbol as u64)
+}
+
+/// The native token of the ERC20 mirror contract at `address`.
+pub fn erc20_mirror_token(address: EvmAddress) -> Option<TokenSymbol> {
+	let index = address.to_low_u64_be().checked_sub(ERC20_MIRROR_ADDRESS_START)?;
+	let symbol: TokenSymbol = u8::try_from(index).ok()?.try_into().ok()?;
+	if erc20_mirror_address(symbol) == address {
+		Some(symbol)
+	} else {
+		None
+	}
+}
+
 pub type NFTBalance = u128;
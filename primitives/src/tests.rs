@@ -4,21 +4,47 @@ use frame_support::{assert_err, assert_ok};
 
 #[test]
 fn currency_id_to_bytes_works() {
-	assert_eq!(Into::<[u8; 32]>::into(CurrencyId::Token(TokenSymbol::ACA)), [0u8; 32]);
+	assert_eq!(
+		TryInto::<[u8; 32]>::try_into(CurrencyId::Token(TokenSymbol::ACA)),
+		Ok([0u8; 32])
+	);
 
 	let mut bytes = [0u8; 32];
 	bytes[29..].copy_from_slice(&[0, 1, 0][..]);
-	assert_eq!(Into::<[u8; 32]>::into(CurrencyId::Token(TokenSymbol::AUSD)), bytes);
+	assert_eq!(
+		TryInto::<[u8; 32]>::try_into(CurrencyId::Token(TokenSymbol::AUSD)),
+		Ok(bytes)
+	);
 
 	let mut bytes = [0u8; 32];
 	bytes[29..].copy_from_slice(&[0, 5, 0][..]);
-	assert_eq!(Into::<[u8; 32]>::into(CurrencyId::Token(TokenSymbol::RENBTC)), bytes);
+	assert_eq!(
+		TryInto::<[u8; 32]>::try_into(CurrencyId::Token(TokenSymbol::RENBTC)),
+		Ok(bytes)
+	);
 
 	let mut bytes = [0u8; 32];
 	bytes[29..].copy_from_slice(&[1, 0, 1][..]);
 	assert_eq!(
-		Into::<[u8; 32]>::into(CurrencyId::DEXShare(TokenSymbol::ACA, TokenSymbol::AUSD)),
-		bytes
+		TryInto::<[u8; 32]>::try_into(CurrencyId::DEXShare(
+			DexShare::Token(TokenSymbol::ACA),
+			DexShare::Token(TokenSymbol::AUSD)
+		)),
+		Ok(bytes)
+	);
+
+	let address = EvmAddress::from_low_u64_be(2048);
+	let mut bytes = [0u8; 32];
+	bytes[9..29].copy_from_slice(address.as_bytes());
+	bytes[29] = 2;
+	assert_eq!(TryInto::<[u8; 32]>::try_into(CurrencyId::ERC20(address)), Ok(bytes));
+
+	assert_eq!(
+		TryInto::<[u8; 32]>::try_into(CurrencyId::DEXShare(
+			DexShare::Token(TokenSymbol::ACA),
+			DexShare::ERC20(address)
+		)),
+		Err(())
 	);
 }
 
@@ -36,7 +62,7 @@ fn currency_id_try_from_bytes_works() {
 	bytes[29..].copy_from_slice(&[1, 0, 1][..]);
 	assert_ok!(
 		bytes.try_into(),
-		CurrencyId::DEXShare(TokenSymbol::ACA, TokenSymbol::AUSD)
+		CurrencyId::DEXShare(DexShare::Token(TokenSymbol::ACA), DexShare::Token(TokenSymbol::AUSD))
 	);
 
 	let mut bytes = [0u8; 32];
@@ -46,11 +72,106 @@ fn currency_id_try_from_bytes_works() {
 	let mut bytes = [0u8; 32];
 	bytes[29..].copy_from_slice(&[1, 0, 6][..]);
 	assert_err!(TryInto::<CurrencyId>::try_into(bytes), ());
+
+	let address = EvmAddress::from_low_u64_be(2048);
+	let mut bytes = [0u8; 32];
+	bytes[9..29].copy_from_slice(address.as_bytes());
+	bytes[29] = 2;
+	assert_ok!(bytes.try_into(), CurrencyId::ERC20(address));
+
+	bytes[0] = 1;
+	assert_err!(TryInto::<CurrencyId>::try_into(bytes), ());
 }
 
 #[test]
 fn currency_id_encode_decode_bytes_works() {
 	let currency_id = CurrencyId::Token(TokenSymbol::AUSD);
-	let bytes: [u8; 32] = currency_id.into();
-	assert_ok!(bytes.try_into(), currency_id)
+	let bytes: [u8; 32] = currency_id.try_into().unwrap();
+	assert_ok!(bytes.try_into(), currency_id);
+
+	let currency_id = CurrencyId::ERC20(EvmAddress::repeat_byte(1));
+	let bytes: [u8; 32] = currency_id.try_into().unwrap();
+	assert_ok!(bytes.try_into(), currency_id);
+}
+
+#[test]
+fn dex_share_currency_id_works() {
+	let address = EvmAddress::repeat_byte(1);
+	let dex_share =
+		CurrencyId::join_dex_share_currency_id(CurrencyId::Token(TokenSymbol::AUSD), CurrencyId::ERC20(address));
+	assert_eq!(
+		dex_share,
+		Some(CurrencyId::DEXShare(
+			DexShare::Token(TokenSymbol::AUSD),
+			DexShare::ERC20(address)
+		))
+	);
+	assert_eq!(
+		dex_share.unwrap().split_dex_share_currency_id(),
+		Some((CurrencyId::Token(TokenSymbol::AUSD), CurrencyId::ERC20(address)))
+	);
+	assert_eq!(
+		CurrencyId::join_dex_share_currency_id(dex_share.unwrap(), CurrencyId::ERC20(address)),
+		None
+	);
+
+	assert_eq!(
+		TradingPair::from_token_currency_ids(CurrencyId::ERC20(address), CurrencyId::Token(TokenSymbol::AUSD)),
+		Some(TradingPair(
+			CurrencyId::Token(TokenSymbol::AUSD),
+			CurrencyId::ERC20(address)
+		))
+	);
+	assert_eq!(
+		TradingPair::from_token_currency_ids(dex_share.unwrap(), CurrencyId::Token(TokenSymbol::AUSD)),
+		None
+	);
+}
+
+#[test]
+fn erc20_mirror_address_works() {
+	assert_eq!(
+		erc20_mirror_address(TokenSymbol::ACA),
+		EvmAddress::from_low_u64_be(ERC20_MIRROR_ADDRESS_START)
+	);
+	// the mirrors don't overlap the pre-deployed contracts
+	assert!(erc20_mirror_address(TokenSymbol::RENBTC) < EvmAddress::from_low_u64_be(PREDEPLOY_ADDRESS_START));
+	assert_eq!(
+		erc20_mirror_token(erc20_mirror_address(TokenSymbol::RENBTC)),
+		Some(TokenSymbol::RENBTC)
+	);
+	assert_eq!(
+		erc20_mirror_token(EvmAddress::from_low_u64_be(ERC20_MIRROR_ADDRESS_START + 6)),
+		None
+	);
+	assert_eq!(erc20_mirror_token(EvmAddress::from_low_u64_be(1024)), None);
+
+	let mut address = erc20_mirror_address(TokenSymbol::AUSD);
+	address.0[0] = 1;
+	assert_eq!(erc20_mirror_token(address), None);
+}
+
+#[test]
+fn currency_id_scale_encoding_works() {
+	use codec::{Decode, Encode};
+
+	let erc20 = EvmAddress::from_low_u64_be(1);
+	let token = CurrencyId::Token(TokenSymbol::AUSD);
+	let token_share = CurrencyId::DEXShare(DexShare::Token(TokenSymbol::AUSD), DexShare::Token(TokenSymbol::DOT));
+	let erc20_share = CurrencyId::DEXShare(DexShare::Token(TokenSymbol::AUSD), DexShare::ERC20(erc20));
+
+	assert_eq!(token.encode(), vec![0, 1]);
+	// the same as `DEXShare(TokenSymbol, TokenSymbol)`
+	assert_eq!(token_share.encode(), vec![1, 1, 2]);
+	assert_eq!(CurrencyId::ERC20(erc20).encode(), [&[2][..], &erc20[..]].concat());
+	assert_eq!(erc20_share.encode(), [&[3, 0, 1, 1][..], &erc20[..]].concat());
+
+	for currency_id in vec![token, token_share, CurrencyId::ERC20(erc20), erc20_share] {
+		assert_eq!(
+			CurrencyId::decode(&mut &currency_id.encode()[..]).ok(),
+			Some(currency_id)
+		);
+	}
+	assert!(CurrencyId::decode(&mut &[3, 0, 1, 0, 2][..]).is_err());
+	assert!(CurrencyId::decode(&mut &[4][..]).is_err());
 }
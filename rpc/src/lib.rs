@@ -18,7 +18,9 @@ use std::sync::Arc;
 
 pub use sc_rpc::SubscriptionTaskExecutor;
 
-pub use evm_rpc::{EVMApi, EVMApiServer, EVMRuntimeRPCApi};
+pub use evm_rpc::{
+	DebugApi, DebugApiServer, EVMApi, EVMApiServer, EVMRuntimeRPCApi, EVMTraceApi, EthApi, EthApiServer, LogIndex,
+};
 pub use sc_rpc::DenyUnsafe;
 
 /// A type representing all RPC extensions.
@@ -64,6 +66,8 @@ pub struct GrandpaDeps<B> {
 pub struct FullDeps<C, P, SC, B> {
 	/// The client instance to use.
 	pub client: Arc<C>,
+	/// The backend instance to use.
+	pub backend: Arc<B>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
 	/// The SelectChain Strategy
@@ -74,6 +78,8 @@ pub struct FullDeps<C, P, SC, B> {
 	pub babe: BabeDeps,
 	/// GRANDPA specific dependencies.
 	pub grandpa: GrandpaDeps<B>,
+	/// Index of the EVM logs.
+	pub log_index: LogIndex<Block, C, Balance>,
 }
 
 /// Instantiate all Full RPC extensions.
@@ -81,6 +87,7 @@ pub fn create_full<C, P, SC, B>(deps: FullDeps<C, P, SC, B>) -> RpcExtension
 where
 	C: ProvideRuntimeApi<Block>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError>,
+	C: sc_client_api::BlockBackend<Block> + sc_client_api::AuxStore,
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
 	C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber>,
@@ -88,9 +95,10 @@ where
 	C::Api: orml_oracle_rpc::OracleRuntimeApi<Block, DataProviderId, CurrencyId, runtime_common::TimeStampedPrice>,
 	C::Api: module_staking_pool_rpc::StakingPoolRuntimeApi<Block, AccountId, Balance>,
 	C::Api: EVMRuntimeRPCApi<Block, Balance>,
+	C::Api: EVMTraceApi<Block>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
-	P: TransactionPool + Sync + Send + 'static,
+	P: TransactionPool<Block = Block, Hash = Hash> + Sync + Send + 'static,
 	SC: SelectChain<Block> + 'static,
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
 	B::State: sc_client_api::StateBackend<sp_runtime::traits::HashFor<Block>>,
@@ -106,11 +114,13 @@ where
 	let mut io = jsonrpc_core::IoHandler::default();
 	let FullDeps {
 		client,
+		backend,
 		pool,
 		select_chain,
 		deny_unsafe,
 		babe,
 		grandpa,
+		log_index,
 	} = deps;
 	let BabeDeps {
 		keystore,
@@ -127,7 +137,7 @@ where
 
 	io.extend_with(SystemApi::to_delegate(FullSystem::new(
 		client.clone(),
-		pool,
+		pool.clone(),
 		deny_unsafe,
 	)));
 	io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(
@@ -154,7 +164,9 @@ where
 	)));
 	io.extend_with(OracleApi::to_delegate(Oracle::new(client.clone())));
 	io.extend_with(StakingPoolApi::to_delegate(StakingPool::new(client.clone())));
-	io.extend_with(EVMApiServer::to_delegate(EVMApi::new(client)));
+	io.extend_with(EVMApiServer::to_delegate(EVMApi::new(client.clone(), backend)));
+	io.extend_with(EthApiServer::to_delegate(EthApi::new(client.clone(), pool, log_index)));
+	io.extend_with(DebugApiServer::to_delegate(DebugApi::new(client, deny_unsafe)));
 
 	io
 }
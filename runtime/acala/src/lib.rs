@@ -18,10 +18,11 @@ use sp_api::impl_runtime_apis;
 use sp_core::{
 	crypto::KeyTypeId,
 	u32_trait::{_1, _2, _3, _4},
-	OpaqueMetadata, H160,
+	OpaqueMetadata, H160, H256, U256,
 };
 use sp_runtime::traits::{
-	BadOrigin, BlakeTwo256, Block as BlockT, NumberFor, OpaqueKeys, SaturatedConversion, Saturating, StaticLookup,
+	BadOrigin, BlakeTwo256, Block as BlockT, Convert, NumberFor, OpaqueKeys, SaturatedConversion, Saturating,
+	StaticLookup,
 };
 use sp_runtime::{
 	create_runtime_str,
@@ -58,7 +59,7 @@ pub use frame_support::{
 	},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
-		IdentityFee, Weight,
+		IdentityFee, Weight, WeightToFeePolynomial,
 	},
 	StorageValue,
 };
@@ -73,7 +74,7 @@ pub use authority::AuthorityConfigImpl;
 pub use constants::{currency::*, fee::*, time::*};
 pub use primitives::{
 	AccountId, AccountIndex, Amount, AuctionId, AuthoritysOriginId, Balance, BlockNumber, CurrencyId, DataProviderId,
-	EraIndex, Hash, Moment, Nonce, Share, Signature, TokenSymbol, TradingPair,
+	DexShare, EraIndex, Hash, Moment, Nonce, Share, Signature, TokenSymbol, TradingPair,
 };
 pub use runtime_common::{
 	CurveFeeModel, ExchangeRate, GasToWeight, Price, Rate, Ratio, SystemContractsFilter, TimeStampedPrice,
@@ -792,7 +793,7 @@ parameter_types! {
 
 impl orml_currencies::Config for Runtime {
 	type Event = Event;
-	type MultiCurrency = Tokens;
+	type MultiCurrency = module_evm_bridge::MultiCurrencyAdapter<Runtime, Tokens>;
 	type NativeCurrency = BasicCurrencyAdapter<Runtime, Balances, Amount, BlockNumber>;
 	type GetNativeCurrencyId = GetNativeCurrencyId;
 	type WeightInfo = ();
@@ -1053,12 +1054,19 @@ impl module_transaction_payment::Config for Runtime {
 	type WeightInfo = weights::transaction_payment::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	pub const EvmMappingCooldown: BlockNumber = DAYS;
+}
+
 impl module_evm_accounts::Config for Runtime {
 	type Event = Event;
 	type Currency = Balances;
 	type KillAccount = frame_system::CallKillAccount<Runtime>;
+	type ChainId = ChainId;
+	type MappingCooldown = EvmMappingCooldown;
 	type AddressMapping = EvmAddressMapping<Runtime>;
 	type MergeAccount = Currencies;
+	type Erc20BalanceChecker = EVMBridge;
 	type WeightInfo = weights::evm_accounts::WeightInfo<Runtime>;
 }
 
@@ -1109,6 +1117,7 @@ parameter_types! {
 	pub const GetStakingCurrencyId: CurrencyId = CurrencyId::Token(TokenSymbol::DOT);
 	pub DefaultExchangeRate: ExchangeRate = ExchangeRate::saturating_from_rational(10, 100);	// 1 : 10
 	pub PoolAccountIndexes: Vec<u32> = vec![1, 2, 3, 4];
+	pub const EraRewardsHistoryDepth: EraIndex = 28;
 }
 
 impl module_staking_pool::Config for Runtime {
@@ -1123,10 +1132,12 @@ impl module_staking_pool::Config for Runtime {
 	type Nominees = NomineesElection;
 	type Bridge = PolkadotBridge;
 	type Currency = Currencies;
+	type EraRewardsHistoryDepth = EraRewardsHistoryDepth;
 }
 
 impl module_homa::Config for Runtime {
 	type Homa = StakingPool;
+	type OnLiquidRedeem = NomineesElection;
 }
 
 parameter_types! {
@@ -1134,6 +1145,10 @@ parameter_types! {
 	pub const NominateesCount: usize = 7;
 	pub const MaxUnlockingChunks: usize = 7;
 	pub const NomineesElectionBondingDuration: EraIndex = 7;
+	pub const NomineesElectionStrategy: module_nominees_election::ElectionStrategy =
+		module_nominees_election::ElectionStrategy::TopVotes;
+	pub const MaxElectionVoters: u32 = 1000;
+	pub const NomineesElectionLiquidVoting: bool = false;
 }
 
 impl module_nominees_election::Config for Runtime {
@@ -1143,6 +1158,11 @@ impl module_nominees_election::Config for Runtime {
 	type BondingDuration = NomineesElectionBondingDuration;
 	type NominateesCount = NominateesCount;
 	type MaxUnlockingChunks = MaxUnlockingChunks;
+	type Strategy = NomineesElectionStrategy;
+	type MaxElectionVoters = MaxElectionVoters;
+	type LiquidStakingExchangeRateProvider = LiquidStakingExchangeRateProvider;
+	type LiquidVoting = NomineesElectionLiquidVoting;
+	type WeightInfo = weights::nominees_election::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -1238,11 +1258,22 @@ impl pallet_contracts::Config for Runtime {
 parameter_types! {
 	// TODO: update
 	pub const ChainId: u64 = 787;
+	pub const StorageMetering: module_evm::StorageGasMetering = module_evm::StorageGasMetering::Acala;
 	pub const ContractExistentialDeposit: Balance = DOLLARS;
 	pub const TransferMaintainerDeposit: Balance = DOLLARS;
 	pub const StorageDepositPerByte: Balance = MICROCENTS;
 	// https://eips.ethereum.org/EIPS/eip-170
 	pub const StorageDefaultQuota: u32 = 0x6000;
+	// TODO: update
+	pub const RentPerBytePerBlock: Balance = 0;
+	pub const RentGracePeriod: BlockNumber = 7 * DAYS;
+	pub const RentCollectionPeriod: BlockNumber = DAYS;
+	pub const MaxRentCollections: u32 = 50;
+	pub const DeveloperDeposit: Balance = 100 * DOLLARS;
+	pub const CodeUpgradeDelay: BlockNumber = 2 * DAYS;
+	// the weight fee of one gas
+	pub MinGasPrice: Balance = WeightToFee::calc(&GasToWeight::convert(1));
+	pub GasPriceMultiplier: Multiplier = TransactionPayment::next_fee_multiplier();
 	pub NetworkContractSource: H160 = H160::from_low_u64_be(0);
 }
 
@@ -1251,6 +1282,15 @@ pub type MultiCurrencyPrecompile =
 
 pub type NFTPrecompile = runtime_common::NFTPrecompile<AccountId, EvmAddressMapping<Runtime>, NFT>;
 
+pub type Erc20MirrorPrecompile = runtime_common::Erc20MirrorPrecompile<Runtime, Currencies, EVMBridge>;
+
+pub type OraclePrecompile =
+	runtime_common::OraclePrecompile<AccountId, EvmAddressMapping<Runtime>, Prices, AggregatedDataProvider>;
+
+pub type HonzonPrecompile = runtime_common::HonzonPrecompile<AccountId, EvmAddressMapping<Runtime>, Honzon>;
+
+pub type ScheduleCallPrecompile = runtime_common::ScheduleCallPrecompile<Runtime, Scheduler, Call, OriginCaller>;
+
 impl module_evm::Config for Runtime {
 	type AddressMapping = EvmAddressMapping<Runtime>;
 	type Currency = Balances;
@@ -1259,18 +1299,54 @@ impl module_evm::Config for Runtime {
 	type TransferMaintainerDeposit = TransferMaintainerDeposit;
 	type StorageDepositPerByte = StorageDepositPerByte;
 	type StorageDefaultQuota = StorageDefaultQuota;
+	type RentPerBytePerBlock = RentPerBytePerBlock;
+	type RentGracePeriod = RentGracePeriod;
+	type RentCollectionPeriod = RentCollectionPeriod;
+	type MaxRentCollections = MaxRentCollections;
+	type OnRentPayment = AcalaTreasury;
+	type CodeUpgradeDelay = CodeUpgradeDelay;
 	type Event = Event;
-	type Precompiles = runtime_common::AllPrecompiles<SystemContractsFilter, MultiCurrencyPrecompile, NFTPrecompile>;
+	type Precompiles = runtime_common::AllPrecompiles<
+		SystemContractsFilter,
+		MultiCurrencyPrecompile,
+		NFTPrecompile,
+		Erc20MirrorPrecompile,
+		OraclePrecompile,
+		HonzonPrecompile,
+		ScheduleCallPrecompile,
+	>;
 	type ChainId = ChainId;
 	type Runner = module_evm::runner::native::Runner<Self>;
 	type GasToWeight = GasToWeight;
+	type StorageMetering = StorageMetering;
 	type NetworkContractOrigin = EnsureRootOrTwoThirdsTechnicalCommittee;
 	type NetworkContractSource = NetworkContractSource;
+	type OnTransactionPayment = AcalaTreasury;
+	type MinGasPrice = MinGasPrice;
+	type GasPriceMultiplier = GasPriceMultiplier;
+	type DeploymentOrigin = EnsureRootOrHalfGeneralCouncil;
+	type DeveloperDeposit = DeveloperDeposit;
 	type WeightInfo = weights::evm::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	pub const Erc20QueryGasLimit: u32 = 1_000_000;
+	pub const Erc20TransactGasLimit: u32 = 2_100_000;
+	pub const MaxErc20Contracts: u32 = 16;
+}
+
 impl module_evm_bridge::Config for Runtime {
+	type Event = Event;
 	type EVM = EVM;
+	type AddressMapping = EvmAddressMapping<Runtime>;
+	type QueryGasLimit = Erc20QueryGasLimit;
+	type TransactGasLimit = Erc20TransactGasLimit;
+	type GasToWeight = GasToWeight;
+	type Currency = Balances;
+	type OnTransactionPayment = AcalaTreasury;
+	type RegisterOrigin = EnsureRootOrHalfGeneralCouncil;
+	type MaxErc20Contracts = MaxErc20Contracts;
+	type WeightInfo = weights::evm_bridge::WeightInfo<Runtime>;
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -1368,8 +1444,8 @@ construct_runtime!(
 
 		// Smart contracts
 		Contracts: pallet_contracts::{Module, Call, Config<T>, Storage, Event<T>},
-		EVM: module_evm::{Module, Config<T>, Call, Storage, Event<T>},
-		EVMBridge: module_evm_bridge::{Module},
+		EVM: module_evm::{Module, Config<T>, Call, Storage, Event<T>, ValidateUnsigned},
+		EVMBridge: module_evm_bridge::{Module, Call, Storage, Event},
 
 		// Dev
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
@@ -1403,8 +1479,46 @@ pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
 /// Executive: handles dispatch to the various modules.
-pub type Executive =
-	frame_executive::Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllModules>;
+pub type Executive = frame_executive::Executive<
+	Runtime,
+	Block,
+	frame_system::ChainContext<Runtime>,
+	Runtime,
+	AllModules,
+	runtime_common::InstallErc20MirrorCode<Runtime>,
+>;
+
+/// Replay the extrinsics of the block of `header` and trace the one at `index`.
+#[cfg(all(feature = "std", feature = "evm-tracing"))]
+fn trace_extrinsic(
+	header: <Block as BlockT>::Header,
+	extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+	index: u32,
+	tracer: module_evm::TracerKind,
+) -> Result<module_evm::TraceResult, sp_runtime::DispatchError> {
+	Executive::initialize_block(&header);
+
+	let mut extrinsics = extrinsics.into_iter();
+	for extrinsic in extrinsics.by_ref().take(index as usize) {
+		let _ = Executive::apply_extrinsic(extrinsic);
+	}
+	let extrinsic = extrinsics
+		.next()
+		.ok_or(sp_runtime::DispatchError::Other("extrinsic not found"))?;
+
+	let (_, trace) = module_evm::runner::tracing::using(tracer, || Executive::apply_extrinsic(extrinsic));
+	Ok(trace)
+}
+
+#[cfg(not(all(feature = "std", feature = "evm-tracing")))]
+fn trace_extrinsic(
+	_header: <Block as BlockT>::Header,
+	_extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+	_index: u32,
+	_tracer: module_evm::TracerKind,
+) -> Result<module_evm::TraceResult, sp_runtime::DispatchError> {
+	Err(sp_runtime::DispatchError::Other("EVM tracing is not enabled"))
+}
 
 #[cfg(not(feature = "disable-runtime-api"))]
 impl_runtime_apis! {
@@ -1604,6 +1718,55 @@ impl_runtime_apis! {
 		fn get_liquid_staking_exchange_rate() -> ExchangeRate {
 			StakingPool::liquid_exchange_rate()
 		}
+
+		fn get_staking_pool_info() -> module_staking_pool_rpc_runtime_api::StakingPoolInfo<Balance> {
+			use frame_support::IterableStorageMap;
+
+			let mut unbonding = module_staking_pool::Unbonding::iter()
+				.map(|(era, (unbonding, claimed, initial_claimed))| module_staking_pool_rpc_runtime_api::UnbondingInfo {
+					era,
+					unbonding,
+					claimed,
+					initial_claimed,
+				})
+				.collect::<Vec<_>>();
+			unbonding.sort_by_key(|info| info.era);
+
+			module_staking_pool_rpc_runtime_api::StakingPoolInfo {
+				params: StakingPool::staking_pool_params(),
+				total_bonded: StakingPool::total_bonded(),
+				free_unbonded: StakingPool::free_unbonded(),
+				unbonding_to_free: StakingPool::unbonding_to_free(),
+				unbonding,
+				liquid_exchange_rate: StakingPool::liquid_exchange_rate(),
+				estimated_apr: StakingPool::get_estimated_apr(365 * DAYS / EraLength::get()),
+			}
+		}
+
+		fn get_redeem_quote(
+			amount: Balance,
+			strategy: module_staking_pool_rpc_runtime_api::RedeemStrategy,
+		) -> Option<module_staking_pool_rpc_runtime_api::RedeemQuote<Balance>> {
+			let quote = match strategy {
+				module_staking_pool_rpc_runtime_api::RedeemStrategy::Immediately => {
+					StakingPool::quote_redeem_by_free_unbonded(amount)
+				}
+				module_staking_pool_rpc_runtime_api::RedeemStrategy::Target(target_era) => {
+					StakingPool::quote_redeem_by_claim_unbonding(amount, target_era)
+				}
+				module_staking_pool_rpc_runtime_api::RedeemStrategy::WaitForUnbonding => {
+					StakingPool::quote_redeem_by_unbond(amount)
+				}
+			};
+
+			quote.ok().flatten().map(|(liquid_amount, staking_amount, fee)| {
+				module_staking_pool_rpc_runtime_api::RedeemQuote {
+					liquid_amount,
+					staking_amount,
+					fee,
+				}
+			})
+		}
 	}
 
 	impl pallet_contracts_rpc_runtime_api::ContractsApi<Block, AccountId, Balance, BlockNumber>
@@ -1656,6 +1819,7 @@ impl_runtime_apis! {
 				data,
 				value,
 				gas_limit,
+				None,
 				config.as_ref().unwrap_or(<Runtime as module_evm::Config>::config()),
 			)
 		}
@@ -1680,10 +1844,60 @@ impl_runtime_apis! {
 				data,
 				value,
 				gas_limit,
+				None,
 				config.as_ref().unwrap_or(<Runtime as module_evm::Config>::config()),
 			)
 		}
 
+		fn chain_id() -> u64 {
+			<Runtime as module_evm::Config>::ChainId::get()
+		}
+
+		fn gas_price() -> U256 {
+			EVM::gas_price().into()
+		}
+
+		fn account_basic(address: H160) -> module_evm::Account {
+			EVM::account_basic(&address)
+		}
+
+		fn get_code(address: H160) -> Vec<u8> {
+			EVM::code_at_address(&address)
+		}
+
+		fn get_storage_at(address: H160, index: H256) -> H256 {
+			EVM::account_storages(address, index)
+		}
+
+		fn block_receipts() -> Vec<module_evm::ExecutionReceipt> {
+			let events = System::events()
+				.into_iter()
+				.filter_map(|record| match (record.phase, record.event) {
+					(frame_system::Phase::ApplyExtrinsic(index), Event::module_evm(event)) => Some((index, event)),
+					_ => None,
+				});
+			EVM::receipts_from_events(events)
+		}
+
+		fn block_timestamp() -> u64 {
+			Timestamp::now() / 1000
+		}
+
+		fn convert_transaction(transaction: Vec<u8>) -> Option<<Block as BlockT>::Extrinsic> {
+			let transaction = module_evm::EthereumTransaction::decode_rlp(&transaction).ok()?;
+			Some(UncheckedExtrinsic::new_unsigned(Call::EVM(module_evm::Call::transact(transaction))))
+		}
+	}
+
+	impl module_evm_rpc_runtime_api::EVMTraceApi<Block> for Runtime {
+		fn trace_extrinsic(
+			header: <Block as BlockT>::Header,
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			index: u32,
+			tracer: module_evm::TracerKind,
+		) -> Result<module_evm::TraceResult, sp_runtime::DispatchError> {
+			trace_extrinsic(header, extrinsics, index, tracer)
+		}
 	}
 
 
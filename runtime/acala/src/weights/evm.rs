@@ -4,6 +4,11 @@
 //! DATE: 2020-12-08, STEPS: [1, ], REPEAT: 1, LOW RANGE: [], HIGH RANGE: []
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB
 //! CACHE: 128
+//!
+//! Only the storage quota and transfer maintainer weights are generated. The
+//! rent, code upgrade and deployment permission weights are estimated from
+//! them by the storage accesses, rounded up, until the benchmarks of `evm` in
+//! `runtime/mandala/src/benchmarking/evm.rs` are run.
 
 #![allow(unused_parens)]
 #![allow(unused_imports)]
@@ -45,4 +50,57 @@ impl<T: frame_system::Config> module_evm::WeightInfo for WeightInfo<T> {
 			.saturating_add(DbWeight::get().reads(6 as Weight))
 			.saturating_add(DbWeight::get().writes(3 as Weight))
 	}
+	fn collect_rent(s: u32) -> Weight {
+		(300_000_000 as Weight)
+			.saturating_add((5_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(DbWeight::get().reads(9 as Weight))
+			.saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(DbWeight::get().writes(9 as Weight))
+			.saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+	}
+	fn on_initialize(c: u32) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((100_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().reads((6 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+			.saturating_add(DbWeight::get().writes((4 as Weight).saturating_mul(c as Weight)))
+	}
+	fn restore_contract(s: u32) -> Weight {
+		(300_000_000 as Weight)
+			.saturating_add((5_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(DbWeight::get().reads(7 as Weight))
+			.saturating_add(DbWeight::get().writes(7 as Weight))
+			.saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+	}
+	fn set_code(c: u32) -> Weight {
+		(200_000_000 as Weight)
+			.saturating_add((5_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn apply_code() -> Weight {
+		(350_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(6 as Weight))
+			.saturating_add(DbWeight::get().writes(6 as Weight))
+	}
+	fn add_deployer() -> Weight {
+		(40_000_000 as Weight).saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn remove_deployer() -> Weight {
+		(40_000_000 as Weight).saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn set_open_deployment() -> Weight {
+		(40_000_000 as Weight).saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn enable_developer_mode() -> Weight {
+		(130_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn disable_developer_mode() -> Weight {
+		(130_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
 }
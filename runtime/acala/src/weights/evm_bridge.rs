@@ -0,0 +1,26 @@
+//! Weights for evm_bridge
+//!
+//! Estimated from the benchmarked `dex::enable_trading_pair`, which has the
+//! same origin check, and the storage accesses, to be replaced by running the
+//! benchmarks of `evm_bridge`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
+
+use sp_std::marker::PhantomData;
+
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> module_evm_bridge::WeightInfo for WeightInfo<T> {
+	fn register_erc20() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn deregister_erc20() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+}
@@ -0,0 +1,22 @@
+//! Weights for nominees_election
+//!
+//! Estimated from the storage accesses, to be replaced by running the
+//! benchmarks of `nominees_election`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
+
+use sp_std::marker::PhantomData;
+
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> module_nominees_election::WeightInfo for WeightInfo<T> {
+	fn rebalance_by_phragmen(v: u32) -> Weight {
+		(34_127_000 as Weight)
+			.saturating_add((27_518_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+}
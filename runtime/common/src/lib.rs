@@ -12,7 +12,10 @@ use sp_runtime::{
 };
 
 pub mod precompile;
-pub use precompile::{AllPrecompiles, MultiCurrencyPrecompile, NFTPrecompile};
+pub use precompile::{
+	AllPrecompiles, Erc20MirrorPrecompile, HonzonPrecompile, InstallErc20MirrorCode, MultiCurrencyPrecompile,
+	NFTPrecompile, OraclePrecompile, ScheduleCallPrecompile, ERC20_MIRROR_CODE,
+};
 
 pub type TimeStampedPrice = orml_oracle::TimestampedValue<Price, primitives::Moment>;
 
@@ -267,11 +270,14 @@ impl PrecompileCallerFilter for SystemContractsFilter {
 	}
 }
 
+/// The weight of a unit of gas.
+pub const WEIGHT_PER_GAS: Weight = 1;
+
 /// Convert gas to weight
 pub struct GasToWeight;
 impl Convert<u32, Weight> for GasToWeight {
 	fn convert(a: u32) -> u64 {
-		a as Weight
+		(a as Weight).saturating_mul(WEIGHT_PER_GAS)
 	}
 }
 
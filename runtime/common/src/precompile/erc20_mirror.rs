@@ -0,0 +1,248 @@
+use frame_support::{
+	debug,
+	traits::{Get, OnRuntimeUpgrade},
+	weights::Weight,
+};
+use module_evm::{Context, ExitError, ExitSucceed, Log, Precompile};
+use module_support::Erc20Allowances;
+use primitives::{erc20_mirror_address, evm::AddressMapping as AddressMappingT, Balance, CurrencyId, TokenSymbol};
+use sp_core::{H160, H256, U256};
+use sp_std::{convert::TryFrom, marker::PhantomData, prelude::*, result};
+
+use orml_traits::MultiCurrency as MultiCurrencyT;
+
+use super::{
+	ensure_not_static, ensure_storage_cost,
+	input::{Input, InputT},
+};
+
+/// The ERC20 mirror contracts of the native tokens, pre-deployed at
+/// `primitives::erc20_mirror_address`.
+///
+/// The balances are the `MultiCurrency` balances of the mapped accounts, the
+/// allowances are kept natively by `Allowances`. The caller of the mirror
+/// contract is the sender. The transfers and approvals are rejected in the
+/// static calls.
+///
+/// Methods:
+/// - `totalSupply()`
+/// - `balanceOf(address)`
+/// - `allowance(address, address)`
+/// - `transfer(address, uint256)`, emits `Transfer`
+/// - `approve(address, uint256)`, emits `Approval`
+/// - `transferFrom(address, address, uint256)`, emits `Transfer`
+pub struct Erc20MirrorPrecompile<Runtime, MultiCurrency, Allowances>(PhantomData<(Runtime, MultiCurrency, Allowances)>);
+
+/// The placeholder code of the ERC20 mirror contracts, so that the contracts
+/// calling them see code at the addresses. The genesis config installs the
+/// same code.
+pub const ERC20_MIRROR_CODE: [u8; 1] = [0xfe];
+
+/// Install `ERC20_MIRROR_CODE` at the addresses of the ERC20 mirror contracts
+/// without code, for the chains started before the mirrors moved to
+/// `primitives::ERC20_MIRROR_ADDRESS_START`.
+pub struct InstallErc20MirrorCode<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime: module_evm::Config> OnRuntimeUpgrade for InstallErc20MirrorCode<Runtime> {
+	fn on_runtime_upgrade() -> Weight {
+		let mut reads: Weight = 0;
+		let mut writes: Weight = 0;
+		for symbol in (0..=u8::MAX).filter_map(|index| TokenSymbol::try_from(index).ok()) {
+			let address = erc20_mirror_address(symbol);
+			reads += 1;
+			if module_evm::Module::<Runtime>::accounts(&address)
+				.map_or(false, |account_info| account_info.contract_info.is_some())
+			{
+				continue;
+			}
+
+			reads += 2;
+			writes += 4;
+			if let Err(e) = module_evm::Module::<Runtime>::on_contract_initialization(
+				&address,
+				&H160::default(),
+				ERC20_MIRROR_CODE.to_vec(),
+				Some(0),
+			) {
+				debug::warn!("install ERC20 mirror code at {:?} failed: {:?}", address, e);
+			}
+		}
+		<Runtime as frame_system::Config>::DbWeight::get().reads_writes(reads, writes)
+	}
+}
+
+enum Action {
+	TotalSupply,
+	BalanceOf,
+	Allowance,
+	Transfer,
+	Approve,
+	TransferFrom,
+	Unknown,
+}
+
+impl From<&[u8]> for Action {
+	fn from(selector: &[u8]) -> Self {
+		match selector {
+			[0x18, 0x16, 0x0d, 0xdd] => Action::TotalSupply,
+			[0x70, 0xa0, 0x82, 0x31] => Action::BalanceOf,
+			[0xdd, 0x62, 0xed, 0x3e] => Action::Allowance,
+			[0xa9, 0x05, 0x9c, 0xbb] => Action::Transfer,
+			[0x09, 0x5e, 0xa7, 0xb3] => Action::Approve,
+			[0x23, 0xb8, 0x72, 0xdd] => Action::TransferFrom,
+			_ => Action::Unknown,
+		}
+	}
+}
+
+/// Transfer(address,address,uint256) event signature
+const TRANSFER_EVENT: [u8; 32] = [
+	0xdd, 0xf2, 0x52, 0xad, 0x1b, 0xe2, 0xc8, 0x9b, 0x69, 0xc2, 0xb0, 0x68, 0xfc, 0x37, 0x8d, 0xaa, 0x95, 0x2b, 0xa7,
+	0xf1, 0x63, 0xc4, 0xa1, 0x16, 0x28, 0xf5, 0x5a, 0x4d, 0xf5, 0x23, 0xb3, 0xef,
+];
+/// Approval(address,address,uint256) event signature
+const APPROVAL_EVENT: [u8; 32] = [
+	0x8c, 0x5b, 0xe1, 0xe5, 0xeb, 0xec, 0x7d, 0x5b, 0xd1, 0x4f, 0x71, 0x42, 0x7d, 0x1e, 0x84, 0xf3, 0xdd, 0x03, 0x14,
+	0xc0, 0xf7, 0xb2, 0x29, 0x1e, 0x5b, 0x20, 0x0a, 0xc8, 0xc7, 0xc3, 0xb9, 0x25,
+];
+
+impl<Runtime, MultiCurrency, Allowances> Precompile for Erc20MirrorPrecompile<Runtime, MultiCurrency, Allowances>
+where
+	Runtime: module_evm::Config,
+	MultiCurrency: MultiCurrencyT<Runtime::AccountId, Balance = Balance, CurrencyId = CurrencyId>,
+	Allowances: Erc20Allowances<CurrencyId, Balance>,
+{
+	fn execute(
+		input: &[u8],
+		target_gas: Option<usize>,
+		context: &Context,
+		is_static: bool,
+	) -> result::Result<(ExitSucceed, Vec<u8>, usize), ExitError> {
+		debug::debug!("input: {:?}", input);
+
+		let currency_id = primitives::erc20_mirror_token(context.address)
+			.map(CurrencyId::Token)
+			.ok_or_else(|| ExitError::Other("not a mirror contract".into()))?;
+		if input.len() < 4 {
+			return Err(ExitError::Other("invalid input".into()));
+		}
+		let action: Action = input[0..4].into();
+		// the params are 32-byte aligned after the 4 bytes selector
+		let input = Input::<u8, Runtime::AccountId, Runtime::AddressMapping>::new(&input[4..]);
+
+		match action {
+			Action::TotalSupply => {
+				// reads: total issuance
+				let cost = ensure_storage_cost(target_gas, 1, 0)?;
+				let total_issuance = MultiCurrency::total_issuance(currency_id);
+				Ok((ExitSucceed::Returned, vec_u8_from_balance(total_issuance), cost))
+			}
+			Action::BalanceOf => {
+				// reads: address mapping, account
+				let cost = ensure_storage_cost(target_gas, 2, 0)?;
+				let who = input.account_id_at(0)?;
+				let balance = MultiCurrency::free_balance(currency_id, &who);
+				Ok((ExitSucceed::Returned, vec_u8_from_balance(balance), cost))
+			}
+			Action::Allowance => {
+				// reads: allowance
+				let cost = ensure_storage_cost(target_gas, 1, 0)?;
+				let owner = input.evm_address_at(0)?;
+				let spender = input.evm_address_at(1)?;
+				let allowance = Allowances::allowance(currency_id, owner, spender);
+				Ok((ExitSucceed::Returned, vec_u8_from_balance(allowance), cost))
+			}
+			Action::Transfer => {
+				ensure_not_static(is_static)?;
+				// reads: 2 address mappings, 2 accounts
+				// writes: 2 accounts, log
+				let cost = ensure_storage_cost(target_gas, 4, 3)?;
+				let to = input.evm_address_at(0)?;
+				let amount = input.balance_at(1)?;
+
+				Self::transfer(context.address, currency_id, context.caller, to, amount)?;
+				Ok((ExitSucceed::Returned, vec_u8_from_bool(true), cost))
+			}
+			Action::Approve => {
+				ensure_not_static(is_static)?;
+				// writes: allowance, log
+				let cost = ensure_storage_cost(target_gas, 0, 2)?;
+				let spender = input.evm_address_at(0)?;
+				let amount = input.balance_at(1)?;
+				if spender == H160::default() {
+					return Err(ExitError::Other("approve to the zero address".into()));
+				}
+
+				Allowances::set_allowance(currency_id, context.caller, spender, amount);
+				Self::deposit_log(context.address, APPROVAL_EVENT, context.caller, spender, amount);
+				Ok((ExitSucceed::Returned, vec_u8_from_bool(true), cost))
+			}
+			Action::TransferFrom => {
+				ensure_not_static(is_static)?;
+				// reads: allowance, 2 address mappings, 2 accounts
+				// writes: allowance, 2 accounts, log
+				let cost = ensure_storage_cost(target_gas, 5, 4)?;
+				let from = input.evm_address_at(0)?;
+				let to = input.evm_address_at(1)?;
+				let amount = input.balance_at(2)?;
+
+				let allowance = Allowances::allowance(currency_id, from, context.caller)
+					.checked_sub(amount)
+					.ok_or_else(|| ExitError::Other("transfer amount exceeds allowance".into()))?;
+				Self::transfer(context.address, currency_id, from, to, amount)?;
+				Allowances::set_allowance(currency_id, from, context.caller, allowance);
+				Ok((ExitSucceed::Returned, vec_u8_from_bool(true), cost))
+			}
+			Action::Unknown => Err(ExitError::Other("unknown action".into())),
+		}
+	}
+}
+
+impl<Runtime, MultiCurrency, Allowances> Erc20MirrorPrecompile<Runtime, MultiCurrency, Allowances>
+where
+	Runtime: module_evm::Config,
+	MultiCurrency: MultiCurrencyT<Runtime::AccountId, Balance = Balance, CurrencyId = CurrencyId>,
+{
+	fn transfer(
+		contract: H160,
+		currency_id: CurrencyId,
+		from: H160,
+		to: H160,
+		amount: Balance,
+	) -> result::Result<(), ExitError> {
+		if to == H160::default() {
+			return Err(ExitError::Other("transfer to the zero address".into()));
+		}
+
+		let from_account = Runtime::AddressMapping::to_account(&from);
+		let to_account = Runtime::AddressMapping::to_account(&to);
+		MultiCurrency::transfer(currency_id, &from_account, &to_account, amount).map_err(|e| {
+			let err_msg: &str = e.into();
+			ExitError::Other(err_msg.into())
+		})?;
+
+		Self::deposit_log(contract, TRANSFER_EVENT, from, to, amount);
+		Ok(())
+	}
+
+	/// Emit the `Transfer` or `Approval` event of the mirror contract.
+	fn deposit_log(contract: H160, event: [u8; 32], from: H160, to: H160, amount: Balance) {
+		module_evm::Module::<Runtime>::deposit_log(Log {
+			address: contract,
+			topics: vec![H256::from(event), H256::from(from), H256::from(to)],
+			data: vec_u8_from_balance(amount),
+		});
+	}
+}
+
+fn vec_u8_from_balance(balance: Balance) -> Vec<u8> {
+	let mut be_bytes = [0u8; 32];
+	U256::from(balance).to_big_endian(&mut be_bytes[..]);
+	be_bytes.to_vec()
+}
+
+fn vec_u8_from_bool(value: bool) -> Vec<u8> {
+	let mut be_bytes = [0u8; 32];
+	be_bytes[31] = value as u8;
+	be_bytes.to_vec()
+}
@@ -0,0 +1,172 @@
+use frame_support::debug;
+use module_evm::{Context, ExitError, ExitSucceed, Precompile};
+use module_support::HonzonManager as HonzonManagerT;
+use primitives::evm::AddressMapping as AddressMappingT;
+use sp_core::{H160, U256};
+use sp_runtime::{DispatchError, FixedPointNumber};
+use sp_std::{fmt::Debug, marker::PhantomData, prelude::*, result};
+
+use super::{
+	ensure_not_static, ensure_storage_cost,
+	input::{Input, InputT},
+};
+use primitives::{Amount, Balance, CurrencyId, PRECOMPILE_ADDRESS_START};
+
+/// The `Honzon` impl precompile.
+///
+/// `input` data starts with `action` and `currency_id`. The loans are the loans
+/// of the caller. It can't be called by `DELEGATECALL` or `CALLCODE`, which
+/// would act on the loans of the caller of the calling contract, and the loans
+/// can't be changed in the static calls.
+///
+/// Actions:
+/// - Adjust loan. Rest `input` bytes: `collateral_adjustment`,
+///   `debit_adjustment`.
+/// - Transfer loan from. Rest `input` bytes: `from`.
+/// - Authorize. Rest `input` bytes: `to`.
+/// - Unauthorize. Rest `input` bytes: `to`.
+/// - Query position. Rest `input` bytes: `account_id`.
+/// - Query collateral ratio. Rest `input` bytes: `account_id`.
+pub struct HonzonPrecompile<AccountId, AddressMapping, Honzon>(PhantomData<(AccountId, AddressMapping, Honzon)>);
+
+enum Action {
+	AdjustLoan,
+	TransferLoanFrom,
+	Authorize,
+	Unauthorize,
+	QueryPosition,
+	QueryCollateralRatio,
+	Unknown,
+}
+
+impl From<u8> for Action {
+	fn from(a: u8) -> Self {
+		match a {
+			0 => Action::AdjustLoan,
+			1 => Action::TransferLoanFrom,
+			2 => Action::Authorize,
+			3 => Action::Unauthorize,
+			4 => Action::QueryPosition,
+			5 => Action::QueryCollateralRatio,
+			_ => Action::Unknown,
+		}
+	}
+}
+
+impl<AccountId, AddressMapping, Honzon> Precompile for HonzonPrecompile<AccountId, AddressMapping, Honzon>
+where
+	AccountId: Debug + Clone,
+	AddressMapping: AddressMappingT<AccountId>,
+	Honzon: HonzonManagerT<AccountId, CurrencyId, Amount, Balance>,
+{
+	fn execute(
+		input: &[u8],
+		target_gas: Option<usize>,
+		context: &Context,
+		is_static: bool,
+	) -> result::Result<(ExitSucceed, Vec<u8>, usize), ExitError> {
+		debug::debug!("input: {:?}", input);
+
+		if context.address != H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 3) {
+			return Err(ExitError::Other("delegatecall is not allowed".into()));
+		}
+
+		let input = Input::<Action, AccountId, AddressMapping>::new(input);
+
+		let action = input.action()?;
+		let currency_id = input.currency_id_at(1)?;
+
+		debug::debug!("currency id: {:?}", currency_id);
+
+		match action {
+			Action::AdjustLoan => {
+				ensure_not_static(is_static)?;
+				// reads: address mapping, the same as `module_honzon::adjust_loan`
+				// writes: the same as `module_honzon::adjust_loan`
+				let cost = ensure_storage_cost(target_gas, 25, 10)?;
+				let who = AddressMapping::to_account(&context.caller);
+				let collateral_adjustment = input.amount_at(2)?;
+				let debit_adjustment = input.amount_at(3)?;
+
+				debug::debug!("who: {:?}", who);
+				debug::debug!("collateral adjustment: {:?}", collateral_adjustment);
+				debug::debug!("debit adjustment: {:?}", debit_adjustment);
+
+				Honzon::adjust_loan(&who, currency_id, collateral_adjustment, debit_adjustment).map_err(exit_error)?;
+
+				Ok((ExitSucceed::Returned, vec![], cost))
+			}
+			Action::TransferLoanFrom => {
+				ensure_not_static(is_static)?;
+				// reads: 2 address mappings, the same as `module_honzon::transfer_loan_from`
+				// writes: the same as `module_honzon::transfer_loan_from`
+				let cost = ensure_storage_cost(target_gas, 23, 8)?;
+				let to = AddressMapping::to_account(&context.caller);
+				let from = input.account_id_at(2)?;
+
+				debug::debug!("from: {:?}", from);
+				debug::debug!("to: {:?}", to);
+
+				Honzon::transfer_loan_from(currency_id, &from, &to).map_err(exit_error)?;
+
+				Ok((ExitSucceed::Returned, vec![], cost))
+			}
+			Action::Authorize => {
+				ensure_not_static(is_static)?;
+				// reads: 2 address mappings
+				// writes: authorization, event
+				let cost = ensure_storage_cost(target_gas, 2, 2)?;
+				let from = AddressMapping::to_account(&context.caller);
+				let to = input.account_id_at(2)?;
+
+				Honzon::authorize(&from, currency_id, &to).map_err(exit_error)?;
+
+				Ok((ExitSucceed::Returned, vec![], cost))
+			}
+			Action::Unauthorize => {
+				ensure_not_static(is_static)?;
+				// reads: 2 address mappings
+				// writes: authorization, event
+				let cost = ensure_storage_cost(target_gas, 2, 2)?;
+				let from = AddressMapping::to_account(&context.caller);
+				let to = input.account_id_at(2)?;
+
+				Honzon::unauthorize(&from, currency_id, &to).map_err(exit_error)?;
+
+				Ok((ExitSucceed::Returned, vec![], cost))
+			}
+			Action::QueryPosition => {
+				// reads: address mapping, position
+				let cost = ensure_storage_cost(target_gas, 2, 0)?;
+				let who = input.account_id_at(2)?;
+				let (collateral, debit) = Honzon::get_position(&who, currency_id);
+
+				let mut output = vec_u8_from_u128(collateral);
+				output.extend(vec_u8_from_u128(debit));
+				Ok((ExitSucceed::Returned, output, cost))
+			}
+			Action::QueryCollateralRatio => {
+				// reads: address mapping, position, 2 prices, debit exchange rate
+				let cost = ensure_storage_cost(target_gas, 5, 0)?;
+				let who = input.account_id_at(2)?;
+				let ratio = Honzon::get_collateral_ratio(&who, currency_id)
+					.map(|ratio| ratio.into_inner())
+					.unwrap_or_default();
+
+				Ok((ExitSucceed::Returned, vec_u8_from_u128(ratio), cost))
+			}
+			Action::Unknown => Err(ExitError::Other("unknown action".into())),
+		}
+	}
+}
+
+fn exit_error(e: DispatchError) -> ExitError {
+	let err_msg: &str = e.into();
+	ExitError::Other(err_msg.into())
+}
+
+fn vec_u8_from_u128(value: u128) -> Vec<u8> {
+	let mut be_bytes = [0u8; 32];
+	U256::from(value).to_big_endian(&mut be_bytes[..]);
+	be_bytes.to_vec()
+}
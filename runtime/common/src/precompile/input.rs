@@ -3,6 +3,7 @@ use sp_std::{convert::TryInto, marker::PhantomData, mem, result::Result};
 
 use module_evm::ExitError;
 use primitives::{evm::AddressMapping as AddressMappingT, Amount, Balance, CurrencyId};
+use sp_core::H160;
 
 const PER_PARAM_BYTES: usize = 32;
 const ACTION_INDEX: usize = 0;
@@ -21,6 +22,7 @@ pub trait InputT {
 	fn action(&self) -> Result<Self::Action, Self::Error>;
 
 	fn account_id_at(&self, index: usize) -> Result<Self::AccountId, Self::Error>;
+	fn evm_address_at(&self, index: usize) -> Result<H160, Self::Error>;
 	fn currency_id_at(&self, index: usize) -> Result<CurrencyId, Self::Error>;
 
 	fn balance_at(&self, index: usize) -> Result<Balance, Self::Error>;
@@ -69,12 +71,18 @@ where
 	}
 
 	fn account_id_at(&self, index: usize) -> Result<Self::AccountId, Self::Error> {
+		let address = self.evm_address_at(index)?;
+
+		Ok(AddressMapping::to_account(&address))
+	}
+
+	fn evm_address_at(&self, index: usize) -> Result<H160, Self::Error> {
 		let param = self.nth_param(index)?;
 
 		let mut address = [0u8; 20];
 		address.copy_from_slice(&param[12..]);
 
-		Ok(AddressMapping::to_account(&address.into()))
+		Ok(address.into())
 	}
 
 	fn currency_id_at(&self, index: usize) -> Result<CurrencyId, Self::Error> {
@@ -172,6 +180,10 @@ mod tests {
 				None
 			}
 		}
+
+		fn get_or_create_evm_address(account_id: &AccountId) -> H160 {
+			Self::to_evm_address(account_id).unwrap_or_default()
+		}
 	}
 
 	pub type TestInput<'a> = Input<'a, Action, AccountId, EvmAddressMapping>;
@@ -211,6 +223,14 @@ mod tests {
 		assert_ok!(input.account_id_at(0), account_id);
 	}
 
+	#[test]
+	fn evm_address_works() {
+		let mut raw_input = [0u8; 32];
+		raw_input[31] = 1;
+		let input = TestInput::new(&raw_input[..]);
+		assert_ok!(input.evm_address_at(0), H160::from_low_u64_be(1));
+	}
+
 	#[test]
 	fn currency_id_works() {
 		let input = TestInput::new(&[0u8; 32][..]);
@@ -1,7 +1,16 @@
 //! The precompiles for EVM, includes standard Ethereum precompiles, and more:
 //! - MultiCurrency at address `H160::from_low_u64_be(1024)`.
+//! - NFT at address `H160::from_low_u64_be(1025)`.
+//! - Oracle at address `H160::from_low_u64_be(1026)`.
+//! - Honzon at address `H160::from_low_u64_be(1027)`.
+//! - ScheduleCall at address `H160::from_low_u64_be(1028)`.
+//! - ERC20 mirrors of the native tokens at `primitives::erc20_mirror_address`.
 
-use crate::is_system_contract;
+use crate::{is_system_contract, WEIGHT_PER_GAS};
+use frame_support::weights::{
+	constants::{RocksDbWeight, WEIGHT_PER_MICROS},
+	Weight,
+};
 use module_evm::{
 	precompiles::{Precompile, Precompiles},
 	Context, ExitError, ExitSucceed,
@@ -9,14 +18,23 @@ use module_evm::{
 use module_support::PrecompileCallerFilter as PrecompileCallerFilterT;
 use primitives::PRECOMPILE_ADDRESS_START;
 use sp_core::H160;
+use sp_runtime::SaturatedConversion;
 use sp_std::{marker::PhantomData, prelude::*};
 
+pub mod erc20_mirror;
+pub mod honzon;
 pub mod input;
 pub mod multicurrency;
 pub mod nft;
+pub mod oracle;
+pub mod schedule_call;
 
+pub use erc20_mirror::{Erc20MirrorPrecompile, InstallErc20MirrorCode, ERC20_MIRROR_CODE};
+pub use honzon::HonzonPrecompile;
 pub use multicurrency::MultiCurrencyPrecompile;
 pub use nft::NFTPrecompile;
+pub use oracle::OraclePrecompile;
+pub use schedule_call::ScheduleCallPrecompile;
 
 pub type EthereumPrecompiles = (
 	module_evm::precompiles::ECRecover,
@@ -25,15 +43,100 @@ pub type EthereumPrecompiles = (
 	module_evm::precompiles::Identity,
 );
 
-pub struct AllPrecompiles<PrecompileCallerFilter, MultiCurrencyPrecompile, NFTPrecompile>(
-	PhantomData<(PrecompileCallerFilter, MultiCurrencyPrecompile, NFTPrecompile)>,
+/// The gas of `weight`, the inverse of `GasToWeight`.
+fn weight_to_gas(weight: Weight) -> usize {
+	(weight / WEIGHT_PER_GAS).saturated_into()
+}
+
+/// The base gas cost of calling a precompile, the weight of decoding the input
+/// and encoding the output, which takes less than a microsecond.
+pub fn precompile_base_gas() -> usize {
+	weight_to_gas(WEIGHT_PER_MICROS)
+}
+
+/// The gas cost of a storage read in the precompiles, the weight of a
+/// `RocksDbWeight` read.
+pub fn storage_read_gas() -> usize {
+	weight_to_gas(RocksDbWeight::get().reads(1))
+}
+
+/// The gas cost of a storage write in the precompiles, the weight of a
+/// `RocksDbWeight` write.
+pub fn storage_write_gas() -> usize {
+	weight_to_gas(RocksDbWeight::get().writes(1))
+}
+
+/// The gas cost of a precompile action with `reads` storage reads and `writes`
+/// storage writes. Returns `OutOfGas` if the cost exceeds `target_gas`.
+pub fn ensure_storage_cost(target_gas: Option<usize>, reads: usize, writes: usize) -> Result<usize, ExitError> {
+	let cost = storage_read_gas()
+		.checked_mul(reads)
+		.and_then(|read_cost| read_cost.checked_add(storage_write_gas().checked_mul(writes)?))
+		.and_then(|storage_cost| storage_cost.checked_add(precompile_base_gas()))
+		.ok_or(ExitError::OutOfGas)?;
+
+	if let Some(target_gas) = target_gas {
+		if cost > target_gas {
+			return Err(ExitError::OutOfGas);
+		}
+	}
+
+	Ok(cost)
+}
+
+/// Ensure the precompile action changing the state isn't in a static call.
+pub fn ensure_not_static(is_static: bool) -> Result<(), ExitError> {
+	if is_static {
+		return Err(ExitError::Other("state change in static call".into()));
+	}
+	Ok(())
+}
+
+pub struct AllPrecompiles<
+	PrecompileCallerFilter,
+	MultiCurrencyPrecompile,
+	NFTPrecompile,
+	Erc20MirrorPrecompile,
+	OraclePrecompile,
+	HonzonPrecompile,
+	ScheduleCallPrecompile,
+>(
+	PhantomData<(
+		PrecompileCallerFilter,
+		MultiCurrencyPrecompile,
+		NFTPrecompile,
+		Erc20MirrorPrecompile,
+		OraclePrecompile,
+		HonzonPrecompile,
+		ScheduleCallPrecompile,
+	)>,
 );
 
-impl<PrecompileCallerFilter, MultiCurrencyPrecompile, NFTPrecompile> Precompiles
-	for AllPrecompiles<PrecompileCallerFilter, MultiCurrencyPrecompile, NFTPrecompile>
+impl<
+		PrecompileCallerFilter,
+		MultiCurrencyPrecompile,
+		NFTPrecompile,
+		Erc20MirrorPrecompile,
+		OraclePrecompile,
+		HonzonPrecompile,
+		ScheduleCallPrecompile,
+	> Precompiles
+	for AllPrecompiles<
+		PrecompileCallerFilter,
+		MultiCurrencyPrecompile,
+		NFTPrecompile,
+		Erc20MirrorPrecompile,
+		OraclePrecompile,
+		HonzonPrecompile,
+		ScheduleCallPrecompile,
+	>
 where
 	MultiCurrencyPrecompile: Precompile,
 	NFTPrecompile: Precompile,
+	Erc20MirrorPrecompile: Precompile,
+	OraclePrecompile: Precompile,
+	HonzonPrecompile: Precompile,
+	ScheduleCallPrecompile: Precompile,
 	PrecompileCallerFilter: PrecompileCallerFilterT,
 {
 	#[allow(clippy::type_complexity)]
@@ -42,16 +145,36 @@ where
 		input: &[u8],
 		target_gas: Option<usize>,
 		context: &Context,
+		is_static: bool,
 	) -> Option<core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError>> {
-		EthereumPrecompiles::execute(address, input, target_gas, context).or_else(|| {
+		EthereumPrecompiles::execute(address, input, target_gas, context, is_static).or_else(|| {
+			// the mirror contracts are public, any contract or account can call them
+			if primitives::erc20_mirror_token(address).is_some() {
+				return Some(Erc20MirrorPrecompile::execute(input, target_gas, context, is_static));
+			}
+
+			// the Honzon precompile only manipulates the loans of the caller, any contract
+			// or account can call it
+			if address == H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 3) {
+				return Some(HonzonPrecompile::execute(input, target_gas, context, is_static));
+			}
+
+			// the scheduled calls are owned and paid by the caller, any contract or account
+			// can schedule them
+			if address == H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 4) {
+				return Some(ScheduleCallPrecompile::execute(input, target_gas, context, is_static));
+			}
+
 			if is_system_contract(address) && !PrecompileCallerFilter::is_allowed(context.caller) {
 				return Some(Err(ExitError::Other("no permission".into())));
 			}
 
 			if address == H160::from_low_u64_be(PRECOMPILE_ADDRESS_START) {
-				Some(MultiCurrencyPrecompile::execute(input, target_gas, context))
+				Some(MultiCurrencyPrecompile::execute(input, target_gas, context, is_static))
 			} else if address == H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 1) {
-				Some(NFTPrecompile::execute(input, target_gas, context))
+				Some(NFTPrecompile::execute(input, target_gas, context, is_static))
+			} else if address == H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 2) {
+				Some(OraclePrecompile::execute(input, target_gas, context, is_static))
 			} else {
 				None
 			}
@@ -62,6 +185,7 @@ where
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use sp_runtime::traits::Convert;
 
 	pub struct DummyPrecompile;
 	impl Precompile for DummyPrecompile {
@@ -69,12 +193,44 @@ mod tests {
 			_input: &[u8],
 			_target_gas: Option<usize>,
 			_context: &Context,
+			_is_static: bool,
 		) -> core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError> {
 			Ok((ExitSucceed::Stopped, vec![], 0))
 		}
 	}
 
-	pub type WithSystemContractFilter = AllPrecompiles<crate::SystemContractsFilter, DummyPrecompile, DummyPrecompile>;
+	pub type WithSystemContractFilter = AllPrecompiles<
+		crate::SystemContractsFilter,
+		DummyPrecompile,
+		DummyPrecompile,
+		DummyPrecompile,
+		DummyPrecompile,
+		DummyPrecompile,
+		DummyPrecompile,
+	>;
+
+	#[test]
+	fn ensure_storage_cost_works() {
+		assert_eq!(ensure_storage_cost(None, 0, 0), Ok(precompile_base_gas()));
+		assert_eq!(
+			ensure_storage_cost(None, 2, 1),
+			Ok(precompile_base_gas() + 2 * storage_read_gas() + storage_write_gas())
+		);
+		// the storage accesses are charged by their weights
+		assert_eq!(
+			crate::GasToWeight::convert(storage_read_gas() as u32),
+			RocksDbWeight::get().reads(1)
+		);
+		assert_eq!(
+			crate::GasToWeight::convert(storage_write_gas() as u32),
+			RocksDbWeight::get().writes(1)
+		);
+
+		let cost = precompile_base_gas() + storage_read_gas();
+		assert_eq!(ensure_storage_cost(Some(cost), 1, 0), Ok(cost));
+		assert_eq!(ensure_storage_cost(Some(cost - 1), 1, 0), Err(ExitError::OutOfGas));
+		assert_eq!(ensure_storage_cost(None, usize::MAX, 0), Err(ExitError::OutOfGas));
+	}
 
 	#[test]
 	fn precompile_filter_works_on_system_contracts() {
@@ -89,11 +245,38 @@ mod tests {
 			apparent_value: 0.into(),
 		};
 		assert_eq!(
-			WithSystemContractFilter::execute(system, &[0u8; 1], None, &non_system_caller_context),
+			WithSystemContractFilter::execute(system, &[0u8; 1], None, &non_system_caller_context, false),
 			Some(Err(ExitError::Other("no permission".into()))),
 		);
 	}
 
+	#[test]
+	fn precompile_filter_does_not_work_on_erc20_mirrors() {
+		let mirror = primitives::erc20_mirror_address(primitives::TokenSymbol::AUSD);
+
+		let mut non_system = [0u8; 20];
+		non_system[0] = 1;
+
+		let non_system_caller_context = Context {
+			address: mirror,
+			caller: non_system.into(),
+			apparent_value: 0.into(),
+		};
+		assert_eq!(
+			WithSystemContractFilter::execute(mirror, &[0u8; 1], None, &non_system_caller_context, false),
+			Some(Ok((ExitSucceed::Stopped, vec![], 0))),
+		);
+	}
+
+	#[test]
+	fn ensure_not_static_works() {
+		assert_eq!(ensure_not_static(false), Ok(()));
+		assert_eq!(
+			ensure_not_static(true),
+			Err(ExitError::Other("state change in static call".into()))
+		);
+	}
+
 	#[test]
 	fn precompile_filter_does_not_work_on_non_system_contracts() {
 		let mut non_system = [0u8; 20];
@@ -106,8 +289,13 @@ mod tests {
 			caller: another_non_system.into(),
 			apparent_value: 0.into(),
 		};
-		assert!(
-			WithSystemContractFilter::execute(non_system.into(), &[0u8; 1], None, &non_system_caller_context).is_none()
-		);
+		assert!(WithSystemContractFilter::execute(
+			non_system.into(),
+			&[0u8; 1],
+			None,
+			&non_system_caller_context,
+			false
+		)
+		.is_none());
 	}
 }
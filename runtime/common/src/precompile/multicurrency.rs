@@ -6,7 +6,10 @@ use sp_std::{fmt::Debug, marker::PhantomData, prelude::*, result};
 
 use orml_traits::MultiCurrency as MultiCurrencyT;
 
-use super::input::{Input, InputT};
+use super::{
+	ensure_not_static, ensure_storage_cost,
+	input::{Input, InputT},
+};
 use primitives::{Balance, CurrencyId};
 
 /// The `MultiCurrency` impl precompile.
@@ -18,6 +21,8 @@ use primitives::{Balance, CurrencyId};
 /// - Query total issuance.
 /// - Query balance. Rest `input` bytes: `account_id`.
 /// - Transfer. Rest `input` bytes: `from`, `to`, `amount`.
+///
+/// The ERC20 currencies aren't supported, call the contracts instead.
 pub struct MultiCurrencyPrecompile<AccountId, AddressMapping, MultiCurrency>(
 	PhantomData<(AccountId, AddressMapping, MultiCurrency)>,
 );
@@ -49,11 +54,10 @@ where
 {
 	fn execute(
 		input: &[u8],
-		_target_gas: Option<usize>,
+		target_gas: Option<usize>,
 		_context: &Context,
+		is_static: bool,
 	) -> result::Result<(ExitSucceed, Vec<u8>, usize), ExitError> {
-		//TODO: evaluate cost
-
 		debug::debug!("input: {:?}", input);
 
 		let input = Input::<Action, AccountId, AddressMapping>::new(input);
@@ -63,23 +67,36 @@ where
 
 		debug::debug!("currency id: {:?}", currency_id);
 
+		// the ERC20 currencies run nested EVM calls, which aren't covered by the cost
+		if let CurrencyId::ERC20(_) = currency_id {
+			return Err(ExitError::Other("ERC20 currency is not supported".into()));
+		}
+
 		match action {
 			Action::QueryTotalIssuance => {
+				// reads: total issuance
+				let cost = ensure_storage_cost(target_gas, 1, 0)?;
 				let total_issuance = vec_u8_from_balance(MultiCurrency::total_issuance(currency_id))?;
 				debug::debug!("total issuance: {:?}", total_issuance);
 
-				Ok((ExitSucceed::Returned, total_issuance, 0))
+				Ok((ExitSucceed::Returned, total_issuance, cost))
 			}
 			Action::QueryBalance => {
+				// reads: address mapping, account
+				let cost = ensure_storage_cost(target_gas, 2, 0)?;
 				let who = input.account_id_at(2)?;
 				debug::debug!("who: {:?}", who);
 
 				let balance = vec_u8_from_balance(MultiCurrency::total_balance(currency_id, &who))?;
 				debug::debug!("balance: {:?}", balance);
 
-				Ok((ExitSucceed::Returned, balance, 0))
+				Ok((ExitSucceed::Returned, balance, cost))
 			}
 			Action::Transfer => {
+				ensure_not_static(is_static)?;
+				// reads: 2 address mappings, 2 accounts
+				// writes: 2 accounts
+				let cost = ensure_storage_cost(target_gas, 4, 2)?;
 				let from = input.account_id_at(2)?;
 				let to = input.account_id_at(3)?;
 				let amount = input.balance_at(4)?;
@@ -95,7 +112,7 @@ where
 
 				debug::debug!("transfer success!");
 
-				Ok((ExitSucceed::Returned, vec![], 0))
+				Ok((ExitSucceed::Returned, vec![], cost))
 			}
 			Action::Unknown => Err(ExitError::Other("unknown action".into())),
 		}
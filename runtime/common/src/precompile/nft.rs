@@ -4,7 +4,10 @@ use sp_std::{borrow::Cow, marker::PhantomData, prelude::*, result};
 
 use orml_traits::NFT as NFTT;
 
-use super::input::{Input, InputT};
+use super::{
+	ensure_not_static, ensure_storage_cost,
+	input::{Input, InputT},
+};
 use primitives::{evm::AddressMapping as AddressMappingT, NFTBalance};
 
 /// The `NFT` impl precompile.
@@ -43,8 +46,9 @@ where
 {
 	fn execute(
 		input: &[u8],
-		_target_gas: Option<usize>,
+		target_gas: Option<usize>,
 		_context: &Context,
+		is_static: bool,
 	) -> result::Result<(ExitSucceed, Vec<u8>, usize), ExitError> {
 		let input = Input::<Action, AccountId, AddressMapping>::new(input);
 
@@ -52,12 +56,16 @@ where
 
 		match action {
 			Action::QueryBalance => {
+				// reads: address mapping, tokens by owner
+				let cost = ensure_storage_cost(target_gas, 2, 0)?;
 				let who = input.account_id_at(1)?;
 				let balance = vec_u8_from_balance(NFT::balance(&who));
 
-				Ok((ExitSucceed::Returned, balance, 0))
+				Ok((ExitSucceed::Returned, balance, cost))
 			}
 			Action::QueryOwner => {
+				// reads: token, address mapping
+				let cost = ensure_storage_cost(target_gas, 2, 0)?;
 				let class_id = input.u32_at(1)?;
 				let token_id = input.u64_at(2)?;
 
@@ -67,9 +75,13 @@ where
 					Default::default()
 				};
 
-				Ok((ExitSucceed::Returned, owner.as_bytes().to_vec(), 0))
+				Ok((ExitSucceed::Returned, owner.as_bytes().to_vec(), cost))
 			}
 			Action::Transfer => {
+				ensure_not_static(is_static)?;
+				// reads: 2 address mappings, the same as `module_nft::transfer`
+				// writes: the same as `module_nft::transfer`
+				let cost = ensure_storage_cost(target_gas, 5, 3)?;
 				let from = input.account_id_at(1)?;
 				let to = input.account_id_at(2)?;
 
@@ -79,7 +91,7 @@ where
 				NFT::transfer(&from, &to, (class_id, token_id))
 					.map_err(|e| ExitError::Other(Cow::Borrowed(e.into())))?;
 
-				Ok((ExitSucceed::Returned, vec![], 0))
+				Ok((ExitSucceed::Returned, vec![], cost))
 			}
 			Action::Unknown => Err(ExitError::Other("unknown action".into())),
 		}
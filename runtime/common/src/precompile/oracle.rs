@@ -0,0 +1,121 @@
+use frame_support::debug;
+use module_evm::{Context, ExitError, ExitSucceed, Precompile};
+use module_support::PriceProvider as PriceProviderT;
+use primitives::evm::AddressMapping as AddressMappingT;
+use sp_core::U256;
+use sp_runtime::FixedPointNumber;
+use sp_std::{fmt::Debug, marker::PhantomData, prelude::*, result};
+
+use orml_traits::DataProviderExtended as DataProviderT;
+
+use super::{
+	ensure_storage_cost,
+	input::{Input, InputT},
+};
+use crate::{Price, TimeStampedPrice};
+use primitives::{CurrencyId, Moment};
+
+/// The `Oracle` impl precompile.
+///
+/// `input` data starts with `action`.
+///
+/// Actions:
+/// - Get price. Rest `input` bytes: `currency_id`.
+/// - Get relative price. Rest `input` bytes: `base_currency_id`,
+///   `quote_currency_id`.
+///
+/// The output is the price with 18 decimals and the timestamp of the oracle
+/// value, the price is zero if it's not available, the timestamp is zero if the
+/// price doesn't come from the oracle.
+pub struct OraclePrecompile<AccountId, AddressMapping, PriceProvider, DataProvider>(
+	PhantomData<(AccountId, AddressMapping, PriceProvider, DataProvider)>,
+);
+
+enum Action {
+	GetPrice,
+	GetRelativePrice,
+	Unknown,
+}
+
+impl From<u8> for Action {
+	fn from(a: u8) -> Self {
+		match a {
+			0 => Action::GetPrice,
+			1 => Action::GetRelativePrice,
+			_ => Action::Unknown,
+		}
+	}
+}
+
+impl<AccountId, AddressMapping, PriceProvider, DataProvider> Precompile
+	for OraclePrecompile<AccountId, AddressMapping, PriceProvider, DataProvider>
+where
+	AccountId: Debug + Clone,
+	AddressMapping: AddressMappingT<AccountId>,
+	PriceProvider: PriceProviderT<CurrencyId>,
+	DataProvider: DataProviderT<CurrencyId, TimeStampedPrice>,
+{
+	fn execute(
+		input: &[u8],
+		target_gas: Option<usize>,
+		_context: &Context,
+		_is_static: bool,
+	) -> result::Result<(ExitSucceed, Vec<u8>, usize), ExitError> {
+		debug::debug!("input: {:?}", input);
+
+		let input = Input::<Action, AccountId, AddressMapping>::new(input);
+
+		let action = input.action()?;
+
+		match action {
+			Action::GetPrice => {
+				// reads: locked price, oracle values, timestamped value
+				let cost = ensure_storage_cost(target_gas, 3, 0)?;
+				let currency_id = input.currency_id_at(1)?;
+				debug::debug!("currency id: {:?}", currency_id);
+
+				let price = PriceProvider::get_price(currency_id);
+				let timestamp = Self::timestamp(currency_id);
+
+				Ok((ExitSucceed::Returned, encode_price(price, timestamp), cost))
+			}
+			Action::GetRelativePrice => {
+				// reads: 2 * (locked price, oracle values, timestamped value)
+				let cost = ensure_storage_cost(target_gas, 6, 0)?;
+				let base_currency_id = input.currency_id_at(1)?;
+				let quote_currency_id = input.currency_id_at(2)?;
+				debug::debug!("base: {:?}, quote: {:?}", base_currency_id, quote_currency_id);
+
+				let price = PriceProvider::get_relative_price(base_currency_id, quote_currency_id);
+				// the older timestamp of the oracle values
+				let timestamp = match (Self::timestamp(base_currency_id), Self::timestamp(quote_currency_id)) {
+					(0, timestamp) | (timestamp, 0) => timestamp,
+					(base_timestamp, quote_timestamp) => base_timestamp.min(quote_timestamp),
+				};
+
+				Ok((ExitSucceed::Returned, encode_price(price, timestamp), cost))
+			}
+			Action::Unknown => Err(ExitError::Other("unknown action".into())),
+		}
+	}
+}
+
+impl<AccountId, AddressMapping, PriceProvider, DataProvider>
+	OraclePrecompile<AccountId, AddressMapping, PriceProvider, DataProvider>
+where
+	DataProvider: DataProviderT<CurrencyId, TimeStampedPrice>,
+{
+	fn timestamp(currency_id: CurrencyId) -> Moment {
+		DataProvider::get_no_op(&currency_id)
+			.map(|value| value.timestamp)
+			.unwrap_or_default()
+	}
+}
+
+fn encode_price(price: Option<Price>, timestamp: Moment) -> Vec<u8> {
+	let mut be_bytes = [0u8; 64];
+	let price = price.map(|price| price.into_inner()).unwrap_or_default();
+	U256::from(price).to_big_endian(&mut be_bytes[..32]);
+	U256::from(timestamp).to_big_endian(&mut be_bytes[32..]);
+	be_bytes.to_vec()
+}
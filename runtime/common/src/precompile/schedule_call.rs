@@ -0,0 +1,152 @@
+use codec::Encode;
+use frame_support::{
+	debug,
+	traits::schedule::{DispatchTime, Named as ScheduleNamed},
+};
+use module_evm::{BalanceOf, Context, ExitError, ExitSucceed, Precompile};
+use primitives::{Balance, PRECOMPILE_ADDRESS_START};
+use sp_core::{H160, U256};
+use sp_std::{marker::PhantomData, prelude::*, result};
+
+use super::{
+	ensure_not_static, ensure_storage_cost,
+	input::{Input, InputT},
+};
+
+/// The raw `input` of the scheduled call starts after `action`, `target`,
+/// `value`, `gas_limit` and `delay`.
+const CALL_INPUT_OFFSET: usize = 5 * 32;
+
+/// The id prefix of the scheduler tasks of the scheduled EVM calls.
+const SCHEDULED_CALL_PREFIX: &[u8] = b"EVM_SCHEDULED_CALL";
+
+/// The `ScheduleCall` impl precompile.
+///
+/// `input` data starts with `action`. The scheduled calls are owned by the
+/// caller, the fee of `gas_limit` is reserved from the caller when scheduling,
+/// and refunded on cancel. It can't be called by `DELEGATECALL`, `CALLCODE` or
+/// in the static calls.
+///
+/// Actions:
+/// - Schedule call. Rest `input` bytes: `target`, `value`, `gas_limit`,
+///   `delay` in blocks, followed by the raw `input` of the call. Returns the
+///   schedule id.
+/// - Cancel call. Rest `input` bytes: `schedule_id`.
+pub struct ScheduleCallPrecompile<Runtime, Scheduler, Call, PalletsOrigin>(
+	PhantomData<(Runtime, Scheduler, Call, PalletsOrigin)>,
+);
+
+enum Action {
+	Schedule,
+	Cancel,
+	Unknown,
+}
+
+impl From<u8> for Action {
+	fn from(a: u8) -> Self {
+		match a {
+			0 => Action::Schedule,
+			1 => Action::Cancel,
+			_ => Action::Unknown,
+		}
+	}
+}
+
+impl<Runtime, Scheduler, Call, PalletsOrigin> Precompile
+	for ScheduleCallPrecompile<Runtime, Scheduler, Call, PalletsOrigin>
+where
+	Runtime: module_evm::Config,
+	BalanceOf<Runtime>: From<Balance>,
+	Call: From<module_evm::Call<Runtime>>,
+	PalletsOrigin: From<frame_system::RawOrigin<Runtime::AccountId>>,
+	Scheduler: ScheduleNamed<Runtime::BlockNumber, Call, PalletsOrigin>,
+{
+	fn execute(
+		input: &[u8],
+		target_gas: Option<usize>,
+		context: &Context,
+		is_static: bool,
+	) -> result::Result<(ExitSucceed, Vec<u8>, usize), ExitError> {
+		debug::debug!("input: {:?}", input);
+
+		if context.address != H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 4) {
+			return Err(ExitError::Other("delegatecall is not allowed".into()));
+		}
+
+		let raw_input = input;
+		let input = Input::<Action, Runtime::AccountId, Runtime::AddressMapping>::new(input);
+
+		let action = input.action()?;
+
+		match action {
+			Action::Schedule => {
+				ensure_not_static(is_static)?;
+				// reads: address mapping, gas price, next schedule id, scheduler lookup, agenda
+				// writes: account, next schedule id, scheduled call, event, scheduler lookup, agenda
+				let cost = ensure_storage_cost(target_gas, 5, 6)?;
+				let target = input.evm_address_at(1)?;
+				let value = input.balance_at(2)?;
+				let gas_limit = input.u32_at(3)?;
+				let delay = input.u32_at(4)?;
+				let call_input = raw_input.get(CALL_INPUT_OFFSET..).unwrap_or_default().to_vec();
+
+				debug::debug!("target: {:?}, value: {:?}", target, value);
+				debug::debug!("gas limit: {:?}, delay: {:?}", gas_limit, delay);
+
+				let schedule_id = module_evm::Module::<Runtime>::reserve_scheduled_call(
+					context.caller,
+					target,
+					call_input,
+					value.into(),
+					gas_limit,
+				)
+				.map_err(exit_error)?;
+				let call = module_evm::Call::<Runtime>::scheduled_call(schedule_id, gas_limit);
+				Scheduler::schedule_named(
+					task_id(schedule_id),
+					DispatchTime::After(delay.into()),
+					None,
+					0,
+					frame_system::RawOrigin::Root.into(),
+					call.into(),
+				)
+				.map_err(|_| ExitError::Other("schedule failed".into()))?;
+
+				Ok((ExitSucceed::Returned, vec_u8_from_u64(schedule_id), cost))
+			}
+			Action::Cancel => {
+				ensure_not_static(is_static)?;
+				// reads: address mapping, scheduled call, scheduler lookup
+				// writes: account, scheduled call, event, scheduler lookup, agenda
+				let cost = ensure_storage_cost(target_gas, 3, 5)?;
+				let schedule_id = input.u64_at(1)?;
+
+				debug::debug!("schedule id: {:?}", schedule_id);
+
+				module_evm::Module::<Runtime>::cancel_scheduled_call(context.caller, schedule_id)
+					.map_err(exit_error)?;
+				Scheduler::cancel_named(task_id(schedule_id))
+					.map_err(|_| ExitError::Other("cancel schedule failed".into()))?;
+
+				Ok((ExitSucceed::Returned, vec![], cost))
+			}
+			Action::Unknown => Err(ExitError::Other("unknown action".into())),
+		}
+	}
+}
+
+/// The scheduler task id of the scheduled call `schedule_id`.
+fn task_id(schedule_id: u64) -> Vec<u8> {
+	(SCHEDULED_CALL_PREFIX, schedule_id).encode()
+}
+
+fn exit_error(e: sp_runtime::DispatchError) -> ExitError {
+	let err_msg: &str = e.into();
+	ExitError::Other(err_msg.into())
+}
+
+fn vec_u8_from_u64(value: u64) -> Vec<u8> {
+	let mut be_bytes = [0u8; 32];
+	U256::from(value).to_big_endian(&mut be_bytes[..]);
+	be_bytes.to_vec()
+}
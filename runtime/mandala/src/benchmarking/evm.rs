@@ -1,10 +1,16 @@
-use crate::{AccountId, Balance, Event, EvmAccounts, Origin, Runtime, System, DOLLARS, EVM};
+use crate::{
+	AccountId, Balance, Balances, CodeUpgradeDelay, Event, EvmAccounts, MaxRentCollections, Origin,
+	RentCollectionPeriod, RentGracePeriod, Runtime, System, DOLLARS, EVM,
+};
 
 use super::utils::set_aca_balance;
-use frame_support::dispatch::DispatchError;
+use frame_support::{
+	dispatch::DispatchError,
+	traits::{Currency, Get, OnInitialize},
+};
 use frame_system::RawOrigin;
 use orml_benchmarking::runtime_benchmarks;
-use sp_core::H160;
+use sp_core::{H160, H256};
 use sp_io::hashing::keccak_256;
 use sp_std::{prelude::*, vec};
 
@@ -113,6 +119,111 @@ runtime_benchmarks! {
 		EVM::request_transfer_maintainer(Origin::signed(bob_account_id()), address)?;
 		let new_maintainer = EvmAccounts::eth_address(&bob());
 	}: _(RawOrigin::Signed(alice_account_id()), address, new_maintainer)
+
+	// evict the contract with `s` storage items
+	collect_rent {
+		let s in 0 .. 100;
+
+		set_aca_balance(&alice_account_id(), dollar(1000));
+		set_aca_balance(&bob_account_id(), dollar(1000));
+		let address = deploy_contract(alice_account_id())?;
+
+		for i in 0..s {
+			module_evm::AccountStorages::insert(address, H256::from_low_u64_be(i as u64), H256::repeat_byte(1));
+		}
+		module_evm::Accounts::<Runtime>::mutate(address, |maybe_account_info| {
+			if let Some(account_info) = maybe_account_info.as_mut() {
+				// no additional storage of the maintainer to release
+				account_info.storage_quota = u32::max_value();
+				if let Some(contract_info) = account_info.contract_info.as_mut() {
+					contract_info.storage_count = s;
+				}
+			}
+		});
+		module_evm::ContractRents::<Runtime>::insert(address, module_evm::RentInfo {
+			paid_until: 1,
+			overdue_since: Some(1),
+			next_collection: 1 + RentCollectionPeriod::get(),
+		});
+		// the maintainer can't pay the rent
+		Balances::make_free_balance_be(&alice_account_id(), 0);
+		System::set_block_number(1 + RentGracePeriod::get());
+	}: _(RawOrigin::Signed(bob_account_id()), address, s)
+	verify {
+		assert!(module_evm::Tombstones::<Runtime>::contains_key(address));
+	}
+
+	// collect the rent of `c` contracts
+	on_initialize {
+		let c in 0 .. MaxRentCollections::get();
+
+		set_aca_balance(&alice_account_id(), dollar(1000));
+		for _ in 0 .. c {
+			deploy_contract(alice_account_id())?;
+		}
+	}: {
+		EVM::on_initialize(1 + RentCollectionPeriod::get());
+	}
+
+	restore_contract {
+		let s in 0 .. 100;
+
+		set_aca_balance(&alice_account_id(), dollar(1000));
+		set_aca_balance(&bob_account_id(), dollar(1000));
+		let address = deploy_contract(alice_account_id())?;
+
+		let code = EVM::code_at_address(&address);
+		let storage: Vec<(H256, H256)> = (0..s)
+			.map(|i| (H256::from_low_u64_be(i as u64), H256::repeat_byte(1)))
+			.collect();
+		let maintainer = EvmAccounts::eth_address(&alice());
+		EVM::remove_account(&address).map_err(|_| "remove_account failed")?;
+		module_evm::Tombstones::<Runtime>::insert(address, module_evm::Tombstone {
+			nonce: 1,
+			maintainer,
+			code_hash: module_evm::code_hash(&code),
+			storage_hash: module_evm::storage_hash(&storage),
+			unpaid_rent: dollar(1),
+		});
+	}: _(RawOrigin::Signed(bob_account_id()), address, code, storage)
+
+	set_code {
+		let c in 0 .. module_evm::MAX_CODE_SIZE;
+
+		set_aca_balance(&alice_account_id(), dollar(1000));
+		let address = deploy_contract(alice_account_id())?;
+	}: _(RawOrigin::Signed(alice_account_id()), address, vec![0x00; c as usize])
+
+	// apply the code of the max size
+	apply_code {
+		set_aca_balance(&alice_account_id(), dollar(1000));
+		set_aca_balance(&bob_account_id(), dollar(1000));
+		let address = deploy_contract(alice_account_id())?;
+
+		EVM::set_code(Origin::signed(alice_account_id()), address, vec![0x00; module_evm::MAX_CODE_SIZE as usize])?;
+		System::set_block_number(1 + CodeUpgradeDelay::get());
+	}: _(RawOrigin::Signed(bob_account_id()), address)
+
+	add_deployer {
+		let address = EvmAccounts::eth_address(&alice());
+	}: _(RawOrigin::Root, address)
+
+	remove_deployer {
+		let address = EvmAccounts::eth_address(&alice());
+		EVM::add_deployer(RawOrigin::Root.into(), address)?;
+	}: _(RawOrigin::Root, address)
+
+	set_open_deployment {
+	}: _(RawOrigin::Root, false)
+
+	enable_developer_mode {
+		set_aca_balance(&alice_account_id(), dollar(1000));
+	}: _(RawOrigin::Signed(alice_account_id()))
+
+	disable_developer_mode {
+		set_aca_balance(&alice_account_id(), dollar(1000));
+		EVM::enable_developer_mode(Origin::signed(alice_account_id()))?;
+	}: _(RawOrigin::Signed(alice_account_id()))
 }
 
 #[cfg(test)]
@@ -171,4 +282,74 @@ mod tests {
 			assert_ok!(test_benchmark_reject_transfer_maintainer());
 		});
 	}
+
+	#[test]
+	fn test_collect_rent() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_collect_rent());
+		});
+	}
+
+	#[test]
+	fn test_on_initialize() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_on_initialize());
+		});
+	}
+
+	#[test]
+	fn test_restore_contract() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_restore_contract());
+		});
+	}
+
+	#[test]
+	fn test_set_code() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_code());
+		});
+	}
+
+	#[test]
+	fn test_apply_code() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_apply_code());
+		});
+	}
+
+	#[test]
+	fn test_add_deployer() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_add_deployer());
+		});
+	}
+
+	#[test]
+	fn test_remove_deployer() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_remove_deployer());
+		});
+	}
+
+	#[test]
+	fn test_set_open_deployment() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_open_deployment());
+		});
+	}
+
+	#[test]
+	fn test_enable_developer_mode() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_enable_developer_mode());
+		});
+	}
+
+	#[test]
+	fn test_disable_developer_mode() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_disable_developer_mode());
+		});
+	}
 }
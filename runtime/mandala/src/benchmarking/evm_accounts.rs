@@ -1,8 +1,9 @@
-use crate::{AccountId, Balance, EvmAccounts, Runtime, DOLLARS};
+use crate::{AccountId, Balance, EvmAccounts, EvmMappingCooldown, Origin, Runtime, System, DOLLARS};
 
 use super::utils::set_aca_balance;
 use codec::Encode;
 use frame_benchmarking::account;
+use frame_support::traits::Get;
 use frame_system::RawOrigin;
 use orml_benchmarking::runtime_benchmarks;
 use sp_io::hashing::keccak_256;
@@ -41,6 +42,23 @@ runtime_benchmarks! {
 		let eth: AccountId = account("eth", 0, SEED);
 		set_aca_balance(&bob_account_id(), dollar(1000));
 	}: _(RawOrigin::Signed(caller), EvmAccounts::eth_address(&alice()), EvmAccounts::eth_sign(&alice(), &caller.encode(), &[][..]))
+
+	claim_default_account {
+		let caller: AccountId = account("caller", 0, SEED);
+	}: _(RawOrigin::Signed(caller))
+
+	unbind_account {
+		let caller: AccountId = account("caller", 0, SEED);
+		EvmAccounts::claim_account(Origin::signed(caller.clone()), EvmAccounts::eth_address(&alice()), EvmAccounts::eth_sign(&alice(), &caller.encode(), &[][..]))?;
+		System::set_block_number(EvmMappingCooldown::get());
+	}: _(RawOrigin::Signed(caller))
+
+	rebind_account {
+		let caller: AccountId = account("caller", 0, SEED);
+		EvmAccounts::claim_account(Origin::signed(caller.clone()), EvmAccounts::eth_address(&alice()), EvmAccounts::eth_sign(&alice(), &caller.encode(), &[][..]))?;
+		System::set_block_number(EvmMappingCooldown::get());
+		set_aca_balance(&bob_account_id(), dollar(1000));
+	}: _(RawOrigin::Signed(caller), EvmAccounts::eth_address(&bob()), EvmAccounts::eth_sign(&bob(), &caller.encode(), &[][..]))
 }
 
 #[cfg(test)]
@@ -61,4 +79,25 @@ mod tests {
 			assert_ok!(test_benchmark_claim_account());
 		});
 	}
+
+	#[test]
+	fn test_claim_default_account() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_claim_default_account());
+		});
+	}
+
+	#[test]
+	fn test_unbind_account() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_unbind_account());
+		});
+	}
+
+	#[test]
+	fn test_rebind_account() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_rebind_account());
+		});
+	}
 }
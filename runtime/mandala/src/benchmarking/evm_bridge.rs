@@ -0,0 +1,50 @@
+use crate::{EVMBridge, Runtime};
+
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use sp_core::H160;
+use sp_std::prelude::*;
+
+fn erc20_address() -> H160 {
+	H160::from_low_u64_be(1)
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_evm_bridge }
+
+	_ {}
+
+	register_erc20 {
+	}: _(RawOrigin::Root, erc20_address())
+
+	deregister_erc20 {
+		EVMBridge::register_erc20(RawOrigin::Root.into(), erc20_address())?;
+	}: _(RawOrigin::Root, erc20_address())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::assert_ok;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap()
+			.into()
+	}
+
+	#[test]
+	fn test_register_erc20() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_register_erc20());
+		});
+	}
+
+	#[test]
+	fn test_deregister_erc20() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_deregister_erc20());
+		});
+	}
+}
@@ -1,6 +1,6 @@
 use crate::{
-	AccountId, Balance, CollateralCurrencyIds, CurrencyId, GetStableCurrencyId, Incentives, Rate, Rewards, Runtime,
-	TokenSymbol, DOLLARS,
+	AccountId, Balance, CollateralCurrencyIds, CurrencyId, DexShare, GetStableCurrencyId, Incentives, Rate, Rewards,
+	Runtime, TokenSymbol, DOLLARS,
 };
 
 use super::utils::set_balance;
@@ -12,7 +12,8 @@ use orml_benchmarking::runtime_benchmarks;
 use sp_std::prelude::*;
 
 const SEED: u32 = 0;
-const BTC_AUSD_LP: CurrencyId = CurrencyId::DEXShare(TokenSymbol::XBTC, TokenSymbol::AUSD);
+const BTC_AUSD_LP: CurrencyId =
+	CurrencyId::DEXShare(DexShare::Token(TokenSymbol::XBTC), DexShare::Token(TokenSymbol::AUSD));
 
 fn dollar(d: u32) -> Balance {
 	let d: Balance = d.into();
@@ -69,11 +70,9 @@ runtime_benchmarks! {
 
 		for i in 0 .. c {
 			let currency_id = currency_ids[i as usize];
-			let lp_share_currency_id = match (currency_id, base_currency_id) {
-				(CurrencyId::Token(other_currency_symbol), CurrencyId::Token(base_currency_symbol)) => {
-					CurrencyId::DEXShare(other_currency_symbol, base_currency_symbol)
-				}
-				_ => return Err("invalid currency id"),
+			let lp_share_currency_id = match CurrencyId::join_dex_share_currency_id(currency_id, base_currency_id) {
+				Some(lp_share_currency_id) => lp_share_currency_id,
+				None => return Err("invalid currency id"),
 			};
 			values.push((lp_share_currency_id, dollar(100)));
 		}
@@ -91,11 +90,9 @@ runtime_benchmarks! {
 
 		for i in 0 .. c {
 			let currency_id = currency_ids[i as usize];
-			let lp_share_currency_id = match (currency_id, base_currency_id) {
-				(CurrencyId::Token(other_currency_symbol), CurrencyId::Token(base_currency_symbol)) => {
-					CurrencyId::DEXShare(other_currency_symbol, base_currency_symbol)
-				}
-				_ => return Err("invalid currency id"),
+			let lp_share_currency_id = match CurrencyId::join_dex_share_currency_id(currency_id, base_currency_id) {
+				Some(lp_share_currency_id) => lp_share_currency_id,
+				None => return Err("invalid currency id"),
 			};
 			values.push((lp_share_currency_id, Rate::default()));
 		}
@@ -0,0 +1,54 @@
+use crate::{AccountId, GetLiquidCurrencyId, MaxElectionVoters, NominateesCount, NomineesElection, Runtime, DOLLARS};
+
+use super::utils::set_balance;
+use frame_benchmarking::account;
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+runtime_benchmarks! {
+	{ Runtime, module_nominees_election }
+
+	_ {}
+
+	rebalance_by_phragmen {
+		let v in 1 .. MaxElectionVoters::get();
+
+		for i in 0 .. v {
+			let voter: AccountId = account("voter", i, SEED);
+			let targets = (0 .. NominateesCount::get() as u32)
+				.map(|j| account("nominee", (i + j) % (v + NominateesCount::get() as u32), SEED))
+				.collect::<Vec<AccountId>>();
+
+			// the vote weight of the bonded must be above the threshold
+			let amount = 100 * DOLLARS * (i as u128 + 1);
+			set_balance(GetLiquidCurrencyId::get(), &voter, amount);
+			NomineesElection::bond(RawOrigin::Signed(voter.clone()).into(), amount)?;
+			NomineesElection::nominate(RawOrigin::Signed(voter).into(), targets)?;
+		}
+	}: {
+		NomineesElection::rebalance_by_phragmen();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::assert_ok;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap()
+			.into()
+	}
+
+	#[test]
+	fn test_rebalance_by_phragmen() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_rebalance_by_phragmen());
+		});
+	}
+}
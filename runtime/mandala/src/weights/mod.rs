@@ -8,8 +8,10 @@ pub mod dex;
 pub mod emergency_shutdown;
 pub mod evm;
 pub mod evm_accounts;
+pub mod evm_bridge;
 pub mod honzon;
 pub mod incentives;
 pub mod nft;
+pub mod nominees_election;
 pub mod prices;
 pub mod transaction_payment;
@@ -3,25 +3,35 @@
 use codec::Encode;
 use frame_support::{
 	assert_noop, assert_ok,
-	traits::{schedule::DispatchTime, Currency, OnFinalize, OnInitialize, OriginTrait},
+	traits::{schedule::DispatchTime, Currency, OnFinalize, OnInitialize, OnRuntimeUpgrade, OriginTrait},
+	unsigned::ValidateUnsigned,
+	weights::Weight,
 };
 use frame_system::RawOrigin;
 use mandala_runtime::{
-	get_all_module_accounts, AccountId, AuthoritysOriginId, Balance, Balances, BlockNumber, Call, CreateClassDeposit,
-	CreateTokenDeposit, CurrencyId, DSWFModuleId, EnabledTradingPairs, Event, EvmAccounts, GetNativeCurrencyId,
-	NativeTokenExistentialDeposit, NftModuleId, Origin, OriginCaller, Perbill, Proxy, Runtime, SevenDays, System,
-	TokenSymbol, EVM, NFT,
+	get_all_module_accounts, AccountId, AuthoritysOriginId, Balance, Balances, BlockNumber, Call, ChainId,
+	CreateClassDeposit, CreateTokenDeposit, CurrencyId, DSWFModuleId, DexShare, EVMBridge, EnabledTradingPairs, Event,
+	EvmAccounts, EvmMappingCooldown, GetNativeCurrencyId, NativeTokenExistentialDeposit, NftModuleId, Origin,
+	OriginCaller, Perbill, Proxy, Runtime, SevenDays, System, Timestamp, TokenSymbol, EVM, NFT,
 };
 use module_cdp_engine::LiquidationStrategy;
+use module_evm::{Context, EthereumTransaction, ExitError, ExitReason, ExitSucceed, Precompiles, TransactionAction};
 use module_support::{CDPTreasury, DEXManager, Price, Rate, Ratio, RiskManager};
 use orml_authority::DelayedOrigin;
 use orml_traits::{Change, MultiCurrency};
-use sp_core::H160;
+use primitives::PRECOMPILE_ADDRESS_START;
+use runtime_common::{
+	precompile::{precompile_base_gas, storage_read_gas, storage_write_gas},
+	InstallErc20MirrorCode, ERC20_MIRROR_CODE,
+};
+use sp_core::{H160, H256, U256};
 use sp_io::hashing::keccak_256;
 use sp_runtime::{
 	traits::{AccountIdConversion, BadOrigin},
+	transaction_validity::{InvalidTransaction, TransactionSource},
 	DispatchError, DispatchResult, FixedPointNumber,
 };
+use std::convert::TryFrom;
 
 const ORACLE1: [u8; 32] = [0u8; 32];
 const ORACLE2: [u8; 32] = [1u8; 32];
@@ -202,6 +212,26 @@ fn deploy_contract(account: AccountId) -> Result<H160, DispatchError> {
 	}
 }
 
+/// Deploy a contract forwarding its calls to `target` by `DELEGATECALL`, which
+/// reverts if the delegate call fails.
+fn deploy_delegate_call_contract(account: AccountId, target: H160) -> Result<H160, DispatchError> {
+	// init code: copy the runtime code to the memory and return it
+	let mut contract = hex_literal::hex!("602e80600b6000396000f3").to_vec();
+	// runtime code: copy the calldata to the memory and push the params of `DELEGATECALL`
+	contract.extend_from_slice(&hex_literal::hex!("3660006000376000600036600073"));
+	contract.extend_from_slice(target.as_bytes());
+	// `DELEGATECALL` with all the gas, revert if it fails
+	contract.extend_from_slice(&hex_literal::hex!("5af4602c5760006000fd5b00"));
+
+	EVM::create(Origin::signed(account), contract, 0, 1000000000).map_or_else(|e| Err(e.error), |_| Ok(()))?;
+
+	if let Event::module_evm(module_evm::RawEvent::Created(address)) = System::events().iter().last().unwrap().event {
+		Ok(address)
+	} else {
+		Err("deploy_delegate_call_contract failed".into())
+	}
+}
+
 #[test]
 fn emergency_shutdown_and_cdp_treasury() {
 	ExtBuilder::default()
@@ -482,12 +512,15 @@ fn test_dex_module() {
 				(0, 0)
 			);
 			assert_eq!(
-				Currencies::total_issuance(CurrencyId::DEXShare(TokenSymbol::AUSD, TokenSymbol::XBTC)),
+				Currencies::total_issuance(CurrencyId::DEXShare(
+					DexShare::Token(TokenSymbol::AUSD),
+					DexShare::Token(TokenSymbol::XBTC)
+				)),
 				0
 			);
 			assert_eq!(
 				Currencies::free_balance(
-					CurrencyId::DEXShare(TokenSymbol::AUSD, TokenSymbol::XBTC),
+					CurrencyId::DEXShare(DexShare::Token(TokenSymbol::AUSD), DexShare::Token(TokenSymbol::XBTC)),
 					&AccountId::from(ALICE)
 				),
 				0
@@ -534,12 +567,15 @@ fn test_dex_module() {
 				(10000, 10000000)
 			);
 			assert_eq!(
-				Currencies::total_issuance(CurrencyId::DEXShare(TokenSymbol::AUSD, TokenSymbol::XBTC)),
+				Currencies::total_issuance(CurrencyId::DEXShare(
+					DexShare::Token(TokenSymbol::AUSD),
+					DexShare::Token(TokenSymbol::XBTC)
+				)),
 				10000000
 			);
 			assert_eq!(
 				Currencies::free_balance(
-					CurrencyId::DEXShare(TokenSymbol::AUSD, TokenSymbol::XBTC),
+					CurrencyId::DEXShare(DexShare::Token(TokenSymbol::AUSD), DexShare::Token(TokenSymbol::XBTC)),
 					&AccountId::from(ALICE)
 				),
 				10000000
@@ -560,12 +596,15 @@ fn test_dex_module() {
 				(10001, 10001000)
 			);
 			assert_eq!(
-				Currencies::total_issuance(CurrencyId::DEXShare(TokenSymbol::AUSD, TokenSymbol::XBTC)),
+				Currencies::total_issuance(CurrencyId::DEXShare(
+					DexShare::Token(TokenSymbol::AUSD),
+					DexShare::Token(TokenSymbol::XBTC)
+				)),
 				10001000
 			);
 			assert_eq!(
 				Currencies::free_balance(
-					CurrencyId::DEXShare(TokenSymbol::AUSD, TokenSymbol::XBTC),
+					CurrencyId::DEXShare(DexShare::Token(TokenSymbol::AUSD), DexShare::Token(TokenSymbol::XBTC)),
 					&AccountId::from(BOB)
 				),
 				1000
@@ -589,12 +628,15 @@ fn test_dex_module() {
 				(10001, 10001000)
 			);
 			assert_eq!(
-				Currencies::total_issuance(CurrencyId::DEXShare(TokenSymbol::AUSD, TokenSymbol::XBTC)),
+				Currencies::total_issuance(CurrencyId::DEXShare(
+					DexShare::Token(TokenSymbol::AUSD),
+					DexShare::Token(TokenSymbol::XBTC)
+				)),
 				10001000
 			);
 			assert_eq!(
 				Currencies::free_balance(
-					CurrencyId::DEXShare(TokenSymbol::AUSD, TokenSymbol::XBTC),
+					CurrencyId::DEXShare(DexShare::Token(TokenSymbol::AUSD), DexShare::Token(TokenSymbol::XBTC)),
 					&AccountId::from(BOB)
 				),
 				1000
@@ -631,7 +673,10 @@ fn test_dex_module() {
 			);
 
 			assert_eq!(
-				Currencies::total_issuance(CurrencyId::DEXShare(TokenSymbol::AUSD, TokenSymbol::XBTC)),
+				Currencies::total_issuance(CurrencyId::DEXShare(
+					DexShare::Token(TokenSymbol::AUSD),
+					DexShare::Token(TokenSymbol::XBTC)
+				)),
 				10002998
 			);
 		});
@@ -1203,6 +1248,73 @@ fn test_evm_accounts_module() {
 		});
 }
 
+fn sign_transaction(secret: &secp256k1::SecretKey, mut transaction: EthereumTransaction) -> EthereumTransaction {
+	transaction.v = ChainId::get() * 2 + 35;
+	let message = secp256k1::Message::parse(&transaction.signing_hash().0);
+	let (signature, recovery_id) = secp256k1::sign(&message, secret);
+	let signature = signature.serialize();
+	transaction.r = H256::from_slice(&signature[0..32]);
+	transaction.s = H256::from_slice(&signature[32..64]);
+	transaction.v += recovery_id.serialize() as u64;
+	transaction
+}
+
+#[test]
+fn test_unbind_evm_account_should_not_replay_transactions() {
+	ExtBuilder::default()
+		.balances(vec![(
+			AccountId::from(ALICE),
+			CurrencyId::Token(TokenSymbol::ACA),
+			amount(1000),
+		)])
+		.build()
+		.execute_with(|| {
+			let alice_address = EvmAccounts::eth_address(&alice());
+			assert_ok!(EvmAccounts::claim_account(
+				Origin::signed(AccountId::from(ALICE)),
+				alice_address,
+				EvmAccounts::eth_sign(&alice(), &AccountId::from(ALICE).encode(), &[][..])
+			));
+
+			let transaction = sign_transaction(
+				&alice(),
+				EthereumTransaction {
+					nonce: U256::zero(),
+					gas_price: U256::from(EVM::gas_price()),
+					gas_limit: U256::from(100_000),
+					action: TransactionAction::Call(EvmAccounts::eth_address(&bob())),
+					value: U256::from(amount(10)),
+					input: vec![],
+					v: 0,
+					r: Default::default(),
+					s: Default::default(),
+				},
+			);
+			let validate = |transaction: &EthereumTransaction| {
+				EVM::validate_unsigned(
+					TransactionSource::External,
+					&module_evm::Call::transact(transaction.clone()),
+				)
+			};
+			assert!(validate(&transaction).is_ok());
+			assert_ok!(EVM::transact(Origin::none(), transaction.clone()));
+			assert_eq!(Balances::free_balance(bob_account_id()), amount(10));
+			assert_eq!(validate(&transaction), InvalidTransaction::Stale.into());
+
+			SystemModule::set_block_number(SystemModule::block_number() + EvmMappingCooldown::get());
+			assert_ok!(EvmAccounts::unbind_account(Origin::signed(AccountId::from(ALICE))));
+
+			// the address is mapped to its padded account, the old transaction is still stale
+			// after the padded account is funded
+			let _ = Balances::deposit_creating(&alice_account_id(), amount(1000));
+			assert_eq!(validate(&transaction), InvalidTransaction::Stale.into());
+			assert_eq!(
+				EVM::pre_dispatch(&module_evm::Call::transact(transaction)),
+				Err(InvalidTransaction::Stale.into())
+			);
+		});
+}
+
 #[test]
 fn test_evm_module() {
 	ExtBuilder::default()
@@ -1275,3 +1387,610 @@ fn test_evm_module() {
 			assert_eq!(last_event(), event);
 		});
 }
+
+/// The ERC20 call input, the 4 bytes selector followed by the 32 bytes params.
+fn erc20_input(selector: [u8; 4], addresses: Vec<H160>, value: Balance) -> Vec<u8> {
+	let mut input = selector.to_vec();
+	for address in addresses {
+		input.extend_from_slice(H256::from(address).as_bytes());
+	}
+	input.extend_from_slice(&[0u8; 16]);
+	input.extend_from_slice(&value.to_be_bytes());
+	input
+}
+
+#[test]
+fn test_erc20_mirror() {
+	ExtBuilder::default()
+		.balances(vec![
+			(alice_account_id(), CurrencyId::Token(TokenSymbol::ACA), amount(1000)),
+			(alice_account_id(), CurrencyId::Token(TokenSymbol::AUSD), amount(1000)),
+			(bob_account_id(), CurrencyId::Token(TokenSymbol::ACA), amount(1000)),
+		])
+		.build()
+		.execute_with(|| {
+			let ausd = CurrencyId::Token(TokenSymbol::AUSD);
+			let mirror = primitives::erc20_mirror_address(TokenSymbol::AUSD);
+			let alice_address = EvmAccounts::eth_address(&alice());
+			let bob_address = EvmAccounts::eth_address(&bob());
+
+			// transfer(address,uint256)
+			assert_ok!(EVM::call(
+				Origin::signed(alice_account_id()),
+				mirror,
+				erc20_input([0xa9, 0x05, 0x9c, 0xbb], vec![bob_address], amount(10)),
+				0,
+				1000000000
+			));
+			assert_eq!(last_event(), Event::module_evm(module_evm::RawEvent::Executed(mirror)));
+			assert!(System::events().iter().any(|record| matches!(
+				&record.event,
+				Event::module_evm(module_evm::RawEvent::Log(log)) if log.address == mirror
+			)));
+			assert_eq!(Currencies::free_balance(ausd, &alice_account_id()), amount(990));
+			assert_eq!(Currencies::free_balance(ausd, &bob_account_id()), amount(10));
+
+			// approve(address,uint256)
+			assert_ok!(EVM::call(
+				Origin::signed(alice_account_id()),
+				mirror,
+				erc20_input([0x09, 0x5e, 0xa7, 0xb3], vec![bob_address], amount(100)),
+				0,
+				1000000000
+			));
+			assert_eq!(EVMBridge::allowances(ausd, (alice_address, bob_address)), amount(100));
+
+			// transferFrom(address,address,uint256)
+			assert_ok!(EVM::call(
+				Origin::signed(bob_account_id()),
+				mirror,
+				erc20_input([0x23, 0xb8, 0x72, 0xdd], vec![alice_address, bob_address], amount(60)),
+				0,
+				1000000000
+			));
+			assert_eq!(last_event(), Event::module_evm(module_evm::RawEvent::Executed(mirror)));
+			assert_eq!(Currencies::free_balance(ausd, &alice_account_id()), amount(930));
+			assert_eq!(Currencies::free_balance(ausd, &bob_account_id()), amount(70));
+			assert_eq!(EVMBridge::allowances(ausd, (alice_address, bob_address)), amount(40));
+
+			// exceeds the allowance
+			assert_ok!(EVM::call(
+				Origin::signed(bob_account_id()),
+				mirror,
+				erc20_input([0x23, 0xb8, 0x72, 0xdd], vec![alice_address, bob_address], amount(41)),
+				0,
+				1000000000
+			));
+			assert!(matches!(
+				last_event(),
+				Event::module_evm(module_evm::RawEvent::ExecutedFailed(address, _, _)) if address == mirror
+			));
+			assert_eq!(Currencies::free_balance(ausd, &bob_account_id()), amount(70));
+
+			// the transfers are rejected in the static calls
+			let context = Context {
+				caller: alice_address,
+				address: mirror,
+				apparent_value: Default::default(),
+			};
+			assert_eq!(
+				<Runtime as module_evm::Config>::Precompiles::execute(
+					mirror,
+					&erc20_input([0xa9, 0x05, 0x9c, 0xbb], vec![bob_address], amount(10)),
+					None,
+					&context,
+					true
+				),
+				Some(Err(ExitError::Other("state change in static call".into())))
+			);
+			assert_eq!(Currencies::free_balance(ausd, &bob_account_id()), amount(70));
+		});
+}
+
+#[test]
+fn test_precompile_gas() {
+	ExtBuilder::default()
+		.balances(vec![
+			(alice_account_id(), CurrencyId::Token(TokenSymbol::ACA), amount(1000)),
+			(alice_account_id(), CurrencyId::Token(TokenSymbol::AUSD), amount(1000)),
+		])
+		.build()
+		.execute_with(|| {
+			let ausd = CurrencyId::Token(TokenSymbol::AUSD);
+			let alice_address = EvmAccounts::eth_address(&alice());
+			let bob_address = EvmAccounts::eth_address(&bob());
+
+			// query balance of the MultiCurrency precompile: address mapping, account
+			let context = Context {
+				caller: H160::from_low_u64_be(1),
+				address: H160::from_low_u64_be(PRECOMPILE_ADDRESS_START),
+				apparent_value: Default::default(),
+			};
+			let mut input = [0u8; 96];
+			input[31] = 1;
+			input[32..64].copy_from_slice(&<[u8; 32]>::try_from(ausd).unwrap());
+			input[76..96].copy_from_slice(alice_address.as_bytes());
+			let cost = precompile_base_gas() + 2 * storage_read_gas();
+			let mut balance = [0u8; 32];
+			balance[16..].copy_from_slice(&amount(1000).to_be_bytes());
+			assert_eq!(
+				<Runtime as module_evm::Config>::Precompiles::execute(
+					context.address,
+					&input,
+					Some(cost),
+					&context,
+					false
+				),
+				Some(Ok((ExitSucceed::Returned, balance.to_vec(), cost)))
+			);
+			assert_eq!(
+				<Runtime as module_evm::Config>::Precompiles::execute(
+					context.address,
+					&input,
+					Some(cost - 1),
+					&context,
+					false
+				),
+				Some(Err(ExitError::OutOfGas))
+			);
+
+			// the ERC20 currencies run nested EVM calls, they're rejected
+			let erc20 = CurrencyId::ERC20(H160::from_low_u64_be(1));
+			input[32..64].copy_from_slice(&<[u8; 32]>::try_from(erc20).unwrap());
+			assert_eq!(
+				<Runtime as module_evm::Config>::Precompiles::execute(context.address, &input, None, &context, false),
+				Some(Err(ExitError::Other("ERC20 currency is not supported".into())))
+			);
+
+			// transfer of the ERC20 mirror: 2 address mappings, 2 accounts reads and 2
+			// accounts, log writes
+			let mirror = primitives::erc20_mirror_address(TokenSymbol::AUSD);
+			let input = erc20_input([0xa9, 0x05, 0x9c, 0xbb], vec![bob_address], amount(10));
+			let cost = precompile_base_gas() + 4 * storage_read_gas() + 3 * storage_write_gas();
+
+			assert_ok!(EVM::call(
+				Origin::signed(alice_account_id()),
+				mirror,
+				input.clone(),
+				0,
+				cost as u32 - 1
+			));
+			assert_eq!(
+				last_event(),
+				Event::module_evm(module_evm::RawEvent::ExecutedFailed(
+					mirror,
+					ExitReason::Error(ExitError::OutOfGas),
+					vec![]
+				))
+			);
+			assert_eq!(Currencies::free_balance(ausd, &alice_account_id()), amount(1000));
+
+			// the caller is charged with the cost
+			let post_info = EVM::call(Origin::signed(alice_account_id()), mirror, input, 0, cost as u32).unwrap();
+			assert_eq!(post_info.actual_weight, Some(cost as Weight));
+			assert_eq!(last_event(), Event::module_evm(module_evm::RawEvent::Executed(mirror)));
+			assert_eq!(Currencies::free_balance(ausd, &alice_account_id()), amount(990));
+			assert_eq!(Currencies::free_balance(ausd, &bob_account_id()), amount(10));
+		});
+}
+
+#[test]
+fn test_oracle_precompile() {
+	ExtBuilder::default().build().execute_with(|| {
+		let dot = CurrencyId::Token(TokenSymbol::DOT);
+		let ausd = CurrencyId::Token(TokenSymbol::AUSD);
+		let xbtc = CurrencyId::Token(TokenSymbol::XBTC);
+		let oracle = H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 2);
+		let context = Context {
+			caller: H160::from_low_u64_be(1),
+			address: oracle,
+			apparent_value: Default::default(),
+		};
+
+		Timestamp::set_timestamp(1000);
+		assert_ok!(set_oracle_price(vec![(dot, Price::saturating_from_rational(100, 1))]));
+
+		let expected_output = |price: Balance, timestamp: u64| -> Vec<u8> {
+			let mut output = [0u8; 64];
+			output[16..32].copy_from_slice(&price.to_be_bytes());
+			output[56..64].copy_from_slice(&timestamp.to_be_bytes());
+			output.to_vec()
+		};
+
+		// get price: action, currency id
+		let mut input = [0u8; 64];
+		input[32..64].copy_from_slice(&<[u8; 32]>::try_from(dot).unwrap());
+		let cost = precompile_base_gas() + 3 * storage_read_gas();
+		assert_eq!(
+			<Runtime as module_evm::Config>::Precompiles::execute(oracle, &input, None, &context, false),
+			Some(Ok((ExitSucceed::Returned, expected_output(amount(100), 1000), cost)))
+		);
+		assert_eq!(
+			<Runtime as module_evm::Config>::Precompiles::execute(oracle, &input, Some(cost - 1), &context, false),
+			Some(Err(ExitError::OutOfGas))
+		);
+
+		// the stable currency has a fixed price without timestamp
+		input[32..64].copy_from_slice(&<[u8; 32]>::try_from(ausd).unwrap());
+		assert_eq!(
+			<Runtime as module_evm::Config>::Precompiles::execute(oracle, &input, None, &context, false),
+			Some(Ok((ExitSucceed::Returned, expected_output(amount(1), 0), cost)))
+		);
+
+		// no price
+		input[32..64].copy_from_slice(&<[u8; 32]>::try_from(xbtc).unwrap());
+		assert_eq!(
+			<Runtime as module_evm::Config>::Precompiles::execute(oracle, &input, None, &context, false),
+			Some(Ok((ExitSucceed::Returned, expected_output(0, 0), cost)))
+		);
+
+		// get relative price: action, base currency id, quote currency id
+		let mut input = [0u8; 96];
+		input[31] = 1;
+		input[32..64].copy_from_slice(&<[u8; 32]>::try_from(dot).unwrap());
+		input[64..96].copy_from_slice(&<[u8; 32]>::try_from(ausd).unwrap());
+		let cost = precompile_base_gas() + 6 * storage_read_gas();
+		assert_eq!(
+			<Runtime as module_evm::Config>::Precompiles::execute(oracle, &input, None, &context, false),
+			Some(Ok((ExitSucceed::Returned, expected_output(amount(100), 1000), cost)))
+		);
+
+		// only the system contracts can call it
+		let mut non_system = [0u8; 20];
+		non_system[0] = 1;
+		let non_system_caller_context = Context {
+			caller: non_system.into(),
+			..context
+		};
+		assert_eq!(
+			<Runtime as module_evm::Config>::Precompiles::execute(
+				oracle,
+				&input,
+				None,
+				&non_system_caller_context,
+				false
+			),
+			Some(Err(ExitError::Other("no permission".into())))
+		);
+	});
+}
+
+#[test]
+fn test_honzon_precompile() {
+	ExtBuilder::default()
+		.balances(vec![
+			(alice_account_id(), CurrencyId::Token(TokenSymbol::ACA), amount(1000)),
+			(alice_account_id(), CurrencyId::Token(TokenSymbol::XBTC), amount(1000)),
+			(bob_account_id(), CurrencyId::Token(TokenSymbol::ACA), amount(1000)),
+		])
+		.build()
+		.execute_with(|| {
+			let xbtc = CurrencyId::Token(TokenSymbol::XBTC);
+			let honzon = H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 3);
+			let alice_address = EvmAccounts::eth_address(&alice());
+
+			assert_ok!(set_oracle_price(vec![(xbtc, Price::saturating_from_rational(1, 1))]));
+			assert_ok!(CdpEngineModule::set_collateral_params(
+				<Runtime as frame_system::Config>::Origin::root(),
+				xbtc,
+				Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+				Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+				Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+				Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+				Change::NewValue(amount(10000)),
+			));
+
+			// the input of the Honzon precompile: action, currency id and the rest params
+			let honzon_input = |action: u8, params: Vec<[u8; 32]>| -> Vec<u8> {
+				let mut input = [0u8; 32].to_vec();
+				input[31] = action;
+				input.extend_from_slice(&<[u8; 32]>::try_from(xbtc).unwrap());
+				for param in params {
+					input.extend_from_slice(&param);
+				}
+				input
+			};
+			let u128_param = |value: u128| -> [u8; 32] {
+				let mut param = [0u8; 32];
+				param[16..].copy_from_slice(&value.to_be_bytes());
+				param
+			};
+			let address_param = |address: H160| -> [u8; 32] { H256::from(address).to_fixed_bytes() };
+
+			// the precompile can't be called by `DELEGATECALL`
+			let delegate_call = deploy_delegate_call_contract(alice_account_id(), honzon).unwrap();
+			assert_ok!(EVM::call(
+				Origin::signed(alice_account_id()),
+				delegate_call,
+				honzon_input(0, vec![u128_param(amount(100)), u128_param(amount(500))]),
+				0,
+				2_000_000_000
+			));
+			assert!(matches!(
+				last_event(),
+				Event::module_evm(module_evm::RawEvent::ExecutedFailed(address, ExitReason::Revert(_), _))
+					if address == delegate_call
+			));
+			assert_eq!(LoansModule::positions(xbtc, alice_account_id()).collateral, 0);
+
+			// the loans can't be changed in the static calls
+			let context = Context {
+				caller: alice_address,
+				address: honzon,
+				apparent_value: Default::default(),
+			};
+			assert_eq!(
+				<Runtime as module_evm::Config>::Precompiles::execute(
+					honzon,
+					&honzon_input(0, vec![u128_param(amount(100)), u128_param(amount(500))]),
+					None,
+					&context,
+					true
+				),
+				Some(Err(ExitError::Other("state change in static call".into())))
+			);
+
+			// adjust the loan of the caller
+			assert_ok!(EVM::call(
+				Origin::signed(alice_account_id()),
+				honzon,
+				honzon_input(0, vec![u128_param(amount(100)), u128_param(amount(500))]),
+				0,
+				2_000_000_000
+			));
+			assert_eq!(last_event(), Event::module_evm(module_evm::RawEvent::Executed(honzon)));
+			assert_eq!(LoansModule::positions(xbtc, alice_account_id()).collateral, amount(100));
+			assert_eq!(LoansModule::positions(xbtc, alice_account_id()).debit, amount(500));
+			assert_eq!(
+				Currencies::free_balance(CurrencyId::Token(TokenSymbol::AUSD), &alice_account_id()),
+				amount(50)
+			);
+
+			// query the position and the collateral ratio
+			let context = Context {
+				caller: alice_address,
+				address: honzon,
+				apparent_value: Default::default(),
+			};
+			let mut position = u128_param(amount(100)).to_vec();
+			position.extend_from_slice(&u128_param(amount(500)));
+			assert_eq!(
+				<Runtime as module_evm::Config>::Precompiles::execute(
+					honzon,
+					&honzon_input(4, vec![address_param(alice_address)]),
+					None,
+					&context,
+					false
+				),
+				Some(Ok((
+					ExitSucceed::Returned,
+					position,
+					precompile_base_gas() + 2 * storage_read_gas()
+				)))
+			);
+			assert_eq!(
+				<Runtime as module_evm::Config>::Precompiles::execute(
+					honzon,
+					&honzon_input(5, vec![address_param(alice_address)]),
+					None,
+					&context,
+					false
+				),
+				Some(Ok((
+					ExitSucceed::Returned,
+					u128_param(amount(2)).to_vec(),
+					precompile_base_gas() + 5 * storage_read_gas()
+				)))
+			);
+
+			// bob can't take the loan without the authorization
+			assert_ok!(EVM::call(
+				Origin::signed(bob_account_id()),
+				honzon,
+				honzon_input(1, vec![address_param(alice_address)]),
+				0,
+				2_000_000_000
+			));
+			assert!(matches!(
+				last_event(),
+				Event::module_evm(module_evm::RawEvent::ExecutedFailed(address, _, _)) if address == honzon
+			));
+
+			assert_ok!(EVM::call(
+				Origin::signed(alice_account_id()),
+				honzon,
+				honzon_input(2, vec![address_param(EvmAccounts::eth_address(&bob()))]),
+				0,
+				2_000_000_000
+			));
+			assert_ok!(EVM::call(
+				Origin::signed(bob_account_id()),
+				honzon,
+				honzon_input(1, vec![address_param(alice_address)]),
+				0,
+				2_000_000_000
+			));
+			assert_eq!(last_event(), Event::module_evm(module_evm::RawEvent::Executed(honzon)));
+			assert_eq!(LoansModule::positions(xbtc, alice_account_id()).collateral, 0);
+			assert_eq!(LoansModule::positions(xbtc, bob_account_id()).collateral, amount(100));
+			assert_eq!(LoansModule::positions(xbtc, bob_account_id()).debit, amount(500));
+		});
+}
+
+#[test]
+fn test_schedule_call_precompile() {
+	ExtBuilder::default()
+		.balances(vec![
+			(alice_account_id(), CurrencyId::Token(TokenSymbol::ACA), amount(1000)),
+			(bob_account_id(), CurrencyId::Token(TokenSymbol::ACA), amount(1000)),
+		])
+		.build()
+		.execute_with(|| {
+			let schedule_call = H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 4);
+			let alice_address = EvmAccounts::eth_address(&alice());
+			let bob_address = EvmAccounts::eth_address(&bob());
+			let gas_limit: u32 = 100_000;
+			let reserved_fee = EVM::gas_price() * Balance::from(gas_limit);
+
+			// the input of the ScheduleCall precompile: action and the rest params
+			let schedule_input = |action: u8, params: Vec<[u8; 32]>| -> Vec<u8> {
+				let mut input = [0u8; 32].to_vec();
+				input[31] = action;
+				for param in params {
+					input.extend_from_slice(&param);
+				}
+				input
+			};
+			let u128_param = |value: u128| -> [u8; 32] {
+				let mut param = [0u8; 32];
+				param[16..].copy_from_slice(&value.to_be_bytes());
+				param
+			};
+			let address_param = |address: H160| -> [u8; 32] { H256::from(address).to_fixed_bytes() };
+			let schedule_transfer_input = schedule_input(
+				0,
+				vec![
+					address_param(bob_address),
+					u128_param(amount(10)),
+					u128_param(gas_limit.into()),
+					u128_param(2),
+				],
+			);
+			let has_event = |event: module_evm::Event<Runtime>| {
+				SystemModule::events()
+					.iter()
+					.any(|r| r.event == Event::module_evm(event.clone()))
+			};
+
+			// schedule a transfer to bob, the fee is reserved
+			assert_ok!(EVM::call(
+				Origin::signed(alice_account_id()),
+				schedule_call,
+				schedule_transfer_input.clone(),
+				0,
+				1000000000
+			));
+			assert_eq!(
+				last_event(),
+				Event::module_evm(module_evm::RawEvent::Executed(schedule_call))
+			);
+			assert!(has_event(module_evm::RawEvent::CallScheduled(alice_address, 0)));
+			assert_eq!(
+				EVM::scheduled_calls(0).map(|call| (call.source, call.target, call.reserved_fee)),
+				Some((alice_address, bob_address, reserved_fee))
+			);
+			assert_eq!(Balances::reserved_balance(alice_account_id()), reserved_fee);
+
+			let alice_balance = Balances::free_balance(alice_account_id());
+			run_to_block(SystemModule::block_number() + 1);
+			assert_eq!(Balances::free_balance(bob_account_id()), amount(1000));
+
+			// dispatched after the delay, the unused fee is refunded
+			run_to_block(SystemModule::block_number() + 10);
+			assert!(has_event(module_evm::RawEvent::ScheduledCallDispatched(
+				alice_address,
+				0
+			)));
+			assert_eq!(EVM::scheduled_calls(0), None);
+			assert_eq!(Balances::free_balance(bob_account_id()), amount(1010));
+			assert_eq!(Balances::reserved_balance(alice_account_id()), 0);
+			let alice_paid = alice_balance - Balances::free_balance(alice_account_id());
+			assert!(alice_paid >= amount(10) && alice_paid < amount(10) + reserved_fee);
+
+			// schedule another transfer and cancel it
+			assert_ok!(EVM::call(
+				Origin::signed(alice_account_id()),
+				schedule_call,
+				schedule_transfer_input.clone(),
+				0,
+				1000000000
+			));
+			assert_eq!(
+				EVM::scheduled_calls(1).map(|call| (call.source, call.target, call.reserved_fee)),
+				Some((alice_address, bob_address, reserved_fee))
+			);
+
+			// only the contract scheduling the call can cancel it
+			assert_ok!(EVM::call(
+				Origin::signed(bob_account_id()),
+				schedule_call,
+				schedule_input(1, vec![u128_param(1)]),
+				0,
+				1000000000
+			));
+			assert!(matches!(
+				last_event(),
+				Event::module_evm(module_evm::RawEvent::ExecutedFailed(address, _, _)) if address == schedule_call
+			));
+			assert_eq!(
+				EVM::scheduled_calls(1).map(|call| (call.source, call.target, call.reserved_fee)),
+				Some((alice_address, bob_address, reserved_fee))
+			);
+
+			assert_ok!(EVM::call(
+				Origin::signed(alice_account_id()),
+				schedule_call,
+				schedule_input(1, vec![u128_param(1)]),
+				0,
+				1000000000
+			));
+			assert!(has_event(module_evm::RawEvent::ScheduledCallCanceled(alice_address, 1)));
+			assert_eq!(EVM::scheduled_calls(1), None);
+			assert_eq!(Balances::reserved_balance(alice_account_id()), 0);
+
+			run_to_block(SystemModule::block_number() + 10);
+			assert_eq!(Balances::free_balance(bob_account_id()), amount(1010));
+
+			// the precompile can't be called by `DELEGATECALL`
+			let delegate_call = deploy_delegate_call_contract(alice_account_id(), schedule_call).unwrap();
+			assert_ok!(EVM::call(
+				Origin::signed(alice_account_id()),
+				delegate_call,
+				schedule_transfer_input.clone(),
+				0,
+				1000000000
+			));
+			assert!(matches!(
+				last_event(),
+				Event::module_evm(module_evm::RawEvent::ExecutedFailed(address, ExitReason::Revert(_), _))
+					if address == delegate_call
+			));
+			assert_eq!(EVM::scheduled_calls(2), None);
+
+			// nothing can be scheduled in the static calls
+			let context = Context {
+				caller: alice_address,
+				address: schedule_call,
+				apparent_value: Default::default(),
+			};
+			assert_eq!(
+				<Runtime as module_evm::Config>::Precompiles::execute(
+					schedule_call,
+					&schedule_transfer_input,
+					None,
+					&context,
+					true
+				),
+				Some(Err(ExitError::Other("state change in static call".into())))
+			);
+		});
+}
+
+#[test]
+fn test_install_erc20_mirror_code_on_runtime_upgrade() {
+	ExtBuilder::default().build().execute_with(|| {
+		let aca_mirror = primitives::erc20_mirror_address(TokenSymbol::ACA);
+		let ausd_mirror = primitives::erc20_mirror_address(TokenSymbol::AUSD);
+		assert_eq!(EVM::code_at_address(&aca_mirror), Vec::<u8>::new());
+
+		InstallErc20MirrorCode::<Runtime>::on_runtime_upgrade();
+		assert_eq!(EVM::code_at_address(&aca_mirror), ERC20_MIRROR_CODE.to_vec());
+		assert_eq!(EVM::code_at_address(&ausd_mirror), ERC20_MIRROR_CODE.to_vec());
+		let code_hash = EVM::code_hash_at_address(&aca_mirror);
+		let ref_count = EVM::code_infos(code_hash).unwrap().ref_count;
+
+		// the installed code isn't installed again
+		InstallErc20MirrorCode::<Runtime>::on_runtime_upgrade();
+		assert_eq!(EVM::code_infos(code_hash).unwrap().ref_count, ref_count);
+	});
+}